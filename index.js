@@ -1,28 +1,61 @@
 #!/usr/bin/env node
 
 const ffi = require('ffi-napi');
+const Module = require('module');
 const path = require('path');
 
 var lib = ffi.Library(path.join(__dirname, './target/release/libffi'), {
   require: ['string', ['string']],
+  transpile: ['string', ['string', 'string']],
+  resolve: ['string', ['string', 'string']],
 });
-require.extensions['.ts'] = require.extensions['.js'];
 
-const originalRequire = require;
-require('module').prototype.require = function (module) {
-  const path = require.resolve(module);
-
-  if (path.endsWith('.js')) {
-    return originalRequire(path);
+// Compiler options are given like `tsc` takes them, e.g. `--module umd`.
+function parseArguments(args) {
+  const options = {};
+  const files = [];
+  for (let index = 0; index < args.length; index++) {
+    const match = args[index].match(/^--(\w+)(?:=(.*))?$/);
+    if (!match) {
+      files.push(args[index]);
+      continue;
+    }
+    const [, name, inline] = match;
+    const value = inline !== undefined ? inline : args[++index];
+    options[name] = value === 'true' ? true : value === 'false' ? false : value;
+  }
+  return { options, files };
+}
+
+const { options, files } = parseArguments(process.argv.slice(2));
+
+const filePath = path.isAbsolute(files[0])
+  ? files[0]
+  : path.join(__dirname, files[0]);
+
+// With `--module` the file is transpiled to that module format and printed
+// rather than run.
+if (options.module) {
+  process.stdout.write(lib.transpile(filePath, JSON.stringify(options)) + '\n');
+} else {
+  // Every TypeScript module is transpiled to CommonJS and run as its own
+  // module, with its own `require`, `exports` and `__filename`.
+  for (const extension of ['.ts', '.tsx', '.mts', '.cts']) {
+    require.extensions[extension] = function (module, filename) {
+      module._compile(lib.require(filename), filename);
+    };
   }
 
-  const str = lib.require(path);
-
-  return eval(str);
-};
-
-const filePath = path.isAbsolute(process.argv[2])
-  ? process.argv[2]
-  : path.join(__dirname, process.argv[2]);
-
-require(filePath);
+  // `./a.js` is `./a.ts` and `index.ts` is the entry point of a directory,
+  // so modules are found by the library first and by Node when it can't.
+  const resolveFilename = Module._resolveFilename;
+  Module._resolveFilename = function (request, parent, ...rest) {
+    const isBuiltin =
+      request.startsWith('node:') || Module.builtinModules.includes(request);
+    const resolved =
+      !isBuiltin && parent && parent.filename && lib.resolve(request, parent.filename);
+    return resolved || resolveFilename.call(this, request, parent, ...rest);
+  };
+
+  require(filePath);
+}
@@ -5,11 +5,46 @@ const path = require('path');
 
 const lib = ffi.Library(path.join(__dirname, './target/release/libffi'), {
   require: ['string', ['string']],
+  transpile: ['string', ['string', 'string']],
+  resolve: ['string', ['string', 'string']],
 });
 
-glob.sync('./test_fixtures/**/*.in.ts').forEach((file) => {
-  it(file.replace(/(test_fixtures\/|in.ts)/, ''), async () => {
+// Compiler options for a fixture are set with `// @name: value` comments at
+// its top, the way the TypeScript test suite does it.
+function fixtureOptions(source) {
+  const options = {};
+  for (const line of source.split('\n')) {
+    const match = line.match(/^\s*\/\/\s*@(\w+):\s*(.*?)\s*$/);
+    if (!match) {
+      break;
+    }
+    const [, name, value] = match;
+    options[name] = value === 'true' ? true : value === 'false' ? false : value;
+  }
+  return options;
+}
+
+glob.sync('./test_fixtures/**/*.in.{ts,tsx}').forEach((file) => {
+  it(file.replace(/(test_fixtures\/|in.tsx?)/, ''), async () => {
     const output = await fs.readFile(file.replace(/in\..*/, 'out.js'), 'utf8');
-    expect(lib.require(path.join(__dirname, file))).toBe(output.trim());
+    const options = fixtureOptions(await fs.readFile(file, 'utf8'));
+    expect(lib.transpile(path.join(__dirname, file), JSON.stringify(options))).toBe(
+      output.trim(),
+    );
   });
 });
+
+// `resolutions.json` maps what the `index.ts` next to it imports to the file
+// the loader runs for it, or `null` when it is left to Node.
+glob.sync('./test_fixtures/**/resolutions.json').forEach((file) => {
+  const directory = path.join(__dirname, path.dirname(file));
+  const importer = path.join(directory, 'index.ts');
+  const resolutions = require(path.join(__dirname, file));
+  for (const [specifier, expected] of Object.entries(resolutions)) {
+    it(`${path.relative('test_fixtures', path.dirname(file))} resolves ${specifier}`, () => {
+      expect(lib.resolve(specifier, importer)).toBe(
+        expected === null ? null : path.join(directory, expected),
+      );
+    });
+  }
+});
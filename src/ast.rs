@@ -17,29 +17,83 @@ pub enum NodeASTType {
     OrStatement,
     AssignmentOperator,
     KeywordAs,
+    KeywordSatisfies,
+    NonNullAssertion,
+    TypeAssertion,
     KeywordFunction,
+    FunctionSignature,
+    ThisParameter,
     KeywordDeclare,
     KeywordInterface,
     KeywordType,
+    KeywordClass,
+    Decorator,
+    DecoratedClassExport,
+    ClassDeclaration,
+    KeywordEnum,
+    EnumDeclaration,
+    EnumOpeningBracket,
+    EnumClosingBracket,
+    EnumMember,
+    EnumMemberInitializer,
+    EnumMemberSeperator,
+    EnumMemberReference,
+    KeywordImplements,
+    KeywordModifier,
+    ClassOpeningBracket,
+    ClassClosingBracket,
+    ClassMemberName,
+    ClassMemberSignature,
+    OptionalMarker,
+    TypeParameters,
+    TypeArguments,
     FunctionDeclaration,
     FunctionIdentifier,
     FunctionIdentifierTypeSeperator,
     FunctionIdentifierType,
     FunctionOpeningParenthesis,
     FunctionClosingParenthesis,
+    FunctionReturnTypeSeperator,
+    DefiniteAssignmentAssertion,
     OpeningParenthesis,
     ClosingParenthesis,
     OpeningBracket,
     ClosingBracket,
+    ObjectOpeningBracket,
+    ObjectClosingBracket,
+    ObjectPropertySeperator,
+    TernaryOperator,
+    TernaryElseSeperator,
+    KeywordCase,
+    CaseSeperator,
+    LabelSeperator,
+    ArrowFunction,
     OpeningBrace,
     ClosingBrace,
     Identifier,
     ExportDeclaration,
+    ExportList,
+    TypeOnlyExport,
+    KeywordNamespace,
+    TypeOnlyNamespace,
+    NamespaceDeclaration,
+    NamespaceNameSeperator,
+    NamespaceOpeningBracket,
+    NamespaceClosingBracket,
+    NamespaceExport,
+    NamespaceMemberReference,
+    ConstEnumDeclaration,
+    ConstEnumReference,
+    ImportDeclaration,
+    ImportBindingReference,
+    CommonJsExport,
+    ExportAll,
+    ImportEquals,
+    ExportAssignment,
+    DynamicImport,
+    ImportMeta,
     WhiteSpace,
     Terminator,
-    CommentLine,
-    CommentMultilineOpener,
-    CommentMultilineCloser,
 }
 
 impl fmt::Display for NodeASTType {
@@ -63,19 +117,76 @@ impl fmt::Display for NodeASTType {
                 NodeASTType::TypeUnionSeperator => "TypeUnionSeperator",
                 NodeASTType::OrStatement => "OrStatement",
                 NodeASTType::KeywordFunction => "KeywordFunction",
+                NodeASTType::FunctionSignature => "FunctionSignature",
+                NodeASTType::ThisParameter => "ThisParameter",
                 NodeASTType::KeywordAs => "KeywordAs",
+                NodeASTType::KeywordSatisfies => "KeywordSatisfies",
+                NodeASTType::NonNullAssertion => "NonNullAssertion",
+                NodeASTType::TypeAssertion => "TypeAssertion",
                 NodeASTType::KeywordDeclare => "KeywordDeclare",
                 NodeASTType::KeywordType => "KeywordType",
                 NodeASTType::KeywordInterface => "KeywordInterface",
+                NodeASTType::KeywordClass => "KeywordClass",
+                NodeASTType::Decorator => "Decorator",
+                NodeASTType::DecoratedClassExport => "DecoratedClassExport",
+                NodeASTType::ClassDeclaration => "ClassDeclaration",
+                NodeASTType::KeywordImplements => "KeywordImplements",
+                NodeASTType::KeywordModifier => "KeywordModifier",
+                NodeASTType::ClassOpeningBracket => "ClassOpeningBracket",
+                NodeASTType::ClassClosingBracket => "ClassClosingBracket",
+                NodeASTType::ClassMemberName => "ClassMemberName",
+                NodeASTType::ClassMemberSignature => "ClassMemberSignature",
+                NodeASTType::OptionalMarker => "OptionalMarker",
+                NodeASTType::TypeParameters => "TypeParameters",
+                NodeASTType::TypeArguments => "TypeArguments",
                 NodeASTType::ExportDeclaration => "ExportDeclaration",
+                NodeASTType::ExportList => "ExportList",
+                NodeASTType::TypeOnlyExport => "TypeOnlyExport",
+                NodeASTType::KeywordNamespace => "KeywordNamespace",
+                NodeASTType::TypeOnlyNamespace => "TypeOnlyNamespace",
+                NodeASTType::NamespaceDeclaration => "NamespaceDeclaration",
+                NodeASTType::NamespaceNameSeperator => "NamespaceNameSeperator",
+                NodeASTType::NamespaceOpeningBracket => "NamespaceOpeningBracket",
+                NodeASTType::NamespaceClosingBracket => "NamespaceClosingBracket",
+                NodeASTType::NamespaceExport => "NamespaceExport",
+                NodeASTType::NamespaceMemberReference => "NamespaceMemberReference",
+                NodeASTType::ConstEnumDeclaration => "ConstEnumDeclaration",
+                NodeASTType::ConstEnumReference => "ConstEnumReference",
+                NodeASTType::ImportDeclaration => "ImportDeclaration",
+                NodeASTType::ImportBindingReference => "ImportBindingReference",
+                NodeASTType::CommonJsExport => "CommonJsExport",
+                NodeASTType::ExportAll => "ExportAll",
+                NodeASTType::ImportEquals => "ImportEquals",
+                NodeASTType::ExportAssignment => "ExportAssignment",
+                NodeASTType::DynamicImport => "DynamicImport",
+                NodeASTType::ImportMeta => "ImportMeta",
+                NodeASTType::KeywordEnum => "KeywordEnum",
+                NodeASTType::EnumDeclaration => "EnumDeclaration",
+                NodeASTType::EnumOpeningBracket => "EnumOpeningBracket",
+                NodeASTType::EnumClosingBracket => "EnumClosingBracket",
+                NodeASTType::EnumMember => "EnumMember",
+                NodeASTType::EnumMemberInitializer => "EnumMemberInitializer",
+                NodeASTType::EnumMemberSeperator => "EnumMemberSeperator",
+                NodeASTType::EnumMemberReference => "EnumMemberReference",
                 NodeASTType::FunctionDeclaration => "FunctionDeclaration",
                 NodeASTType::FunctionIdentifier => "FunctionIdentifier",
                 NodeASTType::FunctionIdentifierTypeSeperator => "FunctionIdentifierTypeSeperator",
                 NodeASTType::FunctionIdentifierType => "FunctionIdentifierType",
                 NodeASTType::FunctionOpeningParenthesis => "FunctionOpeningParenthesis",
                 NodeASTType::FunctionClosingParenthesis => "FunctionClosingParenthesis",
+                NodeASTType::FunctionReturnTypeSeperator => "FunctionReturnTypeSeperator",
+                NodeASTType::DefiniteAssignmentAssertion => "DefiniteAssignmentAssertion",
                 NodeASTType::OpeningBracket => "OpeningBracket",
                 NodeASTType::ClosingBracket => "ClosingBracket",
+                NodeASTType::ObjectOpeningBracket => "ObjectOpeningBracket",
+                NodeASTType::ObjectClosingBracket => "ObjectClosingBracket",
+                NodeASTType::ObjectPropertySeperator => "ObjectPropertySeperator",
+                NodeASTType::TernaryOperator => "TernaryOperator",
+                NodeASTType::TernaryElseSeperator => "TernaryElseSeperator",
+                NodeASTType::KeywordCase => "KeywordCase",
+                NodeASTType::CaseSeperator => "CaseSeperator",
+                NodeASTType::LabelSeperator => "LabelSeperator",
+                NodeASTType::ArrowFunction => "ArrowFunction",
                 NodeASTType::OpeningBrace => "OpeningBrace",
                 NodeASTType::ClosingBrace => "ClosingBrace",
                 NodeASTType::OpeningParenthesis => "OpeningParenthesis",
@@ -83,9 +194,6 @@ impl fmt::Display for NodeASTType {
                 NodeASTType::Identifier => "Identifier",
                 NodeASTType::WhiteSpace => "WhiteSpace",
                 NodeASTType::Terminator => "Terminator",
-                NodeASTType::CommentLine => "CommentLine",
-                NodeASTType::CommentMultilineOpener => "CommentMultilineOpener",
-                NodeASTType::CommentMultilineCloser => "CommentMultilineCloser",
             }
         )
     }
@@ -0,0 +1,340 @@
+use super::import_export::{is_json, ExportAll, ExportList, ImportDeclaration};
+use super::lookahead::{
+    is_identifier, is_opening, matching_close, next_index, next_token, type_end,
+};
+
+/// A declaration after `export` or `export default`, which is written without
+/// them and exported by name.
+pub struct ExportedDeclaration {
+    pub names: Vec<String>,
+    // For `export default function() {}`, the index of the token the name
+    // `default_1` is written after.
+    pub anonymous: Option<usize>,
+}
+
+/// The name of the variable a module is required into, `foo_1` for `./foo`
+/// unless that `is_used` already.
+pub fn module_variable(source: &str, is_used: impl Fn(&str) -> bool) -> String {
+    let path = source.trim_matches(|char| char == '\'' || char == '"');
+    let base = path.rsplit('/').next().unwrap_or(path);
+    let mut name: String = base
+        .chars()
+        .map(
+            |char| match char.is_alphanumeric() || char == '_' || char == '$' {
+                true => char,
+                false => '_',
+            },
+        )
+        .collect();
+    if !is_identifier(&name) {
+        name.insert(0, '_');
+    }
+    let mut count = 1;
+    let mut variable = format!("{}_{}", name, count);
+    while is_used(&variable) {
+        count += 1;
+        variable = format!("{}_{}", name, count);
+    }
+    variable
+}
+
+/// The global variable a module is guessed to be when it isn't required,
+/// `lodashEs` for `lodash-es`.
+pub fn global_name(module: &str) -> String {
+    let base = module.rsplit('/').next().unwrap_or(module);
+    let base = base.split('.').next().unwrap_or(base);
+    let mut name = String::new();
+    let mut is_word_start = false;
+    for char in base.chars() {
+        match char.is_alphanumeric() || char == '_' || char == '$' {
+            true if is_word_start && !name.is_empty() => name.extend(char.to_uppercase()),
+            true => name.push(char),
+            false => {}
+        }
+        is_word_start = !(char.is_alphanumeric() || char == '_' || char == '$');
+    }
+    if !is_identifier(&name) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// `mod.name`, or `mod["a-b"]` for a name that is a string.
+pub fn member(variable: &str, name: &str) -> String {
+    match is_identifier(name) {
+        true => format!("{}.{}", variable, name),
+        false => format!("{}[{}]", variable, name),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    match name.starts_with(['\'', '"']) {
+        true => name.to_string(),
+        false => format!("\"{}\"", name),
+    }
+}
+
+/// Exports `name` with a getter, so that importers see every assignment to
+/// the binding behind `expression`.
+pub fn export_getter(name: &str, expression: &str) -> String {
+    format!(
+        "Object.defineProperty(exports,{},{{enumerable:true,get:function(){{return {};}}}});",
+        property_name(name),
+        expression
+    )
+}
+
+/// The `require` an import is written as, with the interop helper it needs.
+/// Its bindings are referenced as members of `variable`, see
+/// `Tree::collect_import_bindings`. The default of a JSON module is the
+/// parsed JSON itself, which needs no helper.
+pub fn import_statement(
+    declaration: &ImportDeclaration,
+    variable: &str,
+) -> (String, Option<&'static str>) {
+    let require = format!("require({})", declaration.source);
+    if declaration.is_side_effect_only {
+        return (format!("{};", require), None);
+    }
+    let named = declaration
+        .named
+        .iter()
+        .flatten()
+        .any(|specifier| !specifier.is_type_only);
+    let helper = match (
+        &declaration.namespace_binding,
+        &declaration.default_binding,
+        named,
+    ) {
+        (None, None, false) => return (String::new(), None),
+        (Some(_), _, _) | (None, Some(_), true) => Some("__importStar"),
+        (None, Some(_), false) if is_json(&declaration.attributes) => None,
+        (None, Some(_), false) => Some("__importDefault"),
+        (None, None, true) => None,
+    };
+    let value = match helper {
+        Some(helper) => format!("{}({})", helper, require),
+        None => require,
+    };
+    (format!("const {}={};", variable, value), helper)
+}
+
+/// `export { a as b } from './a'`, which reads its bindings from the module
+/// whenever they are used.
+pub fn reexport_statement(list: &ExportList, variable: &str) -> (String, Option<&'static str>) {
+    let source = list.source.as_ref().expect("Only called for re-exports");
+    let specifiers: Vec<_> = list
+        .specifiers
+        .iter()
+        .filter(|specifier| !specifier.is_type_only)
+        .collect();
+    if list.is_type_only || specifiers.is_empty() {
+        return (String::new(), None);
+    }
+    let mut helper = None;
+    let mut statement = format!("const {}=require({});", variable, source);
+    for specifier in specifiers {
+        let expression = match specifier.name.as_str() {
+            "default" if is_json(&list.attributes) => variable.to_string(),
+            "default" => {
+                helper = Some("__importDefault");
+                format!("__importDefault({}).default", variable)
+            }
+            name => member(variable, name),
+        };
+        statement += &export_getter(&specifier.alias, &expression);
+    }
+    (statement, helper)
+}
+
+/// `export * from './a'` and `export * as a from './a'`, without the `;`.
+pub fn export_all_statement(export: &ExportAll) -> (String, &'static str) {
+    let require = format!("require({})", export.source);
+    match &export.namespace {
+        Some(namespace) => (
+            format!("{}=__importStar({})", member("exports", namespace), require),
+            "__importStar",
+        ),
+        None => (format!("__exportStar({},exports)", require), "__exportStar"),
+    }
+}
+
+/// Reads the declaration starting at `position`. Returns `None` when it is
+/// an expression, as in `export default a + b`.
+pub fn exported_declaration(tokens: &[String], position: usize) -> Option<ExportedDeclaration> {
+    let mut index = next_index(tokens, position);
+    if matches!(tokens.get(index)?.as_str(), "async" | "abstract")
+        && matches!(next_token(tokens, index + 1), "function" | "class")
+    {
+        index = next_index(tokens, index + 1);
+    }
+    let mut anonymous = index;
+    let mut name = next_index(tokens, index + 1);
+    let names = match tokens[index].as_str() {
+        "function" => {
+            if next_token(tokens, name) == "*" {
+                anonymous = name;
+                name = next_index(tokens, name + 1);
+            }
+            vec![tokens.get(name)?.clone()]
+        }
+        "class" => vec![tokens.get(name)?.clone()],
+        "enum" | "namespace" | "module" => {
+            return Some(ExportedDeclaration {
+                names: vec![tokens.get(name)?.clone()],
+                anonymous: None,
+            })
+        }
+        "const" | "let" | "var" => {
+            return Some(ExportedDeclaration {
+                names: declared_names(tokens, index + 1),
+                anonymous: None,
+            })
+        }
+        _ => return None,
+    };
+    let is_named =
+        is_identifier(&names[0]) && !matches!(names[0].as_str(), "extends" | "implements");
+    Some(match is_named {
+        true => ExportedDeclaration {
+            names,
+            anonymous: None,
+        },
+        false => ExportedDeclaration {
+            names: vec![String::from("default_1")],
+            anonymous: Some(anonymous),
+        },
+    })
+}
+
+/// A declarator of a `const`, `let` or `var`: `a = 1` or `{ b } = c`.
+pub struct Declarator {
+    // The name or the `{` or `[` of the pattern.
+    pub start: usize,
+    // The index past it, at the `,` or `;` that may follow.
+    pub end: usize,
+    // The names it binds, including the ones in destructuring patterns.
+    pub names: Vec<String>,
+}
+
+/// The declarators of a `const`, `let` or `var` starting at `index`.
+pub fn declarators(tokens: &[String], mut index: usize) -> Vec<Declarator> {
+    let mut declarators = vec![];
+    loop {
+        index = next_index(tokens, index);
+        let start = index;
+        let mut names = vec![];
+        let Some(token) = tokens.get(index) else {
+            break;
+        };
+        if token == "{" || token == "[" {
+            pattern_names(tokens, index, &mut names);
+            index = matching_close(tokens, index) + 1;
+        } else if is_identifier(token) {
+            names.push(token.clone());
+            index += 1;
+        } else {
+            break;
+        }
+        if next_token(tokens, index) == "!" {
+            index = next_index(tokens, index) + 1;
+        }
+        if next_token(tokens, index) == ":" {
+            index = type_end(tokens, next_index(tokens, index) + 1);
+        }
+        if next_token(tokens, index) == "=" {
+            index = initializer_end(tokens, next_index(tokens, index) + 1);
+        }
+        declarators.push(Declarator {
+            start,
+            end: index,
+            names,
+        });
+        if next_token(tokens, index) != "," {
+            break;
+        }
+        index = next_index(tokens, index) + 1;
+    }
+    declarators
+}
+
+/// The names the declarators of a `const`, `let` or `var` starting at `index`
+/// bind, including the ones in destructuring patterns.
+pub fn declared_names(tokens: &[String], index: usize) -> Vec<String> {
+    declarators(tokens, index)
+        .into_iter()
+        .flat_map(|declarator| declarator.names)
+        .collect()
+}
+
+/// Index of the `,` or `;` after the initializer starting at `index`, or of
+/// the line break that ends it.
+pub fn initializer_end(tokens: &[String], mut index: usize) -> usize {
+    let can_end = |token: &str| {
+        token.ends_with(|char: char| {
+            char.is_alphanumeric() || matches!(char, '_' | '$' | ')' | ']' | '}' | '\'' | '"' | '`')
+        })
+    };
+    while index < tokens.len() {
+        let token = tokens[index].as_str();
+        match token {
+            "," | ";" | ")" | "]" | "}" => return index,
+            "\n" => {
+                let previous = tokens[..index]
+                    .iter()
+                    .rfind(|token| token.as_str() != "\n")
+                    .map_or("", String::as_str);
+                let next = next_token(tokens, index);
+                if can_end(previous) && is_identifier(next) {
+                    return index;
+                }
+            }
+            _ if is_opening(token) && token != "<" => index = matching_close(tokens, index),
+            _ => {}
+        }
+        index += 1;
+    }
+    index
+}
+
+/// Adds the names bound by the object or array pattern opened at `opening`.
+pub fn pattern_names(tokens: &[String], opening: usize, names: &mut Vec<String>) {
+    let close = matching_close(tokens, opening);
+    let is_object = tokens[opening] == "{";
+    let mut index = next_index(tokens, opening + 1);
+    while index < close {
+        if tokens[index] == "..." {
+            index = next_index(tokens, index + 1);
+        }
+        let mut target = index;
+        // `{ key: target }`, where the key can be computed.
+        if is_object {
+            let key_end = match tokens[index].as_str() {
+                "[" => matching_close(tokens, index) + 1,
+                _ => index + 1,
+            };
+            if next_token(tokens, key_end) == ":" {
+                target = next_index(tokens, next_index(tokens, key_end) + 1);
+            }
+        }
+        index = match tokens[target].as_str() {
+            "{" | "[" => {
+                pattern_names(tokens, target, names);
+                matching_close(tokens, target) + 1
+            }
+            token if is_identifier(token) => {
+                names.push(token.to_string());
+                target + 1
+            }
+            _ => target,
+        };
+        // A default value, up to the next element.
+        while index < close && tokens[index] != "," {
+            if is_opening(&tokens[index]) && tokens[index] != "<" {
+                index = matching_close(tokens, index);
+            }
+            index += 1;
+        }
+        index = next_index(tokens, index + 1);
+    }
+}
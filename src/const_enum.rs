@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use super::import_export::{parse_export_all, parse_export_list};
+use super::lexer::tokenize;
+use super::lookahead::{enum_member_end, is_identifier, matching_close, next_index, next_token};
+use crate::resolver::{resolve, Resolution};
+
+/// The members of a `const enum` and the literal each one is inlined as.
+pub type ConstEnum = HashMap<String, String>;
+
+/// `export { A as B } from './a'`, or `export * from './a'` without
+/// specifiers, which const enums can be imported through.
+#[derive(Clone)]
+struct Reexport {
+    source: String,
+    specifiers: Option<Vec<(String, String)>>,
+}
+
+/// The const enums a module exports itself, and where it re-exports others
+/// from.
+#[derive(Clone)]
+struct ModuleEnums {
+    enums: HashMap<String, ConstEnum>,
+    reexports: Vec<Reexport>,
+}
+
+/// Const enums of the modules that have been imported from, so a module
+/// imported from many places is only read once. The modification time is kept
+/// to notice when the file changes in a long running process.
+type Cache = HashMap<PathBuf, (Option<SystemTime>, ModuleEnums)>;
+
+fn cache() -> &'static Mutex<Cache> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Clone)]
+enum Value {
+    Number(f64),
+    String(String),
+}
+
+fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return String::from("NaN");
+    }
+    if value.is_infinite() {
+        return String::from(if value > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+    if value.fract() == 0.0 && value.abs() < 1e21 {
+        return format!("{}", value as i64);
+    }
+    format!("{}", value)
+}
+
+impl Value {
+    fn to_literal(&self) -> String {
+        match self {
+            // `(-1)` so that `E.A ** 2` and `x - E.A` stay what they were.
+            Value::Number(value) if value.is_sign_negative() && *value != 0.0 => {
+                format!("({})", number_to_string(*value))
+            }
+            Value::Number(value) => number_to_string(*value),
+            Value::String(value) => {
+                let mut literal = String::from("\"");
+                for char in value.chars() {
+                    match char {
+                        '"' => literal += "\\\"",
+                        '\\' => literal += "\\\\",
+                        '\n' => literal += "\\n",
+                        '\r' => literal += "\\r",
+                        '\u{2028}' => literal += "\\u2028",
+                        '\u{2029}' => literal += "\\u2029",
+                        _ => literal.push(char),
+                    }
+                }
+                literal + "\""
+            }
+        }
+    }
+
+    fn to_number(&self) -> f64 {
+        match self {
+            Value::Number(value) => *value,
+            Value::String(value) => value.trim().parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    fn to_int32(&self) -> i32 {
+        let value = self.to_number();
+        if !value.is_finite() {
+            return 0;
+        }
+        (value.trunc() as i64 as u64 & 0xffff_ffff) as u32 as i32
+    }
+}
+
+/// The text of a string literal token without its quotes and escapes.
+pub fn string_value(token: &str) -> Option<String> {
+    let quote = token.chars().next()?;
+    if !matches!(quote, '\'' | '"' | '`') || token.len() < 2 || token.ends_with("${") {
+        return None;
+    }
+    let mut value = String::new();
+    let mut chars = token[1..token.len() - 1].chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('b') => value.push('\u{8}'),
+            Some('f') => value.push('\u{c}'),
+            Some('v') => value.push('\u{b}'),
+            Some('0') => value.push('\0'),
+            Some('x') => {
+                let code: String = chars.by_ref().take(2).collect();
+                value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            Some('u') => {
+                let rest = chars.as_str();
+                let code = match rest.strip_prefix('{') {
+                    Some(braced) => braced.split('}').next()?.to_string(),
+                    None => rest.chars().take(4).collect(),
+                };
+                let skipped = if rest.starts_with('{') {
+                    code.len() + 2
+                } else {
+                    4
+                };
+                value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                chars = rest[skipped..].chars();
+            }
+            Some(escaped) => value.push(escaped),
+            None => {}
+        }
+    }
+    Some(value)
+}
+
+fn number_value(token: &str) -> Option<f64> {
+    let literal = token.replace('_', "").to_lowercase();
+    let radix = match literal.get(0..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => return literal.parse().ok(),
+    };
+    u64::from_str_radix(&literal[2..], radix)
+        .ok()
+        .map(|value| value as f64)
+}
+
+/// Evaluates the constant expressions TypeScript allows in enum initializers:
+/// literals, references to other members and the arithmetic between them.
+struct Evaluator<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+    name: &'a str,
+    members: &'a HashMap<String, Value>,
+    enums: &'a HashMap<String, HashMap<String, Value>>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn peek(&self, offset: usize) -> &'a str {
+        self.tokens
+            .get(self.position + offset)
+            .copied()
+            .unwrap_or("")
+    }
+
+    // `>` is never combined by the lexer, so shifts arrive one `>` at a time.
+    fn binary_operator(&self) -> Option<(&'static str, u8)> {
+        let operator = match (self.peek(0), self.peek(1), self.peek(2)) {
+            (">", ">", ">") => ">>>",
+            (">", ">", _) => ">>",
+            ("**", _, _) => "**",
+            ("*", _, _) => "*",
+            ("/", _, _) => "/",
+            ("%", _, _) => "%",
+            ("+", _, _) => "+",
+            ("-", _, _) => "-",
+            ("<", "<", _) => "<<",
+            ("&", _, _) => "&",
+            ("^", _, _) => "^",
+            ("|", _, _) => "|",
+            _ => return None,
+        };
+        let precedence = match operator {
+            "**" => 14,
+            "*" | "/" | "%" => 13,
+            "+" | "-" => 12,
+            ">>>" | ">>" | "<<" => 11,
+            "&" => 8,
+            "^" => 7,
+            _ => 6,
+        };
+        Some((operator, precedence))
+    }
+
+    fn expression(&mut self, min_precedence: u8) -> Option<Value> {
+        let mut left = self.unary()?;
+        while let Some((operator, precedence)) = self.binary_operator() {
+            if precedence < min_precedence {
+                break;
+            }
+            self.position += match operator {
+                ">>>" => 3,
+                ">>" | "<<" => 2,
+                _ => 1,
+            };
+            // `**` is the only right associative operator.
+            let next_precedence = if operator == "**" {
+                precedence
+            } else {
+                precedence + 1
+            };
+            let right = self.expression(next_precedence)?;
+            left = apply(operator, left, right);
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<Value> {
+        let token = self.peek(0);
+        self.position += 1;
+        match token {
+            "-" => Some(Value::Number(-self.unary()?.to_number())),
+            "+" => Some(Value::Number(self.unary()?.to_number())),
+            "~" => Some(Value::Number(!self.unary()?.to_int32() as f64)),
+            "(" => {
+                let value = self.expression(0)?;
+                if self.peek(0) != ")" {
+                    return None;
+                }
+                self.position += 1;
+                Some(value)
+            }
+            _ if token.starts_with(|char: char| char.is_ascii_digit() || char == '.') => {
+                number_value(token).map(Value::Number)
+            }
+            _ if is_identifier(token) => self.reference(token),
+            _ => string_value(token).map(Value::String),
+        }
+    }
+
+    // `A`, `E.A` or `E["A"]`, where `E` is this enum or one declared before it.
+    fn reference(&mut self, token: &str) -> Option<Value> {
+        let members = match token == self.name {
+            true => Some(self.members),
+            false => self.enums.get(token),
+        };
+        if let Some(members) = members {
+            let member = match self.peek(0) {
+                "." => Some(self.peek(1).to_string()),
+                "[" if self.peek(2) == "]" => string_value(self.peek(1)),
+                _ => None,
+            };
+            if let Some(member) = member {
+                self.position += if self.peek(0) == "." { 2 } else { 3 };
+                return members.get(&member).cloned();
+            }
+        }
+        match token {
+            "NaN" => Some(Value::Number(f64::NAN)),
+            "Infinity" => Some(Value::Number(f64::INFINITY)),
+            _ => self.members.get(token).cloned(),
+        }
+    }
+}
+
+fn apply(operator: &str, left: Value, right: Value) -> Value {
+    if operator == "+" {
+        if let (Value::Number(left), Value::Number(right)) = (&left, &right) {
+            return Value::Number(left + right);
+        }
+        let text = |value: &Value| match value {
+            Value::Number(value) => number_to_string(*value),
+            Value::String(value) => value.clone(),
+        };
+        return Value::String(text(&left) + &text(&right));
+    }
+    let (a, b) = (left.to_number(), right.to_number());
+    let (x, y) = (left.to_int32(), right.to_int32());
+    Value::Number(match operator {
+        "-" => a - b,
+        "*" => a * b,
+        "/" => a / b,
+        "%" => a % b,
+        "**" => a.powf(b),
+        "<<" => x.wrapping_shl(y as u32 & 31) as f64,
+        ">>" => x.wrapping_shr(y as u32 & 31) as f64,
+        ">>>" => (x as u32).wrapping_shr(y as u32 & 31) as f64,
+        "&" => (x & y) as f64,
+        "^" => (x ^ y) as f64,
+        _ => (x | y) as f64,
+    })
+}
+
+// Whether the declaration starting at `position` is preceded by `export`,
+// possibly as `export declare const enum`.
+fn is_exported(tokens: &[String], position: usize) -> bool {
+    let previous = |index: usize| {
+        tokens[..index]
+            .iter()
+            .rposition(|token| token != "\n")
+            .map(|index| (index, tokens[index].as_str()))
+    };
+    match previous(position) {
+        Some((_, "export")) => true,
+        Some((index, "declare")) => matches!(previous(index), Some((_, "export"))),
+        _ => false,
+    }
+}
+
+/// Evaluates the members of the `const enum` declarations in `tokens`, only
+/// the exported ones when `is_exported_only`. Members whose value isn't a
+/// constant are left out and keep being looked up at runtime.
+pub fn declared_const_enums(
+    tokens: &[String],
+    is_exported_only: bool,
+) -> HashMap<String, ConstEnum> {
+    let mut enums: HashMap<String, HashMap<String, Value>> = HashMap::new();
+    let mut exported = vec![];
+
+    for position in 0..tokens.len() {
+        if tokens[position] != "const" || next_token(tokens, position + 1) != "enum" {
+            continue;
+        }
+        let name_index = next_index(tokens, next_index(tokens, position + 1) + 1);
+        let name = next_token(tokens, name_index).to_string();
+        let opening = next_index(tokens, name_index + 1);
+        if !is_identifier(&name) || next_token(tokens, opening) != "{" {
+            continue;
+        }
+        if is_exported(tokens, position) {
+            exported.push(name.clone());
+        }
+
+        // Declarations of the same enum merge.
+        let mut members = enums.remove(&name).unwrap_or_default();
+        let close = matching_close(tokens, opening);
+        let mut previous: Option<Value> = None;
+        let mut index = next_index(tokens, opening + 1);
+        while index < close {
+            let end = enum_member_end(tokens, index);
+            let member = string_value(&tokens[index]).unwrap_or_else(|| tokens[index].clone());
+            let initializer = next_index(tokens, index + 1);
+            let value = if next_token(tokens, initializer) == "=" {
+                let mut evaluator = Evaluator {
+                    tokens: tokens[initializer + 1..end]
+                        .iter()
+                        .map(|token| token.as_str())
+                        .filter(|token| *token != "\n")
+                        .collect(),
+                    position: 0,
+                    name: &name,
+                    members: &members,
+                    enums: &enums,
+                };
+                evaluator
+                    .expression(0)
+                    .filter(|_| evaluator.position == evaluator.tokens.len())
+            } else {
+                match &previous {
+                    None => Some(Value::Number(0.0)),
+                    Some(Value::Number(value)) => Some(Value::Number(value + 1.0)),
+                    Some(Value::String(_)) => None,
+                }
+            };
+            if let Some(value) = &value {
+                members.insert(member, value.clone());
+            }
+            previous = value;
+            index = next_index(tokens, end + 1);
+        }
+        enums.insert(name, members);
+    }
+
+    enums
+        .into_iter()
+        .filter(|(name, _)| !is_exported_only || exported.contains(name))
+        .map(|(name, members)| {
+            let literals = members
+                .into_iter()
+                .map(|(member, value)| (member, value.to_literal()))
+                .collect();
+            (name, literals)
+        })
+        .collect()
+}
+
+// The exported const enums of `tokens`, including the ones exported with
+// `export { A }`, and its re-exports.
+fn module_enums(tokens: &[String]) -> ModuleEnums {
+    let declared = declared_const_enums(tokens, false);
+    let mut enums = declared_const_enums(tokens, true);
+    let mut reexports = vec![];
+    for position in 0..tokens.len() {
+        if tokens[position] != "export" {
+            continue;
+        }
+        if let Some(list) = parse_export_list(tokens, position) {
+            let specifiers = list
+                .specifiers
+                .into_iter()
+                .map(|specifier| (specifier.name, specifier.alias));
+            match list.source.as_deref().and_then(string_value) {
+                Some(source) => reexports.push(Reexport {
+                    source,
+                    specifiers: Some(specifiers.collect()),
+                }),
+                None => enums.extend(specifiers.filter_map(|(name, alias)| {
+                    declared.get(&name).map(|members| (alias, members.clone()))
+                })),
+            }
+        } else if let Some(export) = parse_export_all(tokens, position) {
+            if let (None, Some(source)) = (export.namespace, string_value(&export.source)) {
+                reexports.push(Reexport {
+                    source,
+                    specifiers: None,
+                });
+            }
+        }
+    }
+    ModuleEnums { enums, reexports }
+}
+
+fn read_module_enums(path: &Path) -> ModuleEnums {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    let mut cache = cache().lock().unwrap_or_else(|error| error.into_inner());
+    if let Some((cached_modified, enums)) = cache.get(path) {
+        if *cached_modified == modified {
+            return enums.clone();
+        }
+    }
+    let enums = match fs::read_to_string(path) {
+        Ok(contents) => module_enums(&tokenize(&contents)),
+        Err(_) => ModuleEnums {
+            enums: HashMap::new(),
+            reexports: vec![],
+        },
+    };
+    cache.insert(path.to_path_buf(), (modified, enums.clone()));
+    enums
+}
+
+/// The exported const enums of the module at `path`, following the modules
+/// it re-exports from.
+pub fn imported_const_enums(path: &Path) -> HashMap<String, ConstEnum> {
+    exported_const_enums(path, &mut vec![])
+}
+
+fn exported_const_enums(path: &Path, seen: &mut Vec<PathBuf>) -> HashMap<String, ConstEnum> {
+    if seen.iter().any(|seen| seen == path) {
+        return HashMap::new();
+    }
+    seen.push(path.to_path_buf());
+    let ModuleEnums {
+        mut enums,
+        reexports,
+    } = read_module_enums(path);
+    for reexport in reexports {
+        let Some(source) = resolve(path, &reexport.source, Resolution::Types) else {
+            continue;
+        };
+        let reexported = exported_const_enums(&source, seen);
+        match reexport.specifiers {
+            Some(specifiers) => {
+                for (name, alias) in specifiers {
+                    if let Some(members) = reexported.get(&name) {
+                        enums.insert(alias, members.clone());
+                    }
+                }
+            }
+            // Like `export *`, an enum the module declares itself wins.
+            None => {
+                for (name, members) in reexported {
+                    enums.entry(name).or_insert(members);
+                }
+            }
+        }
+    }
+    seen.pop();
+    enums
+}
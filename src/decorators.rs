@@ -0,0 +1,578 @@
+use super::lookahead::{
+    class_member_signature_end, enum_member_end, is_class_member_modifier, is_identifier,
+    is_opening, matching_close, next_index, next_token, type_end,
+};
+
+/// A parameter of a decorated method or constructor.
+pub struct Parameter {
+    pub decorators: Vec<String>,
+    pub annotation: Option<Vec<String>>,
+    pub is_rest: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MemberKind {
+    Field,
+    Method,
+    Getter,
+    Setter,
+    // A field declared with the `accessor` keyword.
+    Accessor,
+}
+
+/// A member of a class, with the decorators on it and on its parameters.
+pub struct MemberDecoration {
+    // The property key as passed to `__decorate`: `"name"`, a literal or the
+    // expression of a computed name.
+    pub key: String,
+    // The name as it was written, `[` for a computed name.
+    pub name: String,
+    pub is_static: bool,
+    pub is_async: bool,
+    pub kind: MemberKind,
+    pub decorators: Vec<String>,
+    pub parameters: Vec<Parameter>,
+    // The type of a field, or the return type of a method or getter.
+    pub annotation: Option<Vec<String>>,
+    // Indexes of its first token after the decorators, of the `accessor`
+    // keyword, of the last token of the name and of the `=` of a field.
+    pub start: usize,
+    pub accessor_keyword: Option<usize>,
+    pub name_end: usize,
+    pub initializer: Option<usize>,
+    // Index of the last token of the member, before its `;` if it has one.
+    pub last: usize,
+    pub has_semicolon: bool,
+}
+
+impl MemberDecoration {
+    pub fn is_decorated(&self) -> bool {
+        !self.decorators.is_empty()
+            || self
+                .parameters
+                .iter()
+                .any(|parameter| !parameter.decorators.is_empty())
+    }
+
+    pub fn is_field(&self) -> bool {
+        matches!(self.kind, MemberKind::Field | MemberKind::Accessor)
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.name.starts_with('#')
+    }
+}
+
+/// The members of a class and the decorators on them and on its
+/// constructor parameters, measured from its body before any of it is
+/// written.
+pub struct ClassDecoration {
+    // Every member except the constructor and the ones that only exist as types.
+    pub members: Vec<MemberDecoration>,
+    // `None` when the class has no constructor of its own.
+    pub constructor: Option<Vec<Parameter>>,
+    // Index of the `{` of the constructor body.
+    pub constructor_body: Option<usize>,
+}
+
+/// Constructors that can be referenced without checking they exist.
+const GLOBAL_CONSTRUCTORS: [&str; 16] = [
+    "Array",
+    "ArrayBuffer",
+    "Boolean",
+    "Date",
+    "Error",
+    "Function",
+    "Map",
+    "Number",
+    "Object",
+    "Promise",
+    "RegExp",
+    "Set",
+    "String",
+    "Symbol",
+    "WeakMap",
+    "WeakSet",
+];
+
+/// Index just past the decorator whose `@` is at `position`: `@a`, `@a.b`,
+/// `@a.b(c)` or `@(expression)`.
+pub fn decorator_end(tokens: &[String], position: usize) -> usize {
+    let mut index = next_index(tokens, position + 1);
+    if next_token(tokens, index) == "(" {
+        return matching_close(tokens, index) + 1;
+    }
+    index += 1;
+    loop {
+        let next = next_index(tokens, index);
+        match next_token(tokens, next) {
+            "." => index = next_index(tokens, next + 1) + 1,
+            "(" => index = matching_close(tokens, next) + 1,
+            _ => return index,
+        }
+    }
+}
+
+/// Index of the `{` that opens the body of the class whose `class` keyword
+/// is at `position`, `None` when there isn't one.
+pub fn class_body(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = position + 1;
+    while index < tokens.len() && tokens[index] != "{" {
+        if matches!(tokens[index].as_str(), "<" | "(" | "[") {
+            index = matching_close(tokens, index);
+        }
+        index += 1;
+    }
+    (index < tokens.len()).then_some(index)
+}
+
+// Reads the decorators starting at `index` and returns the index after them.
+fn read_decorators(tokens: &[String], mut index: usize, decorators: &mut Vec<String>) -> usize {
+    while next_token(tokens, index) == "@" {
+        let position = next_index(tokens, index);
+        decorators.push(join(&tokens[position + 1..decorator_end(tokens, position)]));
+        index = next_index(tokens, decorator_end(tokens, position));
+    }
+    index
+}
+
+// The type annotation after the `:` at `index`, if there is one, and the
+// index after it.
+fn read_annotation(tokens: &[String], index: usize) -> (Option<Vec<String>>, usize) {
+    if next_token(tokens, index) != ":" {
+        return (None, index);
+    }
+    let start = next_index(tokens, index) + 1;
+    let end = type_end(tokens, start);
+    (Some(without_line_breaks(&tokens[start..end])), end)
+}
+
+// The parameters of the list opened at `opening`, leaving out `this`.
+fn read_parameters(tokens: &[String], opening: usize) -> Vec<Parameter> {
+    let close = matching_close(tokens, opening);
+    let mut parameters = vec![];
+    let mut index = next_index(tokens, opening + 1);
+    while index < close {
+        let mut decorators = vec![];
+        index = read_decorators(tokens, index, &mut decorators);
+        while is_class_member_modifier(tokens, index) {
+            index = next_index(tokens, index + 1);
+        }
+        let is_rest = tokens[index] == "...";
+        if is_rest {
+            index = next_index(tokens, index + 1);
+        }
+        let is_this = tokens[index] == "this";
+        if is_opening(&tokens[index]) {
+            index = matching_close(tokens, index);
+        }
+        index = next_index(tokens, index + 1);
+        if tokens[index] == "?" {
+            index = next_index(tokens, index + 1);
+        }
+        let (annotation, end) = read_annotation(tokens, index);
+        index = end;
+        // A default value runs until the next parameter.
+        while index < close && tokens[index] != "," {
+            if is_opening(&tokens[index]) && tokens[index] != "<" {
+                index = matching_close(tokens, index);
+            }
+            index += 1;
+        }
+        index = next_index(tokens, index + 1);
+        if !is_this {
+            parameters.push(Parameter {
+                decorators,
+                annotation,
+                is_rest,
+            });
+        }
+    }
+    parameters
+}
+
+/// Reads the members of the class body opened at `opening` for decorators.
+pub fn class_decoration(tokens: &[String], opening: usize) -> ClassDecoration {
+    let close = matching_close(tokens, opening);
+    let mut decoration = ClassDecoration {
+        members: vec![],
+        constructor: None,
+        constructor_body: None,
+    };
+    let mut index = opening + 1;
+
+    loop {
+        while matches!(tokens.get(index).map(String::as_str), Some("\n" | ";")) {
+            index += 1;
+        }
+        if index >= close {
+            break;
+        }
+
+        let mut decorators = vec![];
+        index = read_decorators(tokens, index, &mut decorators);
+        // Members that only exist as types are erased along with their decorators.
+        if let Some(end) = class_member_signature_end(tokens, index) {
+            index = end;
+            continue;
+        }
+        let start = index;
+        let mut is_static = false;
+        let mut is_async = false;
+        let mut kind = MemberKind::Field;
+        let mut accessor_keyword = None;
+        while is_class_member_modifier(tokens, index) {
+            match tokens[index].as_str() {
+                "static" => is_static = true,
+                "async" => is_async = true,
+                "get" => kind = MemberKind::Getter,
+                "set" => kind = MemberKind::Setter,
+                "accessor" => {
+                    kind = MemberKind::Accessor;
+                    accessor_keyword = Some(index);
+                }
+                _ => {}
+            }
+            index = next_index(tokens, index + 1);
+        }
+        if next_token(tokens, index) == "*" {
+            index = next_index(tokens, index + 1);
+        }
+
+        let name = tokens[index].clone();
+        let key = match name.as_str() {
+            "[" => {
+                let end = matching_close(tokens, index);
+                let key = join(&tokens[index + 1..end]);
+                index = end;
+                key
+            }
+            _ if is_identifier(&name) => format!("\"{}\"", name),
+            _ => name.clone(),
+        };
+        let name_end = index;
+        let is_constructor = name == "constructor";
+        index = next_index(tokens, index + 1);
+        if matches!(next_token(tokens, index), "?" | "!") {
+            index = next_index(tokens, index + 1);
+        }
+        if next_token(tokens, index) == "<" {
+            index = next_index(tokens, matching_close(tokens, index) + 1);
+        }
+
+        let mut parameters = vec![];
+        let annotation;
+        let mut initializer = None;
+        let has_body;
+        if next_token(tokens, index) == "(" {
+            if kind == MemberKind::Field {
+                kind = MemberKind::Method;
+            }
+            parameters = read_parameters(tokens, index);
+            (annotation, index) = read_annotation(tokens, matching_close(tokens, index) + 1);
+            has_body = next_token(tokens, index) == "{";
+            if has_body {
+                let body = next_index(tokens, index);
+                if is_constructor {
+                    decoration.constructor_body = Some(body);
+                }
+                index = matching_close(tokens, body) + 1;
+            }
+        } else {
+            (annotation, index) = read_annotation(tokens, index);
+            has_body = false;
+            if next_token(tokens, index) == "=" {
+                initializer = Some(next_index(tokens, index));
+            }
+            // A field runs until the end of its line, or its `;`.
+            while index < close && !matches!(tokens[index].as_str(), "\n" | ";") {
+                if is_opening(&tokens[index]) && tokens[index] != "<" {
+                    index = matching_close(tokens, index);
+                }
+                index += 1;
+            }
+        }
+        let has_semicolon = tokens.get(index).map(String::as_str) == Some(";");
+        let last = tokens[..index]
+            .iter()
+            .rposition(|token| token != "\n")
+            .unwrap_or(index);
+
+        if is_constructor {
+            if has_body {
+                decoration.constructor = Some(parameters);
+            }
+        } else {
+            decoration.members.push(MemberDecoration {
+                key,
+                name,
+                is_static,
+                is_async,
+                kind,
+                decorators,
+                parameters,
+                annotation,
+                start,
+                accessor_keyword,
+                name_end,
+                initializer,
+                last,
+                has_semicolon,
+            });
+        }
+    }
+    decoration
+}
+
+/// The `__param(0,a)` calls for the decorators of `parameters`.
+pub fn parameter_decorators(parameters: &[Parameter]) -> Vec<String> {
+    let mut decorators = vec![];
+    for (index, parameter) in parameters.iter().enumerate() {
+        for decorator in parameter.decorators.iter() {
+            decorators.push(format!("__param({},{})", index, decorator));
+        }
+    }
+    decorators
+}
+
+/// The `design:*` metadata `tsc` adds to a decorated member with
+/// `emitDecoratorMetadata`. Classes the types refer to are added to
+/// `references`.
+pub fn member_metadata(
+    member: &MemberDecoration,
+    tokens: &[String],
+    references: &mut Vec<String>,
+) -> Vec<String> {
+    let mut metadata = vec![];
+    match member.kind {
+        MemberKind::Field | MemberKind::Accessor => {
+            let annotation = annotation_type(&member.annotation, tokens, references);
+            metadata.push(("design:type", annotation));
+        }
+        MemberKind::Method => {
+            metadata.push(("design:type", String::from("Function")));
+            let parameters = parameter_types(&member.parameters, tokens, references);
+            metadata.push(("design:paramtypes", parameters));
+            let returns = match (&member.annotation, member.is_async) {
+                (Some(annotation), _) => serialize_type(annotation, tokens, references),
+                (None, true) => String::from("Promise"),
+                (None, false) => String::from("void 0"),
+            };
+            metadata.push(("design:returntype", returns));
+        }
+        MemberKind::Getter => {
+            let annotation = annotation_type(&member.annotation, tokens, references);
+            metadata.push(("design:type", annotation));
+            metadata.push(("design:paramtypes", String::from("[]")));
+        }
+        MemberKind::Setter => {
+            let annotation = member
+                .parameters
+                .first()
+                .and_then(|parameter| parameter.annotation.clone());
+            let annotation = annotation_type(&annotation, tokens, references);
+            metadata.push(("design:type", annotation));
+            let parameters = parameter_types(&member.parameters, tokens, references);
+            metadata.push(("design:paramtypes", parameters));
+        }
+    }
+    metadata
+        .into_iter()
+        .map(|(key, value)| format!("__metadata(\"{}\",{})", key, value))
+        .collect()
+}
+
+/// `[A,String]` for the types of `parameters`, as in `design:paramtypes`.
+pub fn parameter_types(
+    parameters: &[Parameter],
+    tokens: &[String],
+    references: &mut Vec<String>,
+) -> String {
+    let types: Vec<String> = parameters
+        .iter()
+        .map(|parameter| match &parameter.annotation {
+            // `...rest: T[]` is described by the type of its elements.
+            Some(annotation) if parameter.is_rest => match annotation.as_slice() {
+                [element @ .., open, close] if open == "[" && close == "]" => {
+                    serialize_type(element, tokens, references)
+                }
+                [array, open, element @ .., close]
+                    if array == "Array" && open == "<" && close == ">" =>
+                {
+                    serialize_type(element, tokens, references)
+                }
+                _ => String::from("Object"),
+            },
+            _ => annotation_type(&parameter.annotation, tokens, references),
+        })
+        .collect();
+    format!("[{}]", types.join(","))
+}
+
+fn annotation_type(
+    annotation: &Option<Vec<String>>,
+    tokens: &[String],
+    references: &mut Vec<String>,
+) -> String {
+    match annotation {
+        Some(annotation) => serialize_type(annotation, tokens, references),
+        None => String::from("Object"),
+    }
+}
+
+// Splits a type at its top level `|` and `&`.
+fn type_constituents(annotation: &[String]) -> Vec<&[String]> {
+    let mut constituents = vec![];
+    let mut start = 0;
+    let mut index = 0;
+    while index <= annotation.len() {
+        let token = annotation.get(index).map_or("|", String::as_str);
+        if token == "|" || token == "&" {
+            if index > start {
+                constituents.push(&annotation[start..index]);
+            }
+            start = index + 1;
+        } else if is_opening(token) {
+            index = matching_close(annotation, index);
+        }
+        index += 1;
+    }
+    constituents
+}
+
+/// The runtime value `tsc` uses to describe a type: the constructor of a
+/// primitive, the class a type refers to or `Object` for everything that
+/// only exists in the type system.
+pub fn serialize_type(
+    annotation: &[String],
+    tokens: &[String],
+    references: &mut Vec<String>,
+) -> String {
+    let constituents = type_constituents(annotation);
+    // Unions and intersections are described by their members when they all
+    // agree, leaving out `null` and `undefined`.
+    if constituents.len() > 1 {
+        let serialized: Vec<String> = constituents
+            .into_iter()
+            .filter(|constituent| {
+                !matches!(constituent, [token] if matches!(token.as_str(), "null" | "undefined" | "never"))
+            })
+            .map(|constituent| serialize_type(constituent, tokens, references))
+            .collect();
+        return match serialized.first() {
+            Some(first) if serialized.iter().all(|value| value == first) => first.clone(),
+            _ => String::from("Object"),
+        };
+    }
+
+    let annotation = constituents.first().copied().unwrap_or(&[]);
+    let Some(first) = annotation.first().map(String::as_str) else {
+        return String::from("Object");
+    };
+    let length = annotation.len();
+    if first == "(" && matching_close(annotation, 0) == length - 1 {
+        return serialize_type(&annotation[1..length - 1], tokens, references);
+    }
+    if length > 2 && annotation[length - 2] == "[" && annotation[length - 1] == "]" {
+        return String::from("Array");
+    }
+    if length == 3 && annotation[1] == "is" {
+        return String::from("Boolean");
+    }
+    let value = match first {
+        "readonly" => return serialize_type(&annotation[1..], tokens, references),
+        // Function and constructor types.
+        "(" | "<" | "new" | "abstract" => "Function",
+        "[" => "Array",
+        "string" => "String",
+        "number" => "Number",
+        "boolean" | "true" | "false" => "Boolean",
+        "bigint" => "BigInt",
+        "symbol" => "Symbol",
+        "void" | "undefined" | "null" | "never" => "void 0",
+        "any" | "unknown" | "object" => "Object",
+        _ if first.starts_with(['\'', '"', '`']) => "String",
+        _ if first.starts_with(|char: char| char.is_ascii_digit()) && first.ends_with('n') => {
+            "BigInt"
+        }
+        _ if first.starts_with(|char: char| char.is_ascii_digit()) || first == "-" => "Number",
+        _ if is_identifier(first) && (length == 1 || annotation[1] == "<") => {
+            return serialize_reference(first, tokens, references)
+        }
+        _ => "Object",
+    };
+    String::from(value)
+}
+
+// A class declared in this module, or a global constructor, is referenced
+// directly. Enums are described by their values and type aliases by the type
+// they stand for, interfaces are `Object`. An imported name might only be a
+// type so it is checked first.
+fn serialize_reference(name: &str, tokens: &[String], references: &mut Vec<String>) -> String {
+    let declaration = |keyword: &str| {
+        tokens
+            .windows(2)
+            .position(|pair| pair[0] == keyword && pair[1] == name)
+    };
+    if let Some(position) = declaration("enum") {
+        return serialize_enum(tokens, position + 2);
+    }
+    if let Some(position) = declaration("type") {
+        // Only `type A = B`, a generic alias or `import type A` are `Object`.
+        let index = next_index(tokens, position + 2);
+        if tokens.get(index).is_some_and(|token| token == "=") {
+            let start = next_index(tokens, index + 1);
+            let annotation = without_line_breaks(&tokens[start..type_end(tokens, start)]);
+            return serialize_type(&annotation, tokens, references);
+        }
+        return String::from("Object");
+    }
+    if declaration("interface").is_some() {
+        return String::from("Object");
+    }
+    if GLOBAL_CONSTRUCTORS.contains(&name) {
+        return name.to_string();
+    }
+    references.push(name.to_string());
+    if declaration("class").is_some() {
+        return name.to_string();
+    }
+    format!("typeof {}===\"undefined\"?Object:{}", name, name)
+}
+
+// `Number` when every member of the enum whose body is after `position` is
+// numeric, `String` when they are all strings and `Object` when they mix.
+fn serialize_enum(tokens: &[String], position: usize) -> String {
+    let open = next_index(tokens, position);
+    let close = matching_close(tokens, open);
+    let mut values = vec![];
+    let mut index = next_index(tokens, open + 1);
+    while index < close {
+        let end = enum_member_end(tokens, index);
+        let is_string = tokens[index..end]
+            .iter()
+            .position(|token| token == "=")
+            .is_some_and(|offset| {
+                next_token(tokens, index + offset + 1).starts_with(['\'', '"', '`'])
+            });
+        values.push(if is_string { "String" } else { "Number" });
+        index = next_index(tokens, end + 1);
+    }
+    match values.first() {
+        Some(first) if values.iter().any(|value| value != first) => String::from("Object"),
+        Some(first) => first.to_string(),
+        None => String::from("Number"),
+    }
+}
+
+fn without_line_breaks(tokens: &[String]) -> Vec<String> {
+    tokens
+        .iter()
+        .filter(|token| token.as_str() != "\n")
+        .cloned()
+        .collect()
+}
+
+fn join(tokens: &[String]) -> String {
+    super::join_tokens(&without_line_breaks(tokens))
+}
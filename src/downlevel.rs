@@ -0,0 +1,2078 @@
+use super::lexer::tokenize;
+use super::lookahead::{is_closing, is_identifier, is_opening, is_template_head, matching_close};
+use super::{can_end_expression, seperator};
+use crate::options::{Options, Target};
+use std::collections::{HashMap, HashSet};
+
+/// Keywords that can't end an expression, so whatever follows them starts one.
+const KEYWORDS: [&str; 28] = [
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+    "export",
+    "default",
+    "extends",
+    "const",
+    "let",
+    "var",
+    "if",
+    "while",
+    "for",
+    "switch",
+    "with",
+    "catch",
+    "function",
+    "class",
+];
+
+const UNARY_OPERATORS: [&str; 10] = [
+    "!", "~", "+", "-", "++", "--", "typeof", "void", "delete", "await",
+];
+
+const ASSIGNMENT_OPERATORS: [&str; 16] = [
+    "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=", ">>>=", "&=", "|=", "^=", "&&=", "||=",
+    "??=",
+];
+
+/// The key the variables declared at the top of the module are kept under.
+const MODULE: usize = usize::MAX;
+
+// How tightly a binary operator binds, `None` for anything else. `??` can't
+// be mixed with `||` and `&&` without parentheses, so they share a level.
+fn precedence(token: &str) -> Option<u8> {
+    Some(match token {
+        "??" | "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" | "===" | "!==" => 6,
+        "<" | ">" | "<=" | ">=" | "instanceof" | "in" => 7,
+        "<<" | ">>" | ">>>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        "**" => 11,
+        _ => return None,
+    })
+}
+
+fn is_regex(token: &str) -> bool {
+    token.starts_with('/') && token.len() > 1 && token != "/="
+}
+
+fn ends_expression(token: &str) -> bool {
+    (can_end_expression(token) || is_regex(token)) && !KEYWORDS.contains(&token)
+}
+
+// A token an expression can consist of by itself.
+fn is_operand(token: &str) -> bool {
+    let first = token.chars().next().unwrap_or(' ');
+    (is_identifier(token) && !KEYWORDS.contains(&token))
+        || first.is_ascii_digit()
+        || matches!(first, '\'' | '"' | '`' | '#')
+        || (first == '.' && token.len() > 1)
+        || is_regex(token)
+}
+
+// Whether an expression goes on after a line break before `token`. The
+// tree only keeps line breaks before tokens that can start an expression,
+// and of those these continue the one before.
+fn is_line_continued(token: &str) -> bool {
+    matches!(token, "(" | "[" | "+" | "-" | "/") || token.starts_with('`')
+}
+
+// The lexer reads `**` and the shifts as single characters, as `>` also
+// closes type arguments, which doesn't matter for the JavaScript read here.
+fn join_operators(tokens: Vec<String>) -> Vec<String> {
+    let mut joined: Vec<String> = vec![];
+    for token in tokens {
+        let operator = match (joined.last().map(String::as_str), token.as_str()) {
+            (Some("*"), "*") => "**",
+            (Some("<"), "<") => "<<",
+            (Some("<"), "<=") => "<<=",
+            (Some(">"), ">") => ">>",
+            (Some(">>"), ">") => ">>>",
+            (Some(">"), ">=") => ">>=",
+            (Some(">>"), ">=") => ">>>=",
+            _ => {
+                joined.push(token);
+                continue;
+            }
+        };
+        *joined.last_mut().expect("Matched above") = operator.to_string();
+    }
+    joined
+}
+
+fn join(tokens: &[String]) -> String {
+    let mut output = String::new();
+    let mut previous = "";
+    let mut has_line_break = false;
+    for token in tokens {
+        if token == "\n" {
+            has_line_break = true;
+            continue;
+        }
+        output += seperator(previous, token, has_line_break);
+        output += token;
+        previous = token;
+        has_line_break = false;
+    }
+    output
+}
+
+fn declaration(names: Vec<String>) -> Vec<String> {
+    let mut tokens = vec![String::from("var")];
+    for (index, name) in names.into_iter().enumerate() {
+        if index > 0 {
+            tokens.push(String::from(","));
+        }
+        tokens.push(name);
+    }
+    tokens.push(String::from(";"));
+    tokens
+}
+
+#[derive(Clone)]
+enum PartKind {
+    // `.a` or `.#a`, at the name.
+    Member(usize),
+    // `[a]` and `(a)`, at their brackets.
+    Computed(usize, usize),
+    Call(usize, usize),
+    // A tagged template, its first and last token.
+    Template(usize, usize),
+}
+
+#[derive(Clone)]
+struct Part {
+    is_optional: bool,
+    kind: PartKind,
+}
+
+impl Part {
+    fn is_member(&self) -> bool {
+        matches!(self.kind, PartKind::Member(_) | PartKind::Computed(..))
+    }
+
+    fn is_call(&self) -> bool {
+        matches!(self.kind, PartKind::Call(..))
+    }
+}
+
+/// `a.b?.[c](d)`, the member accesses and calls on an expression.
+#[derive(Clone)]
+struct Chain {
+    // The first token of that expression and the one after it.
+    base: (usize, usize),
+    parts: Vec<Part>,
+    end: usize,
+}
+
+/// The syntax that is rewritten, each spanning from the token it is found at
+/// up to an end.
+#[derive(Clone)]
+enum Construct {
+    // `a ** b` and `a ?? b`, at the operator.
+    Exponent(usize),
+    Nullish(usize),
+    // `a ||= b`, `a **= b` or an assignment to a private member.
+    Assignment(Chain, usize),
+    // `++a.#b` or `a.#b--`.
+    Update(Chain, usize),
+    // `[a.#b]=c`, a private member that destructuring or a `for…of` loop
+    // assigns to.
+    PrivateTarget(Chain),
+    // A chain with optional or private parts, which is deleted when it
+    // follows `delete`.
+    Chain(Chain, bool),
+    // `#a in b`, at `#a`.
+    PrivateIn(usize),
+    // `#a=1;` in a constructor, see `Tree::end_assigned_field`.
+    PrivateInitialization(usize, Option<(usize, usize)>),
+    Class(usize),
+    Scope(usize),
+}
+
+/// A function body, or the body of an arrow function without braces, which
+/// variables are declared at the top of.
+#[derive(Clone)]
+struct Scope {
+    // The `{`, or the first token of the expression.
+    start: usize,
+    end: usize,
+    is_expression: bool,
+    is_arrow: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MemberKind {
+    Method,
+    Getter,
+    Setter,
+    Field,
+    StaticBlock,
+}
+
+#[derive(Clone)]
+struct Member {
+    start: usize,
+    end: usize,
+    is_static: bool,
+    is_async: bool,
+    is_generator: bool,
+    is_private: bool,
+    is_constructor: bool,
+    kind: MemberKind,
+    // The name, a `[` for a computed one, and the token after it.
+    name: usize,
+    name_end: usize,
+    initializer: Option<(usize, usize)>,
+}
+
+/// How the helpers reach a private member, like `tsc` lowers them: fields
+/// are kept in a WeakMap, and methods and accessors are functions that are
+/// called on instances kept in a WeakSet. Static members are checked
+/// against the class itself.
+#[derive(Clone)]
+struct PrivateName {
+    state: String,
+    // `"f"`, `"m"` or `"a"`.
+    kind: &'static str,
+    // The function of a method, the accessors, or the `{value}` a static
+    // field is kept in.
+    getter: Option<String>,
+    setter: Option<String>,
+}
+
+#[derive(Clone)]
+struct Class {
+    start: usize,
+    name: Option<String>,
+    // Its `{` and `}`.
+    body: usize,
+    close: usize,
+    members: Vec<Member>,
+    is_declaration: bool,
+    is_derived: bool,
+    // Whether it has any syntax to lower.
+    is_lowered: bool,
+    // What its static members are reached through: the name of a class
+    // declaration, or a variable a class expression is assigned to.
+    reference: Option<String>,
+    // The name `A = class {}` gives a class expression, which it wouldn't
+    // have in the parentheses it's lowered to.
+    inferred_name: Option<String>,
+    private_names: Vec<(String, PrivateName)>,
+    instances: Option<String>,
+    moved_fields: Vec<MovedField>,
+}
+
+/// A private field `Tree::end_assigned_field` moved to the constructor as
+/// `#a=1;` or `#a;`.
+#[derive(Clone)]
+struct MovedField {
+    name: usize,
+    value: Option<(usize, usize)>,
+    // After its `;`.
+    end: usize,
+}
+
+impl Member {
+    // Fields and static blocks run outside the class body, in order, and
+    // private methods and accessors become functions next to it.
+    fn is_moved(&self) -> bool {
+        matches!(self.kind, MemberKind::Field | MemberKind::StaticBlock) || self.is_private
+    }
+}
+
+impl Class {
+    fn has_static_setup(&self) -> bool {
+        self.members
+            .iter()
+            .any(|member| member.is_static && member.is_moved())
+    }
+}
+
+struct Lowering<'a> {
+    tokens: Vec<String>,
+    options: &'a Options,
+    // The outermost chain that ends at each index.
+    chain_starts: HashMap<usize, usize>,
+    // The `in` of `for (a in b)`, which isn't an operator.
+    for_in: HashSet<usize>,
+    constructs: Vec<(usize, Construct)>,
+    // The constructs that start at each index, the outermost first.
+    starts: HashMap<usize, Vec<usize>>,
+    classes: Vec<Class>,
+    scopes: Vec<Scope>,
+    // Variables to declare by the start of their scope.
+    declarations: HashMap<usize, Vec<String>>,
+    names: HashSet<String>,
+    temporaries: usize,
+    helpers: Vec<&'static str>,
+    // What `this` is in a static field initializer, which runs outside the
+    // class once it is lowered.
+    this_reference: Option<String>,
+}
+
+// `"use strict";` at `start` is a directive, which has to stay first.
+fn directive_end(tokens: &[String], start: usize) -> usize {
+    match (tokens.get(start), tokens.get(start + 1)) {
+        (Some(token), Some(next)) if token.starts_with(['"', '\'']) && next == ";" => start + 2,
+        _ => start,
+    }
+}
+
+fn push(tokens: &mut Vec<String>, text: &str) {
+    tokens.extend(tokenize(text));
+}
+
+// Whether `tokens` have operators outside of brackets, so they need to be
+// put in parentheses to be an operand.
+fn has_operators(tokens: &[String]) -> bool {
+    let mut depth = 0;
+    tokens.iter().any(|token| {
+        if is_opening(token) && token != "<" {
+            depth += 1;
+        } else if is_closing(token) && token != ">" {
+            depth -= 1;
+        } else {
+            return depth == 0 && !is_operand(token) && !matches!(token.as_str(), "." | "?.");
+        }
+        false
+    })
+}
+
+fn is_literal(tokens: &[String]) -> bool {
+    matches!(tokens, [token] if token.starts_with(|char: char| char.is_ascii_digit() || char == '"' || char == '\''))
+}
+
+/// Rewrites the syntax `options.target` doesn't have yet in a written module
+/// the way `tsc` does: `a ** b` as `Math.pow(a, b)`, `a?.b` and `a ?? b` as
+/// conditionals on `null` and `undefined`, `a ||= b` as `a || (a = b)`, class
+/// fields as assignments after the class is defined, and private members
+/// through WeakMaps and the `__classPrivateField` helpers. Returns the module
+/// and the helpers it now uses.
+pub fn downlevel(output: &str, options: &Options) -> (String, Vec<&'static str>) {
+    let tokens = join_operators(tokenize(output));
+    let names = tokens
+        .iter()
+        .filter(|token| is_identifier(token))
+        .cloned()
+        .collect();
+    let mut lowering = Lowering {
+        tokens,
+        options,
+        chain_starts: HashMap::new(),
+        for_in: HashSet::new(),
+        constructs: vec![],
+        starts: HashMap::new(),
+        classes: vec![],
+        scopes: vec![],
+        declarations: HashMap::new(),
+        names,
+        temporaries: 0,
+        helpers: vec![],
+        this_reference: None,
+    };
+    lowering.find_for_in();
+    lowering.find_classes();
+    lowering.find_scopes();
+    lowering.name_classes();
+    lowering.find_constructs();
+
+    let mut tokens = lowering.emit(0, lowering.tokens.len());
+    if let Some(names) = lowering.declarations.remove(&MODULE) {
+        let start = directive_end(&tokens, 0);
+        tokens.splice(start..start, declaration(names));
+    }
+    (join(&tokens), lowering.helpers)
+}
+
+impl Lowering<'_> {
+    fn token(&self, index: usize) -> &str {
+        self.tokens.get(index).map_or("", String::as_str)
+    }
+
+    // The token before `index`, or `None` when a line break ends the
+    // statement in between.
+    fn previous(&self, index: usize) -> Option<usize> {
+        let mut previous = index.checked_sub(1)?;
+        if self.tokens[previous] != "\n" {
+            return Some(previous);
+        }
+        if !is_line_continued(self.token(index)) {
+            return None;
+        }
+        while self.tokens[previous] == "\n" {
+            previous = previous.checked_sub(1)?;
+        }
+        Some(previous)
+    }
+
+    // `index`, or the token after the line breaks at it when the statement
+    // goes on after them.
+    fn next(&self, index: usize) -> usize {
+        let mut next = index;
+        while self.token(next) == "\n" {
+            next += 1;
+        }
+        match is_line_continued(self.token(next)) {
+            true => next,
+            false => index,
+        }
+    }
+
+    fn matching_open(&self, close: usize) -> usize {
+        let mut depth = 0;
+        for index in (0..=close).rev() {
+            let token = self.token(index);
+            if is_closing(token) && token != ">" {
+                depth += 1;
+            } else if is_opening(token) && token != "<" {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+        }
+        close
+    }
+
+    // Where the expression that starts at `start` ends: at a `,`, `;`,
+    // closing bracket or `:` of a conditional around it, or a line break
+    // that ends the statement.
+    fn assignment_end(&self, start: usize) -> usize {
+        let mut index = start;
+        let mut conditionals = 0;
+        while index < self.tokens.len() {
+            let token = self.token(index);
+            match token {
+                "," | ";" | ")" | "]" | "}" => break,
+                ":" if conditionals == 0 => break,
+                ":" => conditionals -= 1,
+                "?" => conditionals += 1,
+                "\n" if !is_line_continued(self.token(self.next(index))) => break,
+                _ if token.starts_with('}') => break,
+                _ if is_opening(token) && token != "<" => {
+                    index = matching_close(&self.tokens, index);
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+        index
+    }
+
+    fn add(&mut self, start: usize, end: usize, construct: Construct) {
+        let id = self.constructs.len();
+        self.constructs.push((end, construct));
+        let starts = self.starts.entry(start).or_default();
+        let position = starts
+            .iter()
+            .position(|other| self.constructs[*other].0 < end)
+            .unwrap_or(starts.len());
+        starts.insert(position, id);
+    }
+
+    fn find_for_in(&mut self) {
+        for index in 0..self.tokens.len() {
+            let mut opening = index + 1;
+            if self.token(opening) == "await" {
+                opening += 1;
+            }
+            if self.tokens[index] != "for" || self.token(opening) != "(" {
+                continue;
+            }
+            let close = matching_close(&self.tokens, opening);
+            let mut position = opening + 1;
+            while position < close {
+                let token = self.token(position);
+                match token {
+                    ";" => break,
+                    "in" => {
+                        self.for_in.insert(position);
+                        break;
+                    }
+                    _ if is_opening(token) && token != "<" => {
+                        position = matching_close(&self.tokens, position);
+                    }
+                    _ => {}
+                }
+                position += 1;
+            }
+        }
+    }
+
+    fn find_classes(&mut self) {
+        for index in 0..self.tokens.len() {
+            let is_keyword = self.tokens[index] == "class"
+                && !matches!(self.token(index.wrapping_sub(1)), "." | "?.")
+                && self.token(index + 1) != ":";
+            if let Some(class) = is_keyword.then(|| self.parse_class(index)).flatten() {
+                self.classes.push(class);
+            }
+        }
+    }
+
+    fn parse_class(&self, start: usize) -> Option<Class> {
+        let mut index = start + 1;
+        let name = match self.token(index) {
+            "extends" | "{" => None,
+            name => {
+                index += 1;
+                Some(name.to_string())
+            }
+        };
+        let is_derived = self.token(index) == "extends";
+        while self.token(index) != "{" {
+            let token = self.tokens.get(index)?;
+            if matches!(token.as_str(), "(" | "[") || is_template_head(token) {
+                index = matching_close(&self.tokens, index);
+            }
+            index += 1;
+        }
+        let close = matching_close(&self.tokens, index);
+        let is_declaration = match self.previous(start).map(|previous| self.token(previous)) {
+            None | Some(";" | "{" | "}" | "export") => true,
+            Some("default") => name.is_some(),
+            Some(_) => false,
+        };
+        Some(Class {
+            start,
+            name,
+            body: index,
+            close,
+            members: self.parse_members(index, close),
+            is_declaration,
+            is_derived,
+            is_lowered: false,
+            reference: None,
+            inferred_name: None,
+            private_names: vec![],
+            instances: None,
+            moved_fields: vec![],
+        })
+    }
+
+    fn parse_members(&self, body: usize, close: usize) -> Vec<Member> {
+        let mut members = vec![];
+        let mut index = body + 1;
+        while index < close {
+            if matches!(self.token(index), ";" | "\n") {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            let (mut is_static, mut is_async, mut is_generator) = (false, false, false);
+            let mut accessor = None;
+            loop {
+                let token = self.token(index);
+                let is_modifier = token == "*"
+                    || (matches!(token, "static" | "async" | "get" | "set")
+                        && !matches!(self.token(index + 1), "(" | "=" | ";" | "}" | "\n"));
+                if !is_modifier {
+                    break;
+                }
+                match token {
+                    "static" => is_static = true,
+                    "async" => is_async = true,
+                    "*" => is_generator = true,
+                    _ => accessor = Some(token),
+                }
+                index += 1;
+            }
+            let name = index;
+            let mut member = Member {
+                start,
+                end: index + 1,
+                is_static,
+                is_async,
+                is_generator,
+                is_private: self.token(name).starts_with('#'),
+                is_constructor: false,
+                kind: MemberKind::Field,
+                name,
+                name_end: index + 1,
+                initializer: None,
+            };
+            if self.token(name) == "{" {
+                member.kind = MemberKind::StaticBlock;
+                member.end = matching_close(&self.tokens, name) + 1;
+            } else {
+                if self.token(name) == "[" {
+                    member.name_end = matching_close(&self.tokens, name) + 1;
+                }
+                if self.token(member.name_end) == "(" {
+                    let body = matching_close(&self.tokens, member.name_end) + 1;
+                    member.end = matching_close(&self.tokens, body) + 1;
+                    member.kind = match accessor {
+                        Some("get") => MemberKind::Getter,
+                        Some("set") => MemberKind::Setter,
+                        _ => MemberKind::Method,
+                    };
+                    member.is_constructor = !is_static
+                        && matches!(
+                            self.token(name),
+                            "constructor" | "\"constructor\"" | "'constructor'"
+                        );
+                } else {
+                    member.end = member.name_end;
+                    if self.token(member.name_end) == "=" {
+                        let value_end = self.assignment_end(member.name_end + 1).min(close);
+                        member.initializer = Some((member.name_end + 1, value_end));
+                        member.end = value_end;
+                    }
+                    if self.token(member.end) == ";" {
+                        member.end += 1;
+                    }
+                }
+            }
+            index = member.end.max(start + 1);
+            members.push(member);
+        }
+        members
+    }
+
+    // Function bodies, which temporary variables are declared in.
+    fn find_scopes(&mut self) {
+        let class_bodies: HashSet<usize> = self.classes.iter().map(|class| class.body).collect();
+        for index in 0..self.tokens.len() {
+            let previous = self.token(index.wrapping_sub(1));
+            let scope = match self.token(index) {
+                "{" if class_bodies.contains(&index) => continue,
+                "{" if previous == "=>" => (matching_close(&self.tokens, index) + 1, false),
+                "{" if previous == ")" => {
+                    let opening = self.matching_open(index - 1);
+                    let keyword = self.token(opening.wrapping_sub(1));
+                    if matches!(
+                        keyword,
+                        "if" | "for" | "while" | "switch" | "catch" | "with"
+                    ) {
+                        continue;
+                    }
+                    (matching_close(&self.tokens, index) + 1, false)
+                }
+                _ if previous == "=>" => (self.assignment_end(index), true),
+                _ => continue,
+            };
+            let scope = Scope {
+                start: index,
+                end: scope.0,
+                is_expression: scope.1,
+                is_arrow: previous == "=>",
+            };
+            self.add(scope.start, scope.end, Construct::Scope(self.scopes.len()));
+            self.scopes.push(scope);
+        }
+    }
+
+    fn scope_at(&self, position: usize) -> usize {
+        self.scopes
+            .iter()
+            .filter(|scope| scope.start <= position && position < scope.end)
+            .map(|scope| scope.start)
+            .max()
+            .unwrap_or(MODULE)
+    }
+
+    fn declare(&mut self, position: usize, name: String) {
+        let scope = self.scope_at(position);
+        self.declarations.entry(scope).or_default().push(name);
+    }
+
+    fn unique_name(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut count = 1;
+        while !self.names.insert(unique.clone()) {
+            unique = format!("{}_{}", name, count);
+            count += 1;
+        }
+        unique
+    }
+
+    // `_a`, `_b` and so on, declared in the function around `position`.
+    fn temporary(&mut self, position: usize) -> String {
+        loop {
+            let letter = char::from(b'a' + (self.temporaries % 26) as u8);
+            let name = match self.temporaries / 26 {
+                0 => format!("_{}", letter),
+                round => format!("_{}{}", letter, round),
+            };
+            self.temporaries += 1;
+            if self.names.insert(name.clone()) {
+                self.declare(position, name.clone());
+                return name;
+            }
+        }
+    }
+
+    // An expression that is needed twice, as `(_a=value)` the first time and
+    // `_a` after that, unless it's a name that can just be read again.
+    fn reuse(&mut self, value: Vec<String>, position: usize) -> (Vec<String>, Vec<String>) {
+        if matches!(value.as_slice(), [name] if is_identifier(name) && !KEYWORDS.contains(&name.as_str()))
+        {
+            return (value.clone(), value);
+        }
+        let temporary = self.temporary(position);
+        let mut assigned = vec![String::from("("), temporary.clone(), String::from("=")];
+        assigned.extend(value);
+        assigned.push(String::from(")"));
+        (assigned, vec![temporary])
+    }
+
+    fn use_helper(&mut self, helper: &'static str) {
+        if !self.helpers.contains(&helper) {
+            self.helpers.push(helper);
+        }
+    }
+
+    // The private names of a lowered class, and the constructors of the ones
+    // with fields `Tree::end_assigned_field` moved there.
+    fn name_classes(&mut self) {
+        for index in 0..self.classes.len() {
+            let mut class = self.classes[index].clone();
+            if let Some(constructor) = class.members.iter().find(|member| member.is_constructor) {
+                let body = matching_close(&self.tokens, constructor.name_end) + 1;
+                class.moved_fields = self.moved_fields(body);
+            }
+            class.is_lowered = self.options.target < Target::Es2022
+                && (!class.moved_fields.is_empty() || class.members.iter().any(Member::is_moved));
+            if !class.is_lowered {
+                continue;
+            }
+            class.inferred_name = self
+                .previous(class.start)
+                .filter(|equals| class.name.is_none() && self.tokens[*equals] == "=")
+                .and_then(|equals| self.previous(equals))
+                .filter(|name| self.token(name.wrapping_sub(1)) != ".")
+                .map(|name| self.tokens[name].clone())
+                .filter(|name| is_operand(name) && is_identifier(name));
+            // `export default class {}` is named `default_1` like `tsc` does,
+            // so it can stay a declaration.
+            let is_default_export = self
+                .previous(class.start)
+                .is_some_and(|previous| self.tokens[previous] == "default");
+            if class.name.is_none() && is_default_export {
+                let name = self.unique_name(String::from("default_1"));
+                class.name = Some(name.clone());
+                class.inferred_name = Some(name);
+                class.is_declaration = true;
+            }
+            // Naming the class itself would shadow the variable in it.
+            let defines_name = class
+                .inferred_name
+                .as_ref()
+                .is_some_and(|name| self.tokens[class.start..class.close].contains(name));
+            if class.has_static_setup() || defines_name {
+                class.reference = match (&class.name, class.is_declaration) {
+                    (Some(name), true) => Some(name.clone()),
+                    _ => Some(self.temporary(class.start)),
+                };
+            }
+            let reference = class.reference.clone().unwrap_or_default();
+            let prefix = match &class.name {
+                Some(name) => format!("_{}_", name),
+                None => String::from("_"),
+            };
+            let has_instance_methods = class.members.iter().any(|member| {
+                member.is_private
+                    && !member.is_static
+                    && !matches!(member.kind, MemberKind::Field | MemberKind::StaticBlock)
+            });
+            if has_instance_methods {
+                let instances = self.unique_name(format!("{}instances", prefix));
+                self.declare(class.start, instances.clone());
+                class.instances = Some(instances);
+            }
+
+            let moved_fields = class
+                .moved_fields
+                .iter()
+                .map(|field| (field.name, false, MemberKind::Field));
+            let private_members: Vec<_> = moved_fields
+                .chain(
+                    class
+                        .members
+                        .iter()
+                        .filter(|member| member.is_private)
+                        .map(|member| (member.name, member.is_static, member.kind)),
+                )
+                .collect();
+            for (name, is_static, kind) in private_members {
+                let name = self.tokens[name].clone();
+                let variable = match kind {
+                    MemberKind::Getter => format!("{}{}_get", prefix, &name[1..]),
+                    MemberKind::Setter => format!("{}{}_set", prefix, &name[1..]),
+                    _ => format!("{}{}", prefix, &name[1..]),
+                };
+                let variable = self.unique_name(variable);
+                let is_function = kind != MemberKind::Field;
+                if !is_function || !class.is_declaration {
+                    self.declare(class.start, variable.clone());
+                }
+                let state = match (is_static, &class.instances) {
+                    (true, _) => reference.clone(),
+                    (false, Some(instances)) if is_function => instances.clone(),
+                    (false, _) => variable.clone(),
+                };
+                let existing = class
+                    .private_names
+                    .iter_mut()
+                    .find(|(existing, _)| *existing == name);
+                match (kind, existing) {
+                    (MemberKind::Getter, Some((_, private))) => private.getter = Some(variable),
+                    (MemberKind::Setter, Some((_, private))) => private.setter = Some(variable),
+                    _ => {
+                        let (kind, getter, setter) = match (kind, is_static) {
+                            (MemberKind::Field, false) => ("f", None, None),
+                            (MemberKind::Field, true) => {
+                                ("f", Some(variable.clone()), Some(variable))
+                            }
+                            (MemberKind::Getter, _) => ("a", Some(variable), None),
+                            (MemberKind::Setter, _) => ("a", None, Some(variable)),
+                            _ => ("m", Some(variable), None),
+                        };
+                        let private = PrivateName {
+                            state,
+                            kind,
+                            getter,
+                            setter,
+                        };
+                        class.private_names.push((name, private));
+                    }
+                }
+            }
+
+            for field in class.moved_fields.clone() {
+                let initialization = Construct::PrivateInitialization(field.name, field.value);
+                self.add(field.name, field.end, initialization);
+            }
+            self.add(class.start, class.close + 1, Construct::Class(index));
+            self.classes[index] = class;
+        }
+    }
+
+    fn moved_fields(&self, body: usize) -> Vec<MovedField> {
+        let close = matching_close(&self.tokens, body);
+        let mut fields = vec![];
+        let mut index = body + 1;
+        while index < close {
+            let token = self.token(index);
+            if token.starts_with('#') && matches!(self.token(index - 1), "{" | ";") {
+                let (value, end) = match self.token(index + 1) {
+                    "=" => {
+                        let end = self.assignment_end(index + 2);
+                        (Some((index + 2, end)), end)
+                    }
+                    _ => (None, index + 1),
+                };
+                let end = end + usize::from(self.token(end) == ";");
+                fields.push(MovedField {
+                    name: index,
+                    value,
+                    end,
+                });
+                index = end;
+                continue;
+            }
+            if is_opening(token) && token != "<" {
+                index = matching_close(&self.tokens, index);
+            }
+            index += 1;
+        }
+        fields
+    }
+
+    fn is_private(&self, part: &Part) -> bool {
+        self.options.target < Target::Es2022
+            && matches!(part.kind, PartKind::Member(name) if self.tokens[name].starts_with('#'))
+    }
+
+    // Optional parts are lowered along with private ones, which can't be
+    // read on their own.
+    fn lowers_optional(&self, chain: &Chain) -> bool {
+        self.options.target < Target::Es2020 || chain.parts.iter().any(|part| self.is_private(part))
+    }
+
+    fn is_chain_start(&self, index: usize) -> bool {
+        let token = self.token(index);
+        let previous = self.previous(index).map(|previous| self.token(previous));
+        if token == "\n" || matches!(previous, Some("." | "?." | "new")) {
+            return false;
+        }
+        match token {
+            "(" | "[" => previous.is_none_or(|previous| !ends_expression(previous)),
+            "{" => matches!(
+                previous,
+                Some("=" | "(" | "[" | "," | ":" | "?" | "return" | "??" | "||" | "&&")
+            ),
+            _ if token.starts_with('`') => {
+                previous.is_none_or(|previous| !ends_expression(previous))
+            }
+            _ => true,
+        }
+    }
+
+    // Where the expression a chain starting at `start` is applied to ends.
+    fn base_end(&self, start: usize) -> Option<usize> {
+        let token = self.token(start);
+        match token {
+            "new" if self.token(start + 1) == "." => Some(start + 1),
+            "new" => {
+                let mut index = self.base_end(start + 1)?;
+                while matches!(self.token(index), "." | "[") {
+                    index = match self.token(index) {
+                        "." => index + 2,
+                        _ => matching_close(&self.tokens, index) + 1,
+                    };
+                }
+                if self.token(index) == "(" {
+                    index = matching_close(&self.tokens, index) + 1;
+                }
+                Some(index)
+            }
+            "async" if self.token(start + 1) == "function" => self.base_end(start + 1),
+            "function" => {
+                let opening =
+                    (start..self.tokens.len()).find(|index| self.tokens[*index] == "(")?;
+                let body = matching_close(&self.tokens, opening) + 1;
+                Some(matching_close(&self.tokens, body) + 1)
+            }
+            "class" => self
+                .classes
+                .iter()
+                .find(|class| class.start == start)
+                .map(|class| class.close + 1),
+            "(" | "[" | "{" => Some(matching_close(&self.tokens, start) + 1),
+            _ if is_template_head(token) => Some(matching_close(&self.tokens, start) + 1),
+            _ if is_operand(token) => Some(start + 1),
+            _ => None,
+        }
+    }
+
+    fn parse_chain(&self, start: usize) -> Option<Chain> {
+        let base_end = self.base_end(start)?;
+        let mut parts = vec![];
+        let mut index = base_end;
+        loop {
+            let next = self.next(index);
+            let (is_optional, position) = match self.token(next) {
+                "?." => (true, next + 1),
+                _ => (false, next),
+            };
+            let token = self.token(position);
+            let kind = match token {
+                "." if !is_optional => PartKind::Member(position + 1),
+                "(" => PartKind::Call(position, matching_close(&self.tokens, position)),
+                "[" => PartKind::Computed(position, matching_close(&self.tokens, position)),
+                _ if is_optional => PartKind::Member(position),
+                _ if is_template_head(token) => {
+                    PartKind::Template(position, matching_close(&self.tokens, position))
+                }
+                _ if token.starts_with('`') => PartKind::Template(position, position),
+                _ => break,
+            };
+            index = match kind {
+                PartKind::Member(name) => name + 1,
+                PartKind::Computed(_, close)
+                | PartKind::Call(_, close)
+                | PartKind::Template(_, close) => close + 1,
+            };
+            parts.push(Part { is_optional, kind });
+        }
+        Some(Chain {
+            base: (start, base_end),
+            parts,
+            end: index,
+        })
+    }
+
+    fn binds(&self, index: usize, level: u8, is_same_level: bool) -> bool {
+        !self.for_in.contains(&index)
+            && precedence(self.token(index))
+                .is_some_and(|other| other > level || (is_same_level && other == level))
+    }
+
+    // The first token of the unary expression that ends before `end`.
+    fn unary_before(&self, end: usize) -> Option<usize> {
+        let last = self.previous(end)?;
+        let operand_end = match self.token(last) {
+            "++" | "--" => last,
+            _ => last + 1,
+        };
+        let mut start = *self.chain_starts.get(&operand_end)?;
+        while let Some(previous) = self.previous(start) {
+            let is_prefix = match self.token(previous) {
+                "+" | "-" | "++" | "--" => self
+                    .previous(previous)
+                    .is_none_or(|before| !ends_expression(self.token(before))),
+                token => UNARY_OPERATORS.contains(&token),
+            };
+            if !is_prefix {
+                break;
+            }
+            start = previous;
+        }
+        Some(start)
+    }
+
+    // Where the left operand of the binary operator at `operator` starts,
+    // taking in the operators around it that bind tighter.
+    fn left_operand(&self, operator: usize, level: u8, is_left_associative: bool) -> usize {
+        let mut operator = operator;
+        let mut start = operator;
+        while let Some(operand) = self.unary_before(operator) {
+            start = operand;
+            match self.previous(operand) {
+                Some(previous) if self.binds(previous, level, is_left_associative) => {
+                    operator = previous;
+                }
+                _ => break,
+            }
+        }
+        start
+    }
+
+    fn unary_after(&self, start: usize) -> Option<usize> {
+        let mut index = start;
+        while UNARY_OPERATORS.contains(&self.token(index)) {
+            index += 1;
+        }
+        let chain = self.parse_chain(index)?;
+        Some(match self.token(chain.end) {
+            "++" | "--" => chain.end + 1,
+            _ => chain.end,
+        })
+    }
+
+    // Where the right operand of the binary operator before `start` ends.
+    fn right_operand(&self, start: usize, level: u8, is_right_associative: bool) -> usize {
+        let mut end = start;
+        let mut index = start;
+        while let Some(operand_end) = self.unary_after(index) {
+            end = operand_end;
+            let next = self.next(end);
+            if !self.binds(next, level, is_right_associative) {
+                break;
+            }
+            index = next + 1;
+        }
+        end
+    }
+
+    fn find_constructs(&mut self) {
+        let target = self.options.target;
+        for index in 0..self.tokens.len() {
+            let Some(chain) = self
+                .is_chain_start(index)
+                .then(|| self.parse_chain(index))
+                .flatten()
+            else {
+                continue;
+            };
+            self.chain_starts.entry(chain.end).or_insert(index);
+            let has_optional = chain.parts.iter().any(|part| part.is_optional);
+            let is_lowered = chain.parts.iter().any(|part| self.is_private(part))
+                || (has_optional && target < Target::Es2020);
+            let is_private_target = chain.parts.last().is_some_and(|part| self.is_private(part))
+                && self.is_destructuring_target(index, chain.end);
+            if is_private_target {
+                let end = chain.end;
+                self.add(index, end, Construct::PrivateTarget(chain));
+            } else if is_lowered {
+                let delete = self
+                    .previous(index)
+                    .filter(|previous| has_optional && self.tokens[*previous] == "delete");
+                let end = chain.end;
+                self.add(
+                    delete.unwrap_or(index),
+                    end,
+                    Construct::Chain(chain, delete.is_some()),
+                );
+            }
+        }
+
+        for index in 0..self.tokens.len() {
+            match self.tokens[index].as_str() {
+                "**" if target < Target::Es2016 => {
+                    let start = self.left_operand(index, 11, false);
+                    let end = self.right_operand(index + 1, 11, true);
+                    self.add(start, end, Construct::Exponent(index));
+                }
+                "??" if target < Target::Es2020 => {
+                    let start = self.left_operand(index, 1, true);
+                    let end = self.right_operand(index + 1, 1, false);
+                    self.add(start, end, Construct::Nullish(index));
+                }
+                "in" if target < Target::Es2022 && self.tokens[index - 1].starts_with('#') => {
+                    let end = self.right_operand(index + 1, 7, false);
+                    self.add(index - 1, end, Construct::PrivateIn(index - 1));
+                }
+                "++" | "--" if target < Target::Es2022 => self.find_update(index),
+                operator if ASSIGNMENT_OPERATORS.contains(&operator) => {
+                    self.find_assignment(index);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn find_assignment(&mut self, operator: usize) {
+        let Some(chain) = self
+            .previous(operator)
+            .and_then(|previous| self.chain_starts.get(&(previous + 1)))
+            .and_then(|start| self.parse_chain(*start))
+        else {
+            return;
+        };
+        let target = self.options.target;
+        let is_lowered = match self.tokens[operator].as_str() {
+            "**=" => target < Target::Es2016,
+            "&&=" | "||=" | "??=" => target < Target::Es2021,
+            _ => false,
+        };
+        // `[a.#b=1]=c`, where `=1` is a default value.
+        let is_private = chain.parts.last().is_some_and(|part| self.is_private(part))
+            && !self.is_destructuring_target(chain.base.0, operator);
+        if is_lowered || is_private {
+            let end = self.assignment_end(operator + 1);
+            self.add(chain.base.0, end, Construct::Assignment(chain, operator));
+        }
+    }
+
+    // Whether the expression from `start` to `end` is assigned to by the
+    // destructuring pattern it is an element of, like `[a]=b` or `{b:a}=c`,
+    // or by a `for…of` or `for…in` loop.
+    fn is_destructuring_target(&self, start: usize, end: usize) -> bool {
+        let before = self
+            .previous(start)
+            .map_or("", |previous| self.token(previous));
+        let after = self.token(self.next(end));
+        if self.is_for_head(start, end) {
+            return true;
+        }
+        if !matches!(before, "[" | "," | "..." | ":") || !matches!(after, "," | "]" | "}" | "=") {
+            return false;
+        }
+        match self.enclosing_bracket(start) {
+            Some(opening) => self.is_pattern(opening),
+            None => false,
+        }
+    }
+
+    // `for(a of b)`, where `a` goes from `start` to `end`.
+    fn is_for_head(&self, start: usize, end: usize) -> bool {
+        let Some(opening) = self
+            .previous(start)
+            .filter(|previous| self.token(*previous) == "(")
+        else {
+            return false;
+        };
+        let keyword = self
+            .previous(opening)
+            .map_or("", |previous| self.token(previous));
+        let keyword = match keyword {
+            "await" => self
+                .previous(opening - 1)
+                .map_or("", |previous| self.token(previous)),
+            _ => keyword,
+        };
+        keyword == "for" && matches!(self.token(self.next(end)), "of" | "in")
+    }
+
+    // The `[` or `{` that `position` is directly inside of.
+    fn enclosing_bracket(&self, position: usize) -> Option<usize> {
+        let mut index = position;
+        while index > 0 {
+            index -= 1;
+            let token = self.token(index);
+            if is_closing(token) && token != ">" {
+                index = self.matching_open(index);
+            } else if matches!(token, "[" | "{") {
+                return Some(index);
+            } else if matches!(token, "(" | ";") {
+                return None;
+            }
+        }
+        None
+    }
+
+    // Whether the array or object literal opened at `opening` is a pattern
+    // that is assigned to, rather than a value.
+    fn is_pattern(&self, opening: usize) -> bool {
+        let before = self
+            .previous(opening)
+            .map_or("", |previous| self.token(previous));
+        let is_literal = match self.token(opening) {
+            "[" => !ends_expression(before),
+            _ => matches!(before, "" | "(" | "[" | "," | ":" | "..." | "="),
+        };
+        if !is_literal {
+            return false;
+        }
+        let close = matching_close(&self.tokens, opening) + 1;
+        match self.token(self.next(close)) {
+            "=" => true,
+            "of" | "in" => self.is_for_head(opening, close),
+            "," | "]" | "}" => self.is_destructuring_target(opening, close),
+            _ => false,
+        }
+    }
+
+    // `a.#b++` and `++a.#b`.
+    fn find_update(&mut self, operator: usize) {
+        let postfix = match self.tokens[operator - 1].as_str() {
+            "\n" => None,
+            _ => self.chain_starts.get(&operator),
+        };
+        let (start, chain) = match postfix.and_then(|start| self.parse_chain(*start)) {
+            Some(chain) => (chain.base.0, Some(chain)),
+            None => (operator, self.parse_chain(operator + 1)),
+        };
+        let Some(chain) =
+            chain.filter(|chain| chain.parts.last().is_some_and(|part| self.is_private(part)))
+        else {
+            return;
+        };
+        let end = chain.end.max(operator + 1);
+        self.add(start, end, Construct::Update(chain, operator));
+    }
+
+    fn private_name(&self, position: usize) -> Option<PrivateName> {
+        let name = self.token(position);
+        self.classes
+            .iter()
+            .filter(|class| class.body < position && position < class.close)
+            .filter_map(|class| {
+                let private = class.private_names.iter().find(|(other, _)| other == name);
+                private.map(|(_, private)| (class.body, private))
+            })
+            .max_by_key(|(body, _)| *body)
+            .map(|(_, private)| private.clone())
+    }
+
+    fn private_get(&mut self, object: Vec<String>, name: usize) -> Vec<String> {
+        let mut value = vec![];
+        let Some(private) = self.private_name(name) else {
+            value.extend(object);
+            value.push(String::from("."));
+            value.push(self.tokens[name].clone());
+            return value;
+        };
+        self.use_helper("__classPrivateFieldGet");
+        push(&mut value, "__classPrivateFieldGet(");
+        value.extend(object);
+        value.push(String::from(","));
+        value.push(private.state);
+        value.push(String::from(","));
+        value.push(format!("\"{}\"", private.kind));
+        if let Some(getter) = private.getter {
+            value.push(String::from(","));
+            value.push(getter);
+        }
+        value.push(String::from(")"));
+        value
+    }
+
+    fn private_set(
+        &mut self,
+        object: Vec<String>,
+        name: usize,
+        assigned: Vec<String>,
+    ) -> Vec<String> {
+        let mut value = vec![];
+        let Some(private) = self.private_name(name) else {
+            value.extend(object);
+            value.push(String::from("."));
+            value.push(self.tokens[name].clone());
+            value.push(String::from("="));
+            value.extend(assigned);
+            return value;
+        };
+        self.use_helper("__classPrivateFieldSet");
+        push(&mut value, "__classPrivateFieldSet(");
+        value.extend(object);
+        value.push(String::from(","));
+        value.push(private.state);
+        value.push(String::from(","));
+        value.extend(assigned);
+        value.push(String::from(","));
+        value.push(format!("\"{}\"", private.kind));
+        if let Some(setter) = private.setter {
+            value.push(String::from(","));
+            value.push(setter);
+        }
+        value.push(String::from(")"));
+        value
+    }
+
+    fn exponent(&self, left: Vec<String>, right: Vec<String>) -> Vec<String> {
+        let mut value = tokenize("Math.pow(");
+        value.extend(left);
+        value.push(String::from(","));
+        value.extend(right);
+        value.push(String::from(")"));
+        value
+    }
+
+    fn nullish(&mut self, left: Vec<String>, right: Vec<String>, position: usize) -> Vec<String> {
+        let (assigned, reference) = self.reuse(left, position);
+        let mut value = vec![String::from("(")];
+        value.extend(assigned);
+        push(&mut value, "!==null&&");
+        value.extend(reference.clone());
+        push(&mut value, "!==void 0?");
+        value.extend(reference);
+        value.push(String::from(":"));
+        value.extend(right);
+        value.push(String::from(")"));
+        value
+    }
+
+    fn emit(&mut self, start: usize, end: usize) -> Vec<String> {
+        self.emit_excluding(start, end, None)
+    }
+
+    // The tokens from `start` to `end` with the constructs in them lowered,
+    // other than `excluded`, which is being lowered and starts there.
+    fn emit_excluding(&mut self, start: usize, end: usize, excluded: Option<usize>) -> Vec<String> {
+        let mut tokens: Vec<String> = vec![];
+        let mut excluded = excluded;
+        let mut index = start;
+        while index < end {
+            let construct = self.starts.get(&index).and_then(|ids| {
+                ids.iter()
+                    .copied()
+                    .find(|id| Some(*id) != excluded && self.constructs[*id].0 <= end)
+            });
+            excluded = None;
+            if let Some(id) = construct {
+                let lowered = self.emit_construct(id, index);
+                // A statement that now starts with a parenthesis would be
+                // called by the one before it.
+                if lowered.first().is_some_and(|token| token == "(")
+                    && tokens.last().is_some_and(|token| token == "\n")
+                {
+                    *tokens.last_mut().expect("Checked above") = String::from(";");
+                }
+                tokens.extend(lowered);
+                index = self.constructs[id].0;
+                continue;
+            }
+            let token = &self.tokens[index];
+            let token = match &self.this_reference {
+                Some(reference) if token == "this" => reference.clone(),
+                _ if self.options.target < Target::Es2021
+                    && token.starts_with(|char: char| char.is_ascii_digit()) =>
+                {
+                    token.replace('_', "")
+                }
+                _ => token.clone(),
+            };
+            tokens.push(token);
+            index += 1;
+        }
+        tokens
+    }
+
+    fn emit_construct(&mut self, id: usize, start: usize) -> Vec<String> {
+        let (end, construct) = self.constructs[id].clone();
+        match construct {
+            Construct::Exponent(operator) => {
+                let left = self.emit(start, operator);
+                let right = self.emit(operator + 1, end);
+                self.exponent(left, right)
+            }
+            Construct::Nullish(operator) => {
+                let left = self.emit(start, operator);
+                let right = self.emit(operator + 1, end);
+                self.nullish(left, right, start)
+            }
+            Construct::Assignment(chain, operator) => self.emit_assignment(&chain, operator, end),
+            Construct::Update(chain, operator) => self.emit_update(&chain, operator, start, end),
+            Construct::PrivateTarget(chain) => self.emit_private_target(&chain),
+            Construct::Chain(chain, is_delete) => {
+                self.lower_chain(&chain, chain.parts.len(), false, is_delete)
+                    .0
+            }
+            Construct::PrivateIn(name) => {
+                let Some(private) = self.private_name(name) else {
+                    return self.emit_excluding(start, end, Some(id));
+                };
+                self.use_helper("__classPrivateFieldIn");
+                let mut value = tokenize("__classPrivateFieldIn(");
+                value.push(private.state);
+                value.push(String::from(","));
+                value.extend(self.emit(name + 2, end));
+                value.push(String::from(")"));
+                value
+            }
+            Construct::PrivateInitialization(name, initializer) => {
+                let private = self.private_name(name).expect("Named by name_classes");
+                let mut value = vec![private.state];
+                push(&mut value, ".set(this,");
+                match initializer {
+                    Some((start, end)) => value.extend(self.emit(start, end)),
+                    None => push(&mut value, "void 0"),
+                }
+                push(&mut value, ");");
+                value
+            }
+            Construct::Class(index) => self.emit_class(index),
+            Construct::Scope(index) => self.emit_scope(id, index),
+        }
+    }
+
+    // The chain up to `count` parts. When `capture` is set, the object of
+    // the last part, a member access, is also returned to call it on.
+    fn lower_chain(
+        &mut self,
+        chain: &Chain,
+        count: usize,
+        capture: bool,
+        is_delete: bool,
+    ) -> (Vec<String>, Option<Vec<String>>) {
+        let optional = match self.lowers_optional(chain) {
+            true => chain.parts[..count]
+                .iter()
+                .rposition(|part| part.is_optional),
+            false => None,
+        };
+        let Some(optional) = optional else {
+            let base = self.emit(chain.base.0, chain.base.1);
+            let (mut value, this) = self.apply_parts(base, chain, 0, count, capture);
+            if is_delete {
+                value.insert(0, String::from("delete"));
+            }
+            return (value, this);
+        };
+
+        // `a?.b` is undefined when `a` is null or undefined, and `a.b`
+        // otherwise. `a.b?.()` is called on `a`.
+        let call = match chain.parts[optional].kind {
+            PartKind::Call(opening, close)
+                if optional > 0 && chain.parts[optional - 1].is_member() =>
+            {
+                Some((opening, close))
+            }
+            _ => None,
+        };
+        let (object, this) = self.lower_chain(chain, optional, call.is_some(), false);
+        let (assigned, reference) = self.reuse(object, chain.base.0);
+        let (tail, captured) = match (call, this) {
+            (Some((opening, close)), Some(this)) => {
+                let mut tail = reference.clone();
+                push(&mut tail, ".call(");
+                tail.extend(this);
+                let arguments = self.emit(opening + 1, close);
+                if !arguments.is_empty() {
+                    tail.push(String::from(","));
+                    tail.extend(arguments);
+                }
+                tail.push(String::from(")"));
+                self.apply_parts(tail, chain, optional + 1, count, capture)
+            }
+            _ => self.apply_parts(reference.clone(), chain, optional, count, capture),
+        };
+        let mut value = vec![String::from("(")];
+        value.extend(assigned);
+        push(&mut value, "===null||");
+        value.extend(reference);
+        push(&mut value, "===void 0?");
+        match is_delete {
+            true => push(&mut value, "true:delete"),
+            false => push(&mut value, "void 0:"),
+        }
+        value.extend(tail);
+        value.push(String::from(")"));
+        (value, captured)
+    }
+
+    fn apply_parts(
+        &mut self,
+        value: Vec<String>,
+        chain: &Chain,
+        from: usize,
+        to: usize,
+        capture: bool,
+    ) -> (Vec<String>, Option<Vec<String>>) {
+        let lowers_optional = self.lowers_optional(chain);
+        let mut value = value;
+        let mut captured = None;
+        // What a private method is called on.
+        let mut receiver = None;
+        for index in from..to {
+            let part = &chain.parts[index];
+            let is_private = self.is_private(part);
+            let is_private_call = is_private && index + 1 < to && chain.parts[index + 1].is_call();
+            if is_private_call || (capture && index + 1 == to) {
+                let this = match value.as_slice() {
+                    [token] if token == "super" => vec![String::from("this")],
+                    _ => {
+                        let (assigned, reference) = self.reuse(value, chain.base.0);
+                        value = assigned;
+                        reference
+                    }
+                };
+                match is_private_call {
+                    true => receiver = Some(this),
+                    false => captured = Some(this),
+                }
+            }
+            let is_optional = part.is_optional && !lowers_optional;
+            if is_optional {
+                value.push(String::from("?."));
+            }
+            match part.kind {
+                PartKind::Member(name) if is_private => value = self.private_get(value, name),
+                PartKind::Member(name) => {
+                    if !is_optional {
+                        value.push(String::from("."));
+                    }
+                    value.push(self.tokens[name].clone());
+                }
+                PartKind::Computed(opening, close) => {
+                    value.push(String::from("["));
+                    value.extend(self.emit(opening + 1, close));
+                    value.push(String::from("]"));
+                }
+                PartKind::Call(opening, close) => {
+                    let arguments = self.emit(opening + 1, close);
+                    match receiver.take() {
+                        Some(this) => {
+                            push(&mut value, ".call(");
+                            value.extend(this);
+                            if !arguments.is_empty() {
+                                value.push(String::from(","));
+                            }
+                        }
+                        None => value.push(String::from("(")),
+                    }
+                    value.extend(arguments);
+                    value.push(String::from(")"));
+                }
+                PartKind::Template(start, end) => value.extend(self.emit(start, end + 1)),
+            }
+        }
+        (value, captured)
+    }
+
+    // The target of an assignment as it is written first, like `(_a=b).c`,
+    // and after that, like `_a.c`.
+    fn assignment_target(&mut self, chain: &Chain) -> (Vec<String>, Vec<String>) {
+        let Some(last) = chain.parts.last() else {
+            let target = self.emit(chain.base.0, chain.base.1);
+            return (target.clone(), target);
+        };
+        let (object, _) = self.lower_chain(chain, chain.parts.len() - 1, false, false);
+        let (mut first, mut second) = self.reuse(object, chain.base.0);
+        match last.kind {
+            PartKind::Member(name) => {
+                for target in [&mut first, &mut second] {
+                    target.push(String::from("."));
+                    target.push(self.tokens[name].clone());
+                }
+            }
+            PartKind::Computed(opening, close) => {
+                let key = self.emit(opening + 1, close);
+                let (first_key, second_key) = match is_literal(&key) {
+                    true => (key.clone(), key),
+                    false => {
+                        let temporary = self.temporary(chain.base.0);
+                        let mut assigned = vec![temporary.clone(), String::from("=")];
+                        assigned.extend(key);
+                        (assigned, vec![temporary])
+                    }
+                };
+                for (target, key) in [(&mut first, first_key), (&mut second, second_key)] {
+                    target.push(String::from("["));
+                    target.extend(key);
+                    target.push(String::from("]"));
+                }
+            }
+            _ => {
+                let target = self.emit(chain.base.0, chain.end);
+                return (target.clone(), target);
+            }
+        }
+        (first, second)
+    }
+
+    // `({set value(v){__classPrivateFieldSet(a,_A_b,v,"f");}}).value`, which
+    // can be assigned to where `a.#b` can't be read. `this` is the object in
+    // the setter, so anything but a name is kept in a temporary first.
+    fn emit_private_target(&mut self, chain: &Chain) -> Vec<String> {
+        let Some(PartKind::Member(name)) = chain.parts.last().map(|part| part.kind.clone()) else {
+            return self.emit(chain.base.0, chain.end);
+        };
+        let (object, _) = self.lower_chain(chain, chain.parts.len() - 1, false, false);
+        let mut value = vec![String::from("(")];
+        let receiver = match object.as_slice() {
+            [token]
+                if is_identifier(token)
+                    && !KEYWORDS.contains(&token.as_str())
+                    && !matches!(token.as_str(), "this" | "super") =>
+            {
+                object
+            }
+            _ => {
+                let temporary = self.temporary(chain.base.0);
+                value.push(temporary.clone());
+                value.push(String::from("="));
+                value.extend(object);
+                value.push(String::from(","));
+                vec![temporary]
+            }
+        };
+        let parameter = self.unique_name(String::from("value"));
+        push(&mut value, "{set value(");
+        value.push(parameter.clone());
+        push(&mut value, "){");
+        value.extend(self.private_set(receiver, name, vec![parameter]));
+        push(&mut value, ";}}).value");
+        value
+    }
+
+    fn emit_assignment(&mut self, chain: &Chain, operator: usize, end: usize) -> Vec<String> {
+        let value = self.emit(operator + 1, end);
+        let operator = self.tokens[operator].clone();
+        let start = chain.base.0;
+        let is_logical = matches!(operator.as_str(), "&&=" | "||=");
+        let lowers_nullish = self.options.target < Target::Es2020;
+
+        if let Some(name) = chain
+            .parts
+            .last()
+            .filter(|part| self.is_private(part))
+            .and_then(|part| match part.kind {
+                PartKind::Member(name) => Some(name),
+                _ => None,
+            })
+        {
+            let (object, _) = self.lower_chain(chain, chain.parts.len() - 1, false, false);
+            if operator == "=" {
+                return self.private_set(object, name, value);
+            }
+            let (assigned, reference) = self.reuse(object, start);
+            if is_logical || operator == "??=" {
+                let current = self.private_get(assigned, name);
+                let set = self.private_set(reference, name, value);
+                return match (operator.as_str(), lowers_nullish) {
+                    ("??=", true) => self.nullish(current, set, start),
+                    _ => {
+                        let mut lowered = current;
+                        lowered.push(operator.trim_end_matches('=').to_string());
+                        lowered.extend(set);
+                        lowered
+                    }
+                };
+            }
+            let current = self.private_get(reference, name);
+            let combined = match operator.as_str() {
+                "**=" if self.options.target < Target::Es2016 => self.exponent(current, value),
+                _ => {
+                    let mut combined = current;
+                    combined.push(operator.trim_end_matches('=').to_string());
+                    match has_operators(&value) {
+                        true => {
+                            combined.push(String::from("("));
+                            combined.extend(value);
+                            combined.push(String::from(")"));
+                        }
+                        false => combined.extend(value),
+                    }
+                    combined
+                }
+            };
+            return self.private_set(assigned, name, combined);
+        }
+
+        let (first, second) = self.assignment_target(chain);
+        let mut assignment = vec![String::from("(")];
+        assignment.extend(second.clone());
+        assignment.push(String::from("="));
+        assignment.extend(value.clone());
+        assignment.push(String::from(")"));
+        match operator.as_str() {
+            "**=" => {
+                let mut lowered = first;
+                lowered.push(String::from("="));
+                lowered.extend(self.exponent(second, value));
+                lowered
+            }
+            "??=" if lowers_nullish => self.nullish(first, assignment, start),
+            _ => {
+                let mut lowered = first;
+                lowered.push(operator.trim_end_matches('=').to_string());
+                lowered.extend(assignment);
+                lowered
+            }
+        }
+    }
+
+    // `++a.#b` as `__classPrivateFieldSet(a,_A_b,(_a=__classPrivateFieldGet(a,_A_b,"f"),++_a),"f")`,
+    // and `a.#b++` giving the value before.
+    fn emit_update(
+        &mut self,
+        chain: &Chain,
+        operator: usize,
+        start: usize,
+        end: usize,
+    ) -> Vec<String> {
+        let PartKind::Member(name) = chain.parts[chain.parts.len() - 1].kind else {
+            return self.emit(start, chain.end);
+        };
+        // The value before a postfix update isn't needed in a statement of its own.
+        let is_statement = self
+            .previous(start)
+            .is_none_or(|previous| matches!(self.token(previous), ";" | "{" | "}"))
+            && matches!(self.token(end), ";" | "}" | "\n" | "");
+        let is_prefix = operator < chain.base.0 || is_statement;
+        let operator = self.tokens[operator].clone();
+        let (object, _) = self.lower_chain(chain, chain.parts.len() - 1, false, false);
+        let (assigned, reference) = self.reuse(object, start);
+        let current = self.private_get(reference, name);
+        let value = self.temporary(start);
+        let mut updated = vec![String::from("("), value.clone(), String::from("=")];
+        updated.extend(current);
+        updated.push(String::from(","));
+        if is_prefix {
+            updated.extend([operator, value, String::from(")")]);
+            return self.private_set(assigned, name, updated);
+        }
+        let previous = self.temporary(start);
+        updated.extend([previous.clone(), String::from("="), value.clone(), operator]);
+        updated.extend([String::from(","), value, String::from(")")]);
+        let mut lowered = vec![String::from("(")];
+        lowered.extend(self.private_set(assigned, name, updated));
+        lowered.extend([String::from(","), previous, String::from(")")]);
+        lowered
+    }
+
+    fn emit_scope(&mut self, id: usize, index: usize) -> Vec<String> {
+        let scope = self.scopes[index].clone();
+        let this_reference = self.this_reference.clone();
+        if !scope.is_arrow {
+            self.this_reference = None;
+        }
+        let tokens = if scope.is_expression {
+            let body = self.emit_excluding(scope.start, scope.end, Some(id));
+            match self.declarations.remove(&scope.start) {
+                Some(names) => {
+                    let mut tokens = vec![String::from("{")];
+                    tokens.extend(declaration(names));
+                    tokens.push(String::from("return"));
+                    tokens.extend(body);
+                    tokens.push(String::from("}"));
+                    tokens
+                }
+                None => body,
+            }
+        } else {
+            let mut tokens = vec![String::from("{")];
+            tokens.extend(self.emit(scope.start + 1, scope.end - 1));
+            tokens.push(String::from("}"));
+            if let Some(names) = self.declarations.remove(&scope.start) {
+                let start = directive_end(&tokens, 1);
+                tokens.splice(start..start, declaration(names));
+            }
+            tokens
+        };
+        self.this_reference = this_reference;
+        tokens
+    }
+
+    // A field's name as a member access like `.a`, and as a key like `"a"`.
+    fn member_key(&mut self, member: &Member) -> (Vec<String>, Vec<String>) {
+        let name = self.tokens[member.name].clone();
+        if name == "[" {
+            let key = self.emit(member.name + 1, member.name_end - 1);
+            let mut access = vec![String::from("[")];
+            access.extend(key.clone());
+            access.push(String::from("]"));
+            return (access, key);
+        }
+        match is_identifier(&name) {
+            true => (
+                vec![String::from("."), name.clone()],
+                vec![format!("\"{}\"", name)],
+            ),
+            false => (
+                vec![String::from("["), name.clone(), String::from("]")],
+                vec![name],
+            ),
+        }
+    }
+
+    // `a.b=1`, or `Object.defineProperty(a,"b",…)` like `[[Define]]`
+    // semantics when `useDefineForClassFields` is set.
+    fn field_initialization(
+        &mut self,
+        object: &str,
+        member: &Member,
+        value: Option<Vec<String>>,
+    ) -> Option<Vec<String>> {
+        let (access, key) = self.member_key(member);
+        if !self.options.defines_class_fields() {
+            let mut initialization = vec![object.to_string()];
+            initialization.extend(access);
+            initialization.push(String::from("="));
+            initialization.extend(value?);
+            return Some(initialization);
+        }
+        let mut initialization = tokenize("Object.defineProperty(");
+        initialization.push(object.to_string());
+        initialization.push(String::from(","));
+        initialization.extend(key);
+        push(
+            &mut initialization,
+            ",{enumerable:true,configurable:true,writable:true,value:",
+        );
+        match value {
+            Some(value) => initialization.extend(value),
+            None => push(&mut initialization, "void 0"),
+        }
+        push(&mut initialization, "})");
+        Some(initialization)
+    }
+
+    fn emit_class(&mut self, index: usize) -> Vec<String> {
+        let class = self.classes[index].clone();
+        let reference = class.reference.clone().unwrap_or_default();
+        let this_reference = self.this_reference.take();
+
+        // What the class needs before it's used: the WeakMaps of its private
+        // fields and the functions of its private methods.
+        let mut definitions: Vec<Vec<String>> = vec![];
+        let mut functions: Vec<Vec<String>> = vec![];
+        for (_, private) in &class.private_names {
+            if private.kind == "f" && private.getter.is_none() {
+                definitions.push(tokenize(&format!("{}=new WeakMap()", private.state)));
+            }
+        }
+        if let Some(instances) = &class.instances {
+            definitions.push(tokenize(&format!("{}=new WeakSet()", instances)));
+        }
+        for member in &class.members {
+            if !member.is_private
+                || matches!(member.kind, MemberKind::Field | MemberKind::StaticBlock)
+            {
+                continue;
+            }
+            let private = self
+                .private_name(member.name)
+                .expect("Named by name_classes");
+            let name = match member.kind {
+                MemberKind::Setter => private.setter,
+                _ => private.getter,
+            };
+            let name = name.expect("Named by name_classes");
+            let mut function = vec![];
+            if member.is_async {
+                function.push(String::from("async"));
+            }
+            function.push(String::from("function"));
+            if member.is_generator {
+                function.push(String::from("*"));
+            }
+            function.push(name.clone());
+            function.extend(self.emit(member.name_end, member.end));
+            match class.is_declaration {
+                true => functions.push(function),
+                false => {
+                    let mut definition = vec![name, String::from("=")];
+                    definition.extend(function);
+                    definitions.push(definition);
+                }
+            }
+        }
+
+        // Static fields and blocks, run in order once the class is defined,
+        // with `this` being the class.
+        let mut statics: Vec<Vec<String>> = vec![];
+        let mut body_name = None;
+        self.this_reference = Some(reference.clone());
+        for member in &class.members {
+            if !member.is_static
+                || !matches!(member.kind, MemberKind::Field | MemberKind::StaticBlock)
+            {
+                continue;
+            }
+            if member.kind == MemberKind::StaticBlock {
+                let mut block = tokenize("(()=>");
+                block.extend(self.emit(member.name, member.end));
+                push(&mut block, ")()");
+                statics.push(block);
+                continue;
+            }
+            let value = member.initializer.map(|(start, end)| self.emit(start, end));
+            if member.is_private {
+                let private = self
+                    .private_name(member.name)
+                    .expect("Named by name_classes");
+                let mut definition = vec![private.getter.expect("Named by name_classes")];
+                push(&mut definition, "={value:");
+                match value {
+                    Some(value) => definition.extend(value),
+                    None => push(&mut definition, "void 0"),
+                }
+                definition.push(String::from("}"));
+                statics.push(definition);
+            } else if let Some(initialization) =
+                self.field_initialization(&reference, member, value)
+            {
+                statics.push(initialization);
+            }
+        }
+        self.this_reference = None;
+        if let Some(name) = &class.inferred_name {
+            match class.reference.is_some() && self.tokens[class.start..class.close].contains(name)
+            {
+                true => statics.insert(
+                    0,
+                    tokenize(&format!(
+                        "Object.defineProperty({},\"name\",{{value:\"{}\",configurable:true}})",
+                        reference, name
+                    )),
+                ),
+                false => body_name = Some(name.clone()),
+            }
+        }
+
+        // Instance fields, initialized in the constructor.
+        let mut initializers = vec![];
+        if let Some(instances) = &class.instances {
+            push(&mut initializers, &format!("{}.add(this);", instances));
+        }
+        for member in &class.members {
+            if member.is_static || member.kind != MemberKind::Field {
+                continue;
+            }
+            let value = member.initializer.map(|(start, end)| self.emit(start, end));
+            if member.is_private {
+                let private = self
+                    .private_name(member.name)
+                    .expect("Named by name_classes");
+                initializers.push(private.state);
+                push(&mut initializers, ".set(this,");
+                match value {
+                    Some(value) => initializers.extend(value),
+                    None => push(&mut initializers, "void 0"),
+                }
+                push(&mut initializers, ");");
+            } else if let Some(initialization) = self.field_initialization("this", member, value) {
+                initializers.extend(initialization);
+                initializers.push(String::from(";"));
+            }
+        }
+
+        let mut body = self.emit(class.start, class.body + 1);
+        if let Some(name) = body_name {
+            body.insert(1, name);
+        }
+        let has_constructor = class.members.iter().any(|member| member.is_constructor);
+        if !has_constructor && !initializers.is_empty() {
+            push(&mut body, "constructor(){");
+            if class.is_derived {
+                push(&mut body, "super(...arguments);");
+            }
+            body.extend(initializers.clone());
+            body.push(String::from("}"));
+        }
+        for member in &class.members {
+            match (member.is_moved(), member.is_constructor) {
+                (true, _) => {}
+                (false, true) => body.extend(self.emit_constructor(member, &initializers)),
+                (false, false) => body.extend(self.emit(member.start, member.end)),
+            }
+        }
+        body.push(String::from("}"));
+        self.this_reference = this_reference;
+
+        if class.is_declaration {
+            for statement in definitions.into_iter().chain(statics) {
+                body.extend(statement);
+                body.push(String::from(";"));
+            }
+            body.extend(functions.into_iter().flatten());
+            return body;
+        }
+        if definitions.is_empty() && class.reference.is_none() {
+            return body;
+        }
+        let mut tokens = vec![String::from("(")];
+        for definition in definitions {
+            tokens.extend(definition);
+            tokens.push(String::from(","));
+        }
+        match class.reference {
+            Some(reference) => {
+                tokens.extend([reference.clone(), String::from("=")]);
+                tokens.extend(body);
+                for statement in statics {
+                    tokens.push(String::from(","));
+                    tokens.extend(statement);
+                }
+                tokens.extend([String::from(","), reference]);
+            }
+            None => tokens.extend(body),
+        }
+        tokens.push(String::from(")"));
+        tokens
+    }
+
+    // The constructor with the fields initialized right after `super()`,
+    // or first when there isn't a call to it.
+    fn emit_constructor(&mut self, member: &Member, initializers: &[String]) -> Vec<String> {
+        let body = matching_close(&self.tokens, member.name_end) + 1;
+        let close = matching_close(&self.tokens, body);
+        let mut tokens = self.emit(member.start, body + 1);
+        let declarations = tokens.len();
+
+        let mut super_end = None;
+        let mut index = body + 1;
+        while index < close {
+            let token = self.token(index);
+            if token == "super" && self.token(index + 1) == "(" {
+                let end = matching_close(&self.tokens, index + 1) + 1;
+                super_end = Some(end + usize::from(self.token(end) == ";"));
+                break;
+            }
+            if is_opening(token) && token != "<" {
+                index = matching_close(&self.tokens, index);
+            }
+            index += 1;
+        }
+        let start = match super_end {
+            Some(end) => {
+                tokens.extend(self.emit(body + 1, end));
+                if tokens.last().is_some_and(|token| token != ";") && !initializers.is_empty() {
+                    tokens.push(String::from(";"));
+                }
+                end
+            }
+            None => body + 1,
+        };
+        tokens.extend(initializers.iter().cloned());
+        tokens.extend(self.emit(start, close));
+        tokens.push(String::from("}"));
+        if let Some(names) = self.declarations.remove(&body) {
+            let start = directive_end(&tokens, declarations);
+            tokens.splice(start..start, declaration(names));
+        }
+        tokens
+    }
+}
@@ -0,0 +1,444 @@
+use super::decorators::{ClassDecoration, MemberDecoration, MemberKind};
+use super::lookahead::{is_identifier, matching_close, next_index, super_call_end};
+
+/// Text written after the token at `position`, even when that token is
+/// skipped. A statement gets a `;` when it would follow a `)`.
+pub struct Insertion {
+    pub position: usize,
+    pub text: String,
+    pub is_statement: bool,
+}
+
+/// How the tokens of a class body change when it is written: tokens that are
+/// replaced (by nothing to leave them out) and text added after tokens.
+#[derive(Default)]
+pub struct ClassRewrite {
+    pub rewrites: Vec<(usize, String)>,
+    pub insertions: Vec<Insertion>,
+}
+
+impl ClassRewrite {
+    fn insert(&mut self, position: usize, text: String) {
+        self.insertions.push(Insertion {
+            position,
+            text,
+            is_statement: false,
+        });
+    }
+}
+
+/// A class with standard decorators, which becomes an IIFE that decorates
+/// the class while it is being defined, the way `tsc` does it without
+/// `experimentalDecorators`.
+pub struct StandardDecoration {
+    // `let A=(()=>{` and the variables the decorators are kept in, written
+    // before `class`.
+    pub prologue: String,
+    pub rewrite: ClassRewrite,
+    // The end of the IIFE, written after the class body and before its `;`.
+    pub epilogue: String,
+}
+
+/// `accessor x = 1` is a private field with a getter and setter for it.
+/// Statics are reached through `class_reference`.
+pub fn lower_accessors(
+    tokens: &[String],
+    decoration: &ClassDecoration,
+    class_reference: &str,
+    rewrite: &mut ClassRewrite,
+) {
+    let mut storages: Vec<String> = vec![];
+    for member in decoration.members.iter() {
+        let Some(keyword) = member.accessor_keyword else {
+            continue;
+        };
+        let mut storage = format!("#{}_accessor_storage", variable_name(member));
+        if storages.contains(&storage) {
+            storage = format!("{}_{}", storage, storages.len());
+        }
+        storages.push(storage.clone());
+
+        rewrite.rewrites.push((keyword, String::new()));
+        let name = next_index(tokens, keyword + 1);
+        rewrite.rewrites.push((name, storage.clone()));
+        // Everything else of a computed name goes with it.
+        for index in name + 1..=member.name_end {
+            rewrite.rewrites.push((index, String::new()));
+        }
+
+        let (prefix, receiver) = match member.is_static {
+            true => ("static ", class_reference),
+            false => ("", "this"),
+        };
+        let key = match member.name.as_str() {
+            "[" => format!("[{}]", member.key),
+            name => name.to_string(),
+        };
+        let methods = format!(
+            "{0}get {1}(){{return {2}.{3};}}{0}set {1}(value){{{2}.{3}=value;}}",
+            prefix, key, receiver, storage
+        );
+        match member.has_semicolon {
+            true => rewrite.insert(next_index(tokens, member.last + 1), methods),
+            false => rewrite.insert(member.last, format!(";{}", methods)),
+        }
+    }
+}
+
+// The name a member's variables are called after: `_x_decorators`.
+fn variable_name(member: &MemberDecoration) -> &str {
+    let name = member.name.trim_start_matches('#');
+    match is_identifier(name) {
+        true => name,
+        false => "member",
+    }
+}
+
+fn variable_prefix(member: &MemberDecoration, used: &mut Vec<String>) -> String {
+    let mut prefix = String::from("_");
+    if member.is_static {
+        prefix += "static_";
+    }
+    if member.is_private() {
+        prefix += "private_";
+    }
+    match member.kind {
+        MemberKind::Getter => prefix += "get_",
+        MemberKind::Setter => prefix += "set_",
+        _ => {}
+    }
+    prefix += variable_name(member);
+    let mut candidate = prefix.clone();
+    let mut count = 1;
+    while used.contains(&candidate) {
+        candidate = format!("{}_{}", prefix, count);
+        count += 1;
+    }
+    used.push(candidate.clone());
+    candidate
+}
+
+// The `context` object a member decorator is called with, leaving out
+// `metadata`.
+fn member_context(member: &MemberDecoration) -> String {
+    let kind = match member.kind {
+        MemberKind::Field => "field",
+        MemberKind::Method => "method",
+        MemberKind::Getter => "getter",
+        MemberKind::Setter => "setter",
+        MemberKind::Accessor => "accessor",
+    };
+    let is_literal_name = member.name.starts_with(|char: char| char.is_ascii_digit());
+    let name = match member.is_private() || is_literal_name {
+        true => format!("\"{}\"", member.name),
+        false => member.key.clone(),
+    };
+    let (has, property) = match member.name.as_str() {
+        "[" => (format!("({})", member.key), format!("[{}]", member.key)),
+        _ if member.is_private() || is_identifier(&member.name) => {
+            let has = match member.is_private() {
+                true => member.name.clone(),
+                false => member.key.clone(),
+            };
+            (has, format!(".{}", member.name))
+        }
+        _ => (member.key.clone(), format!("[{}]", member.key)),
+    };
+    let mut access = vec![format!("has:obj=>{} in obj", has)];
+    if member.kind != MemberKind::Setter {
+        access.push(format!("get:obj=>obj{}", property));
+    }
+    if matches!(
+        member.kind,
+        MemberKind::Field | MemberKind::Accessor | MemberKind::Setter
+    ) {
+        access.push(format!("set:(obj,value)=>{{obj{}=value;}}", property));
+    }
+    format!(
+        "kind:\"{}\",name:{},static:{},private:{},access:{{{}}}",
+        kind,
+        name,
+        member.is_static,
+        member.is_private(),
+        access.join(",")
+    )
+}
+
+fn run_initializers(receiver: &str, initializers: &[String]) -> String {
+    initializers
+        .iter()
+        .map(|initializer| format!("__runInitializers({},{})", receiver, initializer))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Lowers the decorators of the class whose `class` keyword is at
+/// `position` and whose body opens at `body`. `decorators` are the ones on
+/// the class itself.
+pub fn standard_decoration(
+    tokens: &[String],
+    position: usize,
+    body: usize,
+    name: &str,
+    decorators: &[String],
+    decoration: &ClassDecoration,
+    is_expression: bool,
+) -> StandardDecoration {
+    let is_decorated = !decorators.is_empty();
+    let is_derived = tokens[position..body]
+        .iter()
+        .any(|token| token == "extends");
+    let this_class = match is_decorated {
+        true => "_classThis",
+        false => "this",
+    };
+    let mut rewrite = ClassRewrite::default();
+
+    // Private methods and accessors would have to be moved out of the class
+    // to be replaced, their decorators are left out.
+    let mut used = vec![];
+    let members: Vec<(usize, &MemberDecoration, String)> = decoration
+        .members
+        .iter()
+        .enumerate()
+        .filter(|(_, member)| !member.decorators.is_empty())
+        .filter(|(_, member)| member.kind == MemberKind::Field || !member.is_private())
+        .map(|(index, member)| (index, member, variable_prefix(member, &mut used)))
+        .collect();
+    let has_extra_initializers = |is_static: bool| {
+        members
+            .iter()
+            .any(|(_, member, _)| member.is_static == is_static && !member.is_field())
+    };
+
+    let mut declarations = vec![];
+    if is_decorated {
+        declarations.push(format!("_classDecorators=[{}]", decorators.join(",")));
+        declarations.extend(
+            [
+                "_classDescriptor",
+                "_classExtraInitializers=[]",
+                "_classThis",
+            ]
+            .map(String::from),
+        );
+    }
+    if has_extra_initializers(true) {
+        declarations.push(String::from("_staticExtraInitializers=[]"));
+    }
+    if has_extra_initializers(false) {
+        declarations.push(String::from("_instanceExtraInitializers=[]"));
+    }
+    for (_, member, prefix) in members.iter() {
+        declarations.push(format!("{}_decorators", prefix));
+        if member.is_field() {
+            declarations.push(format!("{}_initializers=[]", prefix));
+            declarations.push(format!("{}_extraInitializers=[]", prefix));
+        }
+    }
+    // A class expression is replaced by the IIFE itself.
+    let mut prologue = match is_expression {
+        true => String::from("(()=>{"),
+        false => format!("let {}=(()=>{{", name),
+    };
+    for declaration in declarations {
+        prologue += &format!("let {};", declaration);
+    }
+    match is_decorated {
+        true => {
+            prologue += &format!("var {}=", name);
+            // The class is anonymous so that it can be replaced.
+            let class_name = next_index(tokens, position + 1);
+            let is_named = is_identifier(&tokens[class_name])
+                && !matches!(tokens[class_name].as_str(), "extends" | "implements");
+            if is_named {
+                rewrite.rewrites.push((class_name, String::new()));
+            }
+        }
+        false => prologue += "return",
+    }
+
+    // The decorators are called in a static block before anything else in
+    // the class: static methods, methods, static fields, fields.
+    let mut block = String::new();
+    if is_decorated {
+        block += "static{_classThis=this;}";
+    }
+    let parent_metadata = match is_derived {
+        true => "Object.getPrototypeOf(this)[Symbol.metadata]??null",
+        false => "null",
+    };
+    block += &format!(
+        "static{{const _metadata=typeof Symbol===\"function\"&&Symbol.metadata?Object.create({}):void 0;",
+        parent_metadata
+    );
+    let order = [(true, false), (false, false), (true, true), (false, true)];
+    for (is_static, is_field) in order {
+        for (_, member, prefix) in members.iter() {
+            if member.is_static != is_static || member.is_field() != is_field {
+                continue;
+            }
+            let (target, initializers, extra_initializers) = match (is_field, is_static) {
+                (true, _) => (
+                    "null",
+                    format!("{}_initializers", prefix),
+                    format!("{}_extraInitializers", prefix),
+                ),
+                (false, true) => (
+                    "this",
+                    String::from("null"),
+                    String::from("_staticExtraInitializers"),
+                ),
+                (false, false) => (
+                    "this",
+                    String::from("null"),
+                    String::from("_instanceExtraInitializers"),
+                ),
+            };
+            // Accessors are defined on the class, unlike fields.
+            let target = match member.kind {
+                MemberKind::Accessor => "this",
+                _ => target,
+            };
+            block += &format!(
+                "{0}_decorators=[{1}];__esDecorate({2},null,{0}_decorators,{{{3},metadata:_metadata}},{4},{5});",
+                prefix,
+                member.decorators.join(","),
+                target,
+                member_context(member),
+                initializers,
+                extra_initializers
+            );
+        }
+    }
+    if is_decorated {
+        block += "__esDecorate(null,_classDescriptor={value:_classThis},_classDecorators,{kind:\"class\",name:_classThis.name,metadata:_metadata},null,_classExtraInitializers);";
+        block += &format!("{}=_classThis=_classDescriptor.value;", name);
+    }
+    block += &format!(
+        "if(_metadata)Object.defineProperty({},Symbol.metadata,{{enumerable:true,configurable:true,writable:true,value:_metadata}});",
+        this_class
+    );
+    if has_extra_initializers(true) {
+        block += &format!(
+            "__runInitializers({},_staticExtraInitializers);",
+            this_class
+        );
+    }
+    block += "}";
+    rewrite.insert(body, block);
+
+    // Field values go through their initializers. The extra initializers of
+    // methods run before the first field, the ones of a field right after it.
+    let mut pending = vec![];
+    if has_extra_initializers(false) {
+        pending.push(String::from("_instanceExtraInitializers"));
+    }
+    for (index, member) in decoration.members.iter().enumerate() {
+        if !member.is_field() {
+            continue;
+        }
+        let prefix = members
+            .iter()
+            .find(|(decorated, _, _)| *decorated == index)
+            .map(|(_, _, prefix)| prefix);
+        let (receiver, before) = match member.is_static {
+            true => (this_class, vec![]),
+            false => ("this", std::mem::take(&mut pending)),
+        };
+        if prefix.is_none() && before.is_empty() {
+            continue;
+        }
+        let mut open = String::new();
+        let mut close = String::new();
+        if !before.is_empty() {
+            open += &format!("({},", run_initializers(receiver, &before));
+            close += ")";
+        }
+        if let Some(prefix) = prefix {
+            open = format!(
+                "{}__runInitializers({},{}_initializers,",
+                open, receiver, prefix
+            );
+            close = format!("){}", close);
+        }
+        match member.initializer {
+            Some(initializer) => {
+                rewrite.insert(initializer, open);
+                rewrite.insert(member.last, close);
+            }
+            None => rewrite.insert(member.name_end, format!("={}void 0{}", open, close)),
+        }
+        let Some(prefix) = prefix else {
+            continue;
+        };
+        let extra_initializers = format!("{}_extraInitializers", prefix);
+        match member.is_static {
+            true => {
+                let block = format!(
+                    "static{{__runInitializers({},{});}}",
+                    this_class, extra_initializers
+                );
+                match member.has_semicolon {
+                    true => rewrite.insert(next_index(tokens, member.last + 1), block),
+                    false => rewrite.insert(member.last, format!(";{}", block)),
+                }
+            }
+            false => pending.push(extra_initializers),
+        }
+    }
+
+    let class_reference = match (is_decorated, is_expression) {
+        (true, _) => "_classThis",
+        (false, true) => "this",
+        (false, false) => name,
+    };
+    lower_accessors(tokens, decoration, class_reference, &mut rewrite);
+
+    // What is left runs in the constructor, after `super()`.
+    let mut end = String::new();
+    if !pending.is_empty() {
+        let statement = format!("{};", run_initializers("this", &pending));
+        match decoration.constructor_body {
+            Some(constructor_body) => {
+                let position = match is_derived {
+                    true => super_call_end(tokens, constructor_body).unwrap_or(constructor_body),
+                    false => constructor_body,
+                };
+                rewrite.insertions.push(Insertion {
+                    position,
+                    text: statement,
+                    is_statement: true,
+                });
+            }
+            None if is_derived => {
+                end += &format!("constructor(){{super(...arguments);{}}}", statement)
+            }
+            None => end += &format!("constructor(){{{}}}", statement),
+        }
+    }
+    if is_decorated {
+        end += "static{__runInitializers(_classThis,_classExtraInitializers);}";
+    }
+    if !end.is_empty() {
+        // A field without a `;` can't be followed by anything else on its line.
+        let ends_with_field = decoration
+            .members
+            .last()
+            .is_some_and(|member| member.is_field() && !member.has_semicolon);
+        if ends_with_field {
+            end.insert(0, ';');
+        }
+        rewrite.insert(matching_close(tokens, body) - 1, end);
+    }
+
+    let epilogue = match is_decorated {
+        true => format!(";return {}=_classThis;}})()", name),
+        false => String::from(";})()"),
+    };
+    StandardDecoration {
+        prologue,
+        rewrite,
+        epilogue,
+    }
+}
@@ -0,0 +1,65 @@
+/// Runtime helpers written at the top of a module that needs them, in the
+/// order `tsc` writes them.
+pub const HELPERS: [(&str, &str); 13] = [
+    (
+        "__createBinding",
+        "var __createBinding=(this&&this.__createBinding)||(Object.create?(function(o,m,k,k2){if(k2===undefined)k2=k;var desc=Object.getOwnPropertyDescriptor(m,k);if(!desc||(\"get\"in desc?!m.__esModule:desc.writable||desc.configurable)){desc={enumerable:true,get:function(){return m[k];}};}Object.defineProperty(o,k2,desc);}):(function(o,m,k,k2){if(k2===undefined)k2=k;o[k2]=m[k];}));",
+    ),
+    (
+        "__setModuleDefault",
+        "var __setModuleDefault=(this&&this.__setModuleDefault)||(Object.create?(function(o,v){Object.defineProperty(o,\"default\",{enumerable:true,value:v});}):function(o,v){o[\"default\"]=v;});",
+    ),
+    (
+        "__decorate",
+        "var __decorate=(this&&this.__decorate)||function(decorators,target,key,desc){var c=arguments.length,r=c<3?target:desc===null?desc=Object.getOwnPropertyDescriptor(target,key):desc,d;if(typeof Reflect===\"object\"&&typeof Reflect.decorate===\"function\")r=Reflect.decorate(decorators,target,key,desc);else for(var i=decorators.length-1;i>=0;i--)if(d=decorators[i])r=(c<3?d(r):c>3?d(target,key,r):d(target,key))||r;return c>3&&r&&Object.defineProperty(target,key,r),r;};",
+    ),
+    (
+        "__metadata",
+        "var __metadata=(this&&this.__metadata)||function(k,v){if(typeof Reflect===\"object\"&&typeof Reflect.metadata===\"function\")return Reflect.metadata(k,v);};",
+    ),
+    (
+        "__param",
+        "var __param=(this&&this.__param)||function(paramIndex,decorator){return function(target,key){decorator(target,key,paramIndex);}};",
+    ),
+    (
+        "__esDecorate",
+        "var __esDecorate=(this&&this.__esDecorate)||function(ctor,descriptorIn,decorators,contextIn,initializers,extraInitializers){function accept(f){if(f!==void 0&&typeof f!==\"function\")throw new TypeError(\"Function expected\");return f;}var kind=contextIn.kind,key=kind===\"getter\"?\"get\":kind===\"setter\"?\"set\":\"value\";var target=!descriptorIn&&ctor?contextIn[\"static\"]?ctor:ctor.prototype:null;var descriptor=descriptorIn||(target?Object.getOwnPropertyDescriptor(target,contextIn.name):{});var _,done=false;for(var i=decorators.length-1;i>=0;i--){var context={};for(var p in contextIn)context[p]=p===\"access\"?{}:contextIn[p];for(var p in contextIn.access)context.access[p]=contextIn.access[p];context.addInitializer=function(f){if(done)throw new TypeError(\"Cannot add initializers after decoration has completed\");extraInitializers.push(accept(f||null));};var result=(0,decorators[i])(kind===\"accessor\"?{get:descriptor.get,set:descriptor.set}:descriptor[key],context);if(kind===\"accessor\"){if(result===void 0)continue;if(result===null||typeof result!==\"object\")throw new TypeError(\"Object expected\");if(_=accept(result.get))descriptor.get=_;if(_=accept(result.set))descriptor.set=_;if(_=accept(result.init))initializers.unshift(_);}else if(_=accept(result)){if(kind===\"field\")initializers.unshift(_);else descriptor[key]=_;}}if(target)Object.defineProperty(target,contextIn.name,descriptor);done=true;};",
+    ),
+    (
+        "__runInitializers",
+        "var __runInitializers=(this&&this.__runInitializers)||function(thisArg,initializers,value){var useValue=arguments.length>2;for(var i=0;i<initializers.length;i++){value=useValue?initializers[i].call(thisArg,value):initializers[i].call(thisArg);}return useValue?value:void 0;};",
+    ),
+    (
+        "__classPrivateFieldGet",
+        "var __classPrivateFieldGet=(this&&this.__classPrivateFieldGet)||function(receiver,state,kind,f){if(kind===\"a\"&&!f)throw new TypeError(\"Private accessor was defined without a getter\");if(typeof state===\"function\"?receiver!==state||!f:!state.has(receiver))throw new TypeError(\"Cannot read private member from an object whose class did not declare it\");return kind===\"m\"?f:kind===\"a\"?f.call(receiver):f?f.value:state.get(receiver);};",
+    ),
+    (
+        "__classPrivateFieldSet",
+        "var __classPrivateFieldSet=(this&&this.__classPrivateFieldSet)||function(receiver,state,value,kind,f){if(kind===\"m\")throw new TypeError(\"Private method is not writable\");if(kind===\"a\"&&!f)throw new TypeError(\"Private accessor was defined without a setter\");if(typeof state===\"function\"?receiver!==state||!f:!state.has(receiver))throw new TypeError(\"Cannot write private member to an object whose class did not declare it\");return(kind===\"a\"?f.call(receiver,value):f?f.value=value:state.set(receiver,value)),value;};",
+    ),
+    (
+        "__classPrivateFieldIn",
+        "var __classPrivateFieldIn=(this&&this.__classPrivateFieldIn)||function(state,receiver){if(receiver===null||(typeof receiver!==\"object\"&&typeof receiver!==\"function\"))throw new TypeError(\"Cannot use 'in' operator on non-object\");return typeof state===\"function\"?receiver===state:state.has(receiver);};",
+    ),
+    (
+        "__importStar",
+        "var __importStar=(this&&this.__importStar)||function(mod){if(mod&&mod.__esModule)return mod;var result={};if(mod!=null)for(var k in mod)if(k!==\"default\"&&Object.prototype.hasOwnProperty.call(mod,k))__createBinding(result,mod,k);__setModuleDefault(result,mod);return result;};",
+    ),
+    (
+        "__exportStar",
+        "var __exportStar=(this&&this.__exportStar)||function(m,exports){for(var p in m)if(p!==\"default\"&&!Object.prototype.hasOwnProperty.call(exports,p))__createBinding(exports,m,p);};",
+    ),
+    (
+        "__importDefault",
+        "var __importDefault=(this&&this.__importDefault)||function(mod){return(mod&&mod.__esModule)?mod:{\"default\":mod};};",
+    ),
+];
+
+/// The helpers `helper` calls, which are written along with it.
+pub fn dependencies(helper: &str) -> &'static [&'static str] {
+    match helper {
+        "__importStar" => &["__createBinding", "__setModuleDefault"],
+        "__exportStar" => &["__createBinding"],
+        _ => &[],
+    }
+}
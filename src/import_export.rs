@@ -0,0 +1,495 @@
+use super::lookahead::{is_identifier, matching_close, next_index, next_token};
+
+/// `name` or `name as alias` inside the braces of an import or export. The
+/// alias is the local binding of an import and the exported name of an export.
+pub struct Specifier {
+    pub name: String,
+    pub alias: String,
+    pub is_type_only: bool,
+}
+
+/// An `import` statement measured with lookahead, so it can be written back
+/// with some of its bindings left out.
+pub struct ImportDeclaration {
+    pub is_type_only: bool,
+    // `import './a'`, which is kept for its side effects.
+    pub is_side_effect_only: bool,
+    pub default_binding: Option<String>,
+    pub namespace_binding: Option<String>,
+    // `None` when the import has no braces at all.
+    pub named: Option<Vec<Specifier>>,
+    pub source: String,
+    // `with { type: 'json' }` as it was written.
+    pub attributes: Vec<String>,
+    // The index just past the statement, including its `;`.
+    pub end: usize,
+}
+
+/// `export { a, b as c }`, optionally re-exported `from` another module.
+pub struct ExportList {
+    pub is_type_only: bool,
+    // `export {}`, which only marks the file as a module.
+    pub is_empty: bool,
+    pub specifiers: Vec<Specifier>,
+    pub source: Option<String>,
+    pub attributes: Vec<String>,
+    pub end: usize,
+}
+
+/// `export * from './a'` or `export * as a from './a'`.
+pub struct ExportAll {
+    pub namespace: Option<String>,
+    pub source: String,
+    pub attributes: Vec<String>,
+    pub end: usize,
+}
+
+/// `import fs = require('fs')` or `import A = N.B`, optionally exported.
+pub struct ImportEquals {
+    pub is_exported: bool,
+    pub is_type_only: bool,
+    pub name: String,
+    // The source of `require('fs')`.
+    pub source: Option<String>,
+    // The tokens of `N.B`.
+    pub entity: Vec<String>,
+    pub end: usize,
+}
+
+fn is_string(token: &str) -> bool {
+    token.starts_with('\'') || token.starts_with('"')
+}
+
+/// Whether the string at `position` names the module of an `import`, an
+/// `export … from`, `import()` or `require()`.
+pub fn is_module_specifier(tokens: &[String], position: usize) -> bool {
+    if !is_string(&tokens[position]) {
+        return false;
+    }
+    let previous: Vec<&str> = tokens[..position]
+        .iter()
+        .rev()
+        .filter(|token| *token != "\n")
+        .take(3)
+        .map(String::as_str)
+        .collect();
+    // `a.import('b')` and `require('a' + b)` aren't.
+    let is_only_argument = matches!(next_token(tokens, position + 1), ")" | ",");
+    match previous.as_slice() {
+        ["from", ..] => true,
+        ["import", rest @ ..] => rest.first() != Some(&"."),
+        ["(", "import" | "require", rest @ ..] => is_only_argument && rest.first() != Some(&"."),
+        _ => false,
+    }
+}
+
+/// Whether `attributes` are `with { type: 'json' }`, which imports the
+/// parsed JSON as the default export.
+pub fn is_json(attributes: &[String]) -> bool {
+    attributes.windows(3).any(|attribute| {
+        attribute[0] == "type" && attribute[1] == ":" && attribute[2].get(1..5) == Some("json")
+    })
+}
+
+fn push_specifiers(tokens: &mut Vec<String>, specifiers: &[&Specifier]) {
+    tokens.push(String::from("{"));
+    for (index, specifier) in specifiers.iter().enumerate() {
+        if index > 0 {
+            tokens.push(String::from(","));
+        }
+        tokens.push(specifier.name.clone());
+        if specifier.name != specifier.alias {
+            tokens.extend(["as", &specifier.alias].map(String::from));
+        }
+    }
+    tokens.push(String::from("}"));
+}
+
+fn push_source(tokens: &mut Vec<String>, source: &str, attributes: &[String]) {
+    tokens.push(String::from("from"));
+    tokens.push(source.to_string());
+    tokens.extend(attributes.iter().cloned());
+}
+
+impl ImportDeclaration {
+    pub fn has_bindings(&self) -> bool {
+        self.default_binding.is_some() || self.namespace_binding.is_some() || self.named.is_some()
+    }
+
+    /// Every local binding the import declares.
+    pub fn bindings(&self) -> Vec<&String> {
+        let named = self
+            .named
+            .iter()
+            .flatten()
+            .map(|specifier| &specifier.alias);
+        self.default_binding
+            .iter()
+            .chain(self.namespace_binding.iter())
+            .chain(named)
+            .collect()
+    }
+
+    /// Leaves out every binding `is_kept` returns false for, along with the
+    /// type-only ones.
+    pub fn retain(&mut self, is_kept: impl Fn(&String) -> bool) {
+        let is_type_only = self.is_type_only;
+        self.default_binding = self
+            .default_binding
+            .take()
+            .filter(|binding| !is_type_only && is_kept(binding));
+        self.namespace_binding = self
+            .namespace_binding
+            .take()
+            .filter(|binding| !is_type_only && is_kept(binding));
+        if let Some(named) = self.named.as_mut() {
+            named.retain(|specifier| {
+                !is_type_only && !specifier.is_type_only && is_kept(&specifier.alias)
+            });
+        }
+    }
+
+    /// The tokens of the statement as it should be written, nothing when all
+    /// of its bindings have been left out.
+    pub fn to_tokens(&self) -> Vec<String> {
+        if self.is_type_only {
+            return vec![];
+        }
+        let mut tokens = vec![String::from("import")];
+        let named: Option<Vec<&Specifier>> = self
+            .named
+            .as_ref()
+            .map(|named| {
+                named
+                    .iter()
+                    .filter(|specifier| !specifier.is_type_only)
+                    .collect()
+            })
+            .filter(|named: &Vec<&Specifier>| !named.is_empty());
+        if self.is_side_effect_only {
+            tokens.push(self.source.clone());
+            tokens.extend(self.attributes.iter().cloned());
+            tokens.push(String::from(";"));
+            return tokens;
+        }
+        if self.default_binding.is_none() && self.namespace_binding.is_none() && named.is_none() {
+            return vec![];
+        }
+        if let Some(binding) = &self.default_binding {
+            tokens.push(binding.clone());
+        }
+        if self.default_binding.is_some() && (self.namespace_binding.is_some() || named.is_some()) {
+            tokens.push(String::from(","));
+        }
+        if let Some(binding) = &self.namespace_binding {
+            tokens.extend(["*", "as", binding].map(String::from));
+        }
+        if let Some(named) = named {
+            push_specifiers(&mut tokens, &named);
+        }
+        push_source(&mut tokens, &self.source, &self.attributes);
+        tokens.push(String::from(";"));
+        tokens
+    }
+}
+
+impl ExportAll {
+    pub fn to_tokens(&self) -> Vec<String> {
+        let mut tokens = vec![String::from("export"), String::from("*")];
+        if let Some(namespace) = &self.namespace {
+            tokens.extend([String::from("as"), namespace.clone()]);
+        }
+        push_source(&mut tokens, &self.source, &self.attributes);
+        tokens.push(String::from(";"));
+        tokens
+    }
+}
+
+impl ExportList {
+    /// The tokens of the statement as it should be written, nothing when all
+    /// of its specifiers have been left out.
+    pub fn to_tokens(&self) -> Vec<String> {
+        let specifiers: Vec<&Specifier> = self
+            .specifiers
+            .iter()
+            .filter(|specifier| !specifier.is_type_only)
+            .collect();
+        if self.is_type_only || (specifiers.is_empty() && !self.is_empty) {
+            return vec![];
+        }
+        let mut tokens = vec![String::from("export")];
+        push_specifiers(&mut tokens, &specifiers);
+        if let Some(source) = &self.source {
+            push_source(&mut tokens, source, &self.attributes);
+        }
+        tokens.push(String::from(";"));
+        tokens
+    }
+}
+
+fn parse_specifiers(tokens: &[String], position: usize) -> Vec<Specifier> {
+    let close = matching_close(tokens, position);
+    let mut specifiers = vec![];
+    let mut index = next_index(tokens, position + 1);
+
+    while index < close {
+        let mut is_type_only = false;
+        // `type A`, but not `type`, `type as A` or `type,`.
+        if tokens[index] == "type" && !matches!(next_token(tokens, index + 1), "," | "}" | "as") {
+            is_type_only = true;
+            index = next_index(tokens, index + 1);
+        }
+        let name = tokens[index].clone();
+        let mut alias = name.clone();
+        index = next_index(tokens, index + 1);
+        if next_token(tokens, index) == "as" {
+            index = next_index(tokens, index + 1);
+            alias = tokens[index].clone();
+            index = next_index(tokens, index + 1);
+        }
+        specifiers.push(Specifier {
+            name,
+            alias,
+            is_type_only,
+        });
+        if next_token(tokens, index) == "," {
+            index = next_index(tokens, index + 1);
+        }
+    }
+    specifiers
+}
+
+// Reads `with { type: 'json' }` and the `;` after the source at `index`, and
+// returns the attributes with the index past the statement.
+fn parse_statement_end(tokens: &[String], mut index: usize) -> (Vec<String>, usize) {
+    let mut attributes = vec![];
+    let keyword = next_index(tokens, index);
+    if matches!(next_token(tokens, index), "with" | "assert")
+        && next_token(tokens, keyword + 1) == "{"
+    {
+        let close = matching_close(tokens, next_index(tokens, keyword + 1));
+        attributes = tokens[keyword..close + 1]
+            .iter()
+            .filter(|token| token.as_str() != "\n")
+            .cloned()
+            .collect();
+        index = close + 1;
+    }
+    if next_token(tokens, index) == ";" {
+        index = next_index(tokens, index) + 1;
+    }
+    (attributes, index)
+}
+
+/// Measures the `import` statement at `position`. Returns `None` for
+/// `import()`, `import.meta` and `import x = require()`.
+pub fn parse_import(tokens: &[String], position: usize) -> Option<ImportDeclaration> {
+    let mut declaration = ImportDeclaration {
+        is_type_only: false,
+        is_side_effect_only: false,
+        default_binding: None,
+        namespace_binding: None,
+        named: None,
+        source: String::new(),
+        attributes: vec![],
+        end: position,
+    };
+    let mut index = next_index(tokens, position + 1);
+
+    // `import type A from` as opposed to importing a default named `type`.
+    if next_token(tokens, index) == "type" {
+        let next = next_index(tokens, index + 1);
+        let is_default_named_type =
+            next_token(tokens, next) == "from" && is_string(next_token(tokens, next + 1));
+        let is_clause = matches!(next_token(tokens, next), "{" | "*")
+            || is_identifier(next_token(tokens, next));
+        if is_clause && !is_default_named_type {
+            declaration.is_type_only = true;
+            index = next;
+        }
+    }
+
+    let token = next_token(tokens, index);
+    if !is_string(token) {
+        if is_identifier(token) {
+            declaration.default_binding = Some(token.to_string());
+            index = next_index(tokens, index + 1);
+            match next_token(tokens, index) {
+                "," => index = next_index(tokens, index + 1),
+                "from" => {}
+                _ => return None,
+            }
+        }
+        match next_token(tokens, index) {
+            "*" => {
+                index = next_index(tokens, index + 1);
+                if next_token(tokens, index) != "as" {
+                    return None;
+                }
+                index = next_index(tokens, index + 1);
+                declaration.namespace_binding = Some(tokens[index].clone());
+                index = next_index(tokens, index + 1);
+            }
+            "{" => {
+                declaration.named = Some(parse_specifiers(tokens, index));
+                index = next_index(tokens, matching_close(tokens, index) + 1);
+            }
+            _ => {}
+        }
+        if !declaration.has_bindings() || next_token(tokens, index) != "from" {
+            return None;
+        }
+        index = next_index(tokens, index + 1);
+    }
+
+    let source = next_token(tokens, index);
+    if !is_string(source) {
+        return None;
+    }
+    declaration.source = source.to_string();
+    declaration.is_side_effect_only = !declaration.has_bindings();
+    (declaration.attributes, declaration.end) = parse_statement_end(tokens, index + 1);
+    Some(declaration)
+}
+
+/// Measures `export { a }`, `export type { A }` or `export { a } from './a'`
+/// starting at the `export` keyword.
+pub fn parse_export_list(tokens: &[String], position: usize) -> Option<ExportList> {
+    let mut index = next_index(tokens, position + 1);
+    let is_type_only = next_token(tokens, index) == "type";
+    if is_type_only {
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) != "{" {
+        return None;
+    }
+    let specifiers = parse_specifiers(tokens, index);
+    index = matching_close(tokens, index) + 1;
+
+    let mut source = None;
+    if next_token(tokens, index) == "from" {
+        index = next_index(tokens, next_index(tokens, index) + 1);
+        source = Some(next_token(tokens, index).to_string());
+        index += 1;
+    }
+    let (attributes, end) = parse_statement_end(tokens, index);
+    Some(ExportList {
+        is_type_only,
+        is_empty: specifiers.is_empty(),
+        specifiers,
+        source,
+        attributes,
+        end,
+    })
+}
+
+/// Measures `export type * from './a'` and `export type * as a from './a'`.
+pub fn type_only_export_all_end(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = next_index(tokens, position + 1);
+    if next_token(tokens, index) != "type" || next_token(tokens, index + 1) != "*" {
+        return None;
+    }
+    while index < tokens.len() && !is_string(&tokens[index]) {
+        index += 1;
+    }
+    Some(parse_statement_end(tokens, index + 1).1)
+}
+
+/// Measures `export * from './a'` and `export * as a from './a'` starting at
+/// the `export` keyword.
+pub fn parse_export_all(tokens: &[String], position: usize) -> Option<ExportAll> {
+    let mut index = next_index(tokens, position + 1);
+    if next_token(tokens, index) != "*" {
+        return None;
+    }
+    index = next_index(tokens, index + 1);
+    let mut namespace = None;
+    if next_token(tokens, index) == "as" {
+        index = next_index(tokens, index + 1);
+        namespace = Some(tokens[index].clone());
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) != "from" {
+        return None;
+    }
+    index = next_index(tokens, index + 1);
+    let source = next_token(tokens, index);
+    if !is_string(source) {
+        return None;
+    }
+    let (attributes, end) = parse_statement_end(tokens, index + 1);
+    Some(ExportAll {
+        namespace,
+        source: source.to_string(),
+        attributes,
+        end,
+    })
+}
+
+/// Measures `import a = require('a')` and `import A = N.B` starting at the
+/// `import` keyword, or at an `export` before it.
+pub fn parse_import_equals(tokens: &[String], position: usize) -> Option<ImportEquals> {
+    let mut index = position;
+    let is_exported = tokens[index] == "export";
+    if is_exported {
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) != "import" {
+        return None;
+    }
+    index = next_index(tokens, index + 1);
+    // `import type = require()` imports something named `type`.
+    let is_type_only = next_token(tokens, index) == "type"
+        && is_identifier(next_token(tokens, index + 1))
+        && next_token(tokens, next_index(tokens, index + 1) + 1) == "=";
+    if is_type_only {
+        index = next_index(tokens, index + 1);
+    }
+    let name = next_token(tokens, index).to_string();
+    index = next_index(tokens, index + 1);
+    if !is_identifier(&name) || next_token(tokens, index) != "=" {
+        return None;
+    }
+    index = next_index(tokens, index + 1);
+
+    let mut declaration = ImportEquals {
+        is_exported,
+        is_type_only,
+        name,
+        source: None,
+        entity: vec![],
+        end: index,
+    };
+    if next_token(tokens, index) == "require" && next_token(tokens, index + 1) == "(" {
+        let opening = next_index(tokens, index + 1);
+        let source = next_token(tokens, opening + 1);
+        if !is_string(source) {
+            return None;
+        }
+        declaration.source = Some(source.to_string());
+        index = matching_close(tokens, opening) + 1;
+    } else {
+        while is_identifier(next_token(tokens, index)) {
+            index = next_index(tokens, index);
+            declaration.entity.push(tokens[index].clone());
+            index += 1;
+            if next_token(tokens, index) != "." {
+                break;
+            }
+            index = next_index(tokens, index);
+            declaration.entity.push(tokens[index].clone());
+            index += 1;
+        }
+        if matches!(
+            declaration.entity.last().map(String::as_str),
+            None | Some(".")
+        ) {
+            return None;
+        }
+    }
+    if next_token(tokens, index) == ";" {
+        index = next_index(tokens, index) + 1;
+    }
+    declaration.end = index;
+    Some(declaration)
+}
@@ -0,0 +1,214 @@
+/// A parsed JSON value. Objects keep their keys in the order they were written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// The value of `key` when this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Json::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct Reader {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    // Skips whitespace and the `//` and `/* */` comments `tsconfig.json` allows.
+    fn skip_trivia(&mut self) {
+        while let Some(char) = self.peek() {
+            let next = self.chars.get(self.position + 1).copied();
+            if char.is_whitespace() || char == '\u{feff}' {
+                self.position += 1;
+            } else if char == '/' && next == Some('/') {
+                while self.peek().is_some_and(|char| char != '\n') {
+                    self.position += 1;
+                }
+            } else if char == '/' && next == Some('*') {
+                self.position += 2;
+                while self.position < self.chars.len()
+                    && !(self.chars[self.position] == '*'
+                        && self.chars.get(self.position + 1) == Some(&'/'))
+                {
+                    self.position += 1;
+                }
+                self.position += 2;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.skip_trivia();
+        if self.peek() != Some(expected) {
+            return None;
+        }
+        self.position += 1;
+        Some(())
+    }
+
+    fn read_value(&mut self) -> Option<Json> {
+        self.skip_trivia();
+        match self.peek()? {
+            '{' => self.read_object(),
+            '[' => self.read_array(),
+            '"' => self.read_string().map(Json::String),
+            _ => self.read_literal(),
+        }
+    }
+
+    fn read_object(&mut self) -> Option<Json> {
+        self.position += 1;
+        let mut entries = vec![];
+        loop {
+            self.skip_trivia();
+            if self.peek()? == '}' {
+                self.position += 1;
+                return Some(Json::Object(entries));
+            }
+            let key = self.read_string()?;
+            self.expect(':')?;
+            entries.push((key, self.read_value()?));
+            self.skip_trivia();
+            // A trailing comma is fine before the `}`.
+            if self.peek()? == ',' {
+                self.position += 1;
+            }
+        }
+    }
+
+    fn read_array(&mut self) -> Option<Json> {
+        self.position += 1;
+        let mut values = vec![];
+        loop {
+            self.skip_trivia();
+            if self.peek()? == ']' {
+                self.position += 1;
+                return Some(Json::Array(values));
+            }
+            values.push(self.read_value()?);
+            self.skip_trivia();
+            if self.peek()? == ',' {
+                self.position += 1;
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Option<String> {
+        self.skip_trivia();
+        if self.peek()? != '"' {
+            return None;
+        }
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            let char = self.peek()?;
+            self.position += 1;
+            match char {
+                '"' => return Some(value),
+                '\\' => {
+                    let escaped = self.peek()?;
+                    self.position += 1;
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'r' => value.push('\r'),
+                        'b' => value.push('\u{8}'),
+                        'f' => value.push('\u{c}'),
+                        'u' => {
+                            let code: String = self
+                                .chars
+                                .get(self.position..self.position + 4)?
+                                .iter()
+                                .collect();
+                            self.position += 4;
+                            value.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                        }
+                        _ => value.push(escaped),
+                    }
+                }
+                _ => value.push(char),
+            }
+        }
+    }
+
+    fn read_literal(&mut self) -> Option<Json> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|char| char.is_alphanumeric() || matches!(char, '-' | '+' | '.'))
+        {
+            self.position += 1;
+        }
+        let literal: String = self.chars[start..self.position].iter().collect();
+        match literal.as_str() {
+            "null" => Some(Json::Null),
+            "true" => Some(Json::Bool(true)),
+            "false" => Some(Json::Bool(false)),
+            _ => literal.parse().ok().map(Json::Number),
+        }
+    }
+}
+
+/// Parses JSON, also accepting the comments and trailing commas that
+/// `tsconfig.json` files are allowed to have.
+pub fn parse(source: &str) -> Option<Json> {
+    let mut reader = Reader {
+        chars: source.chars().collect(),
+        position: 0,
+    };
+    let value = reader.read_value()?;
+    reader.skip_trivia();
+    match reader.position >= reader.chars.len() {
+        true => Some(value),
+        false => None,
+    }
+}
@@ -0,0 +1,412 @@
+use crate::options::{JsxEmit, Options};
+
+/// What can be written between the tags of an element, or as the value of
+/// an attribute.
+pub enum JsxChild {
+    // Text as written, including the quotes when it is an attribute value.
+    Text(String),
+    // The tokens between `{` and `}`.
+    Expression(Vec<String>),
+    Spread(Vec<String>),
+    Element(JsxElement),
+}
+
+pub enum JsxAttribute {
+    // `name="value"`, or just `name`, which is `true`.
+    Named(String, Option<JsxChild>),
+    // `{...props}`
+    Spread(Vec<String>),
+}
+
+pub struct JsxElement {
+    // `None` for a fragment, `<>...</>`.
+    pub name: Option<String>,
+    pub attributes: Vec<JsxAttribute>,
+    pub children: Vec<JsxChild>,
+}
+
+/// Writes JSX as the calls the `jsx` option asks for: `React.createElement`
+/// (or the factory set with `jsxFactory` or a `@jsx` comment) for `react`,
+/// and the `jsx` functions of `<jsxImportSource>/jsx-runtime` for
+/// `react-jsx`.
+pub struct JsxFactory {
+    is_automatic: bool,
+    factory: String,
+    fragment_factory: String,
+    import_source: String,
+    // The functions of the automatic runtime that have been called.
+    imports: Vec<&'static str>,
+}
+
+impl JsxFactory {
+    /// The factory for a module, where the `@jsx`, `@jsxFrag`,
+    /// `@jsxImportSource` and `@jsxRuntime` comments at its top take
+    /// precedence over the options.
+    pub fn new(contents: &str, options: &Options) -> JsxFactory {
+        let mut factory = JsxFactory {
+            is_automatic: options.jsx == JsxEmit::ReactJsx,
+            factory: options.jsx_factory.clone(),
+            fragment_factory: options.jsx_fragment_factory.clone(),
+            import_source: options.jsx_import_source.clone(),
+            imports: vec![],
+        };
+        for (name, value) in pragmas(contents) {
+            match name.as_str() {
+                "jsx" => factory.factory = value,
+                "jsxFrag" => factory.fragment_factory = value,
+                "jsxImportSource" => factory.import_source = value,
+                "jsxRuntime" => factory.is_automatic = value == "automatic",
+                _ => {}
+            }
+        }
+        factory
+    }
+
+    /// The tokens of the call an element is written as.
+    pub fn element(&mut self, element: &JsxElement) -> Vec<String> {
+        match self.is_automatic {
+            true => self.automatic_element(element),
+            false => self.classic_element(element),
+        }
+    }
+
+    // `React.createElement("div", { id: "a" }, "text")`
+    fn classic_element(&mut self, element: &JsxElement) -> Vec<String> {
+        let mut tokens = member_tokens(&self.factory);
+        tokens.push(String::from("("));
+        tokens.extend(match &element.name {
+            Some(name) => element_type(name),
+            None => member_tokens(&self.fragment_factory),
+        });
+        tokens.push(String::from(","));
+        match element.attributes.is_empty() {
+            true => tokens.push(String::from("null")),
+            false => tokens.extend(self.props(&element.attributes, vec![])),
+        }
+        for child in self.children(&element.children) {
+            tokens.push(String::from(","));
+            tokens.extend(child);
+        }
+        tokens.push(String::from(")"));
+        tokens
+    }
+
+    // `_jsx("div", { id: "a", children: "text" }, key)`, or `_jsxs` when
+    // there is more than one child.
+    fn automatic_element(&mut self, element: &JsxElement) -> Vec<String> {
+        let children = self.children(&element.children);
+        let function = match children.len() > 1 {
+            true => "jsxs",
+            false => "jsx",
+        };
+        let mut tokens = vec![self.import(function), String::from("(")];
+        tokens.extend(match &element.name {
+            Some(name) => element_type(name),
+            None => vec![self.import("Fragment")],
+        });
+        tokens.push(String::from(","));
+
+        let mut key = None;
+        let mut attributes = vec![];
+        for attribute in &element.attributes {
+            match attribute {
+                JsxAttribute::Named(name, value) if name == "key" => key = Some(value),
+                _ => attributes.push(attribute),
+            }
+        }
+        let mut children_property = vec![];
+        if !children.is_empty() {
+            children_property = vec![String::from("children"), String::from(":")];
+            match children.len() {
+                1 => children_property.extend(children.into_iter().flatten()),
+                _ => {
+                    children_property.push(String::from("["));
+                    for (index, child) in children.into_iter().enumerate() {
+                        if index > 0 {
+                            children_property.push(String::from(","));
+                        }
+                        children_property.extend(child);
+                    }
+                    children_property.push(String::from("]"));
+                }
+            }
+        }
+        tokens.extend(self.props(attributes, children_property));
+
+        if let Some(key) = key {
+            tokens.push(String::from(","));
+            tokens.extend(self.value(key.as_ref()));
+        }
+        tokens.push(String::from(")"));
+        tokens
+    }
+
+    // The object the attributes are passed as, followed by `extra`.
+    fn props<'a>(
+        &mut self,
+        attributes: impl IntoIterator<Item = &'a JsxAttribute>,
+        extra: Vec<String>,
+    ) -> Vec<String> {
+        let mut properties = vec![];
+        for attribute in attributes {
+            properties.push(match attribute {
+                JsxAttribute::Named(name, value) => {
+                    let mut property = vec![property_name(name), String::from(":")];
+                    property.extend(self.value(value.as_ref()));
+                    property
+                }
+                JsxAttribute::Spread(expression) => {
+                    let mut property = vec![String::from("...")];
+                    property.extend(expression.iter().cloned());
+                    property
+                }
+            });
+        }
+        if !extra.is_empty() {
+            properties.push(extra);
+        }
+
+        let mut tokens = vec![String::from("{")];
+        for (index, property) in properties.into_iter().enumerate() {
+            if index > 0 {
+                tokens.push(String::from(","));
+            }
+            tokens.extend(property);
+        }
+        tokens.push(String::from("}"));
+        tokens
+    }
+
+    fn value(&mut self, value: Option<&JsxChild>) -> Vec<String> {
+        match value {
+            None => vec![String::from("true")],
+            Some(JsxChild::Text(text)) => {
+                vec![string_literal(&decode_entities(&text[1..text.len() - 1]))]
+            }
+            Some(JsxChild::Expression(expression)) => expression.clone(),
+            Some(JsxChild::Spread(expression)) => expression.clone(),
+            Some(JsxChild::Element(element)) => self.element(element),
+        }
+    }
+
+    // The arguments the children are passed as, without the text that is
+    // only white space and the empty `{}` or `{/* comments */}`.
+    fn children(&mut self, children: &[JsxChild]) -> Vec<Vec<String>> {
+        let mut arguments = vec![];
+        for child in children {
+            let argument = match child {
+                JsxChild::Text(text) => match clean_text(text) {
+                    Some(text) => vec![string_literal(&decode_entities(&text))],
+                    None => continue,
+                },
+                JsxChild::Expression(expression) if expression.is_empty() => continue,
+                JsxChild::Expression(expression) => expression.clone(),
+                JsxChild::Spread(expression) => {
+                    let mut argument = vec![String::from("...")];
+                    argument.extend(expression.iter().cloned());
+                    argument
+                }
+                JsxChild::Element(element) => self.element(element),
+            };
+            arguments.push(argument);
+        }
+        arguments
+    }
+
+    fn import(&mut self, function: &'static str) -> String {
+        if !self.imports.contains(&function) {
+            self.imports.push(function);
+        }
+        format!("_{}", function)
+    }
+
+    /// `import { jsx as _jsx } from "react/jsx-runtime";` for the functions of
+    /// the automatic runtime that were called. It goes in front of the
+    /// module and is written like any other import.
+    pub fn runtime_import(&self) -> Vec<String> {
+        if self.imports.is_empty() {
+            return vec![];
+        }
+        let mut tokens = vec![String::from("import"), String::from("{")];
+        for function in ["jsx", "jsxs", "Fragment"] {
+            if !self.imports.contains(&function) {
+                continue;
+            }
+            if tokens.len() > 2 {
+                tokens.push(String::from(","));
+            }
+            tokens.push(function.to_string());
+            tokens.push(String::from("as"));
+            tokens.push(format!("_{}", function));
+        }
+        tokens.push(String::from("}"));
+        tokens.push(String::from("from"));
+        tokens.push(string_literal(&format!(
+            "{}/jsx-runtime",
+            self.import_source
+        )));
+        tokens.push(String::from(";"));
+        tokens.push(String::from("\n"));
+        tokens
+    }
+}
+
+// `@jsx h` style comments before the first statement of a module.
+fn pragmas(contents: &str) -> Vec<(String, String)> {
+    let mut pragmas = vec![];
+    let mut rest = contents.trim_start();
+    loop {
+        let comment = if let Some(comment) = rest.strip_prefix("//") {
+            let end = comment.find('\n').unwrap_or(comment.len());
+            rest = &comment[end..];
+            &comment[..end]
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment.find("*/").unwrap_or(comment.len());
+            rest = &comment[(end + 2).min(comment.len())..];
+            &comment[..end]
+        } else {
+            break;
+        };
+        let mut words = comment.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let Some(name) = word.strip_prefix('@') else {
+                continue;
+            };
+            if name.starts_with("jsx") {
+                if let Some(value) = words.peek() {
+                    pragmas.push((name.to_string(), value.to_string()));
+                }
+            }
+        }
+        rest = rest.trim_start();
+    }
+    pragmas
+}
+
+// `React.createElement` as the tokens `React`, `.` and `createElement`.
+fn member_tokens(name: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    for (index, part) in name.split('.').enumerate() {
+        if index > 0 {
+            tokens.push(String::from("."));
+        }
+        tokens.push(part.to_string());
+    }
+    tokens
+}
+
+// Intrinsic elements like `div` or `my-element` are passed by name,
+// components like `Foo` or `foo.Bar` as the value they are.
+fn element_type(name: &str) -> Vec<String> {
+    let is_intrinsic = (name.starts_with(|char: char| char.is_ascii_lowercase())
+        && !name.contains('.'))
+        || name.contains(['-', ':']);
+    match is_intrinsic {
+        true => vec![string_literal(name)],
+        false => member_tokens(name),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    match name.contains(['-', ':']) {
+        true => string_literal(name),
+        false => name.to_string(),
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    let mut literal = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => literal += "\\\"",
+            '\\' => literal += "\\\\",
+            '\n' => literal += "\\n",
+            '\r' => literal += "\\r",
+            '\t' => literal += "\\t",
+            _ if char.is_control() => literal += &format!("\\u{:04x}", char as u32),
+            _ => literal.push(char),
+        }
+    }
+    literal + "\""
+}
+
+// Text children the way React sees them: lines are trimmed, lines with only
+// white space are dropped and the rest are joined with a space. `None` when
+// nothing is left.
+fn clean_text(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text.split(['\n', '\r']).collect();
+    let last_non_empty = lines.iter().rposition(|line| !line.trim().is_empty())?;
+    let mut cleaned = String::new();
+    for (index, line) in lines.iter().enumerate() {
+        let mut line = line.replace('\t', " ");
+        if index > 0 {
+            line = line.trim_start_matches(' ').to_string();
+        }
+        if index < lines.len() - 1 {
+            line = line.trim_end_matches(' ').to_string();
+        }
+        if line.is_empty() {
+            continue;
+        }
+        cleaned += &line;
+        if index != last_non_empty {
+            cleaned.push(' ');
+        }
+    }
+    Some(cleaned)
+}
+
+const ENTITIES: [(&str, char); 16] = [
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("mdash", '—'),
+    ("ndash", '–'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("times", '×'),
+    ("middot", '·'),
+];
+
+// `&amp;`, `&#123;` and `&#x7B;` as the characters they stand for.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded += &rest[..start];
+        rest = &rest[start..];
+        let character = rest.find(';').and_then(|end| {
+            let name = &rest[1..end];
+            let character = match name.strip_prefix('#') {
+                Some(code) => match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => code.parse().ok(),
+                }
+                .and_then(char::from_u32),
+                None => ENTITIES
+                    .iter()
+                    .find(|(entity, _)| *entity == name)
+                    .map(|(_, character)| *character),
+            };
+            character.map(|character| (character, end))
+        });
+        match character {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded + rest
+}
@@ -0,0 +1,536 @@
+/// Multi character punctuators, longest first so the first match wins.
+///
+/// `>` is deliberately never combined with a following `>` so that nested
+/// generics like `Map<string, Array<number>>` close one bracket at a time.
+/// Shift operators are still written out correctly because adjacent
+/// punctuators are never separated in the output.
+const PUNCTUATORS: [&str; 26] = [
+    "...", "===", "!==", "**=", "&&=", "||=", "??=", "=>", "==", "!=", "<=", ">=", "&&", "||",
+    "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+];
+
+/// Keywords after which a `/` starts a regular expression rather than a division.
+const REGEX_PRECEDING_KEYWORDS: [&str; 14] = [
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+pub fn is_identifier_char(char: char) -> bool {
+    char.is_alphanumeric()
+        || char == '_'
+        || char == '$'
+        || (!char.is_ascii() && !char.is_whitespace())
+}
+
+fn is_line_break(char: char) -> bool {
+    char == '\n' || char == '\r' || char == '\u{2028}' || char == '\u{2029}'
+}
+
+use super::jsx::{JsxAttribute, JsxChild, JsxElement, JsxFactory};
+
+struct Lexer {
+    chars: Vec<char>,
+    position: usize,
+    tokens: Vec<String>,
+    // Each open `{` pushes `false`, each template substitution `${` pushes `true`
+    // so we know whether a `}` closes a block or resumes a template string.
+    brace_stack: Vec<bool>,
+    // Set for `.tsx` files, where JSX elements are read and written as calls.
+    jsx: Option<JsxFactory>,
+}
+
+impl Lexer {
+    fn new(contents: &str) -> Lexer {
+        Lexer {
+            chars: contents.chars().collect(),
+            position: 0,
+            tokens: vec![],
+            brace_stack: vec![],
+            jsx: None,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn starts_with(&self, value: &str) -> bool {
+        value
+            .chars()
+            .enumerate()
+            .all(|(index, char)| self.peek(index) == Some(char))
+    }
+
+    fn push(&mut self, value: String) {
+        self.tokens.push(value);
+    }
+
+    fn push_line_break(&mut self) {
+        if self.tokens.last().map(|token| token.as_str()) != Some("\n") {
+            self.tokens.push(String::from("\n"));
+        }
+    }
+
+    fn previous_token(&self) -> Option<&String> {
+        self.tokens
+            .iter()
+            .rev()
+            .find(|token| token.as_str() != "\n")
+    }
+
+    // A `/` is a regular expression when the previous token can't end an expression.
+    fn is_regex_allowed(&self) -> bool {
+        match self.previous_token() {
+            None => true,
+            Some(token) => {
+                let last = token.chars().last().unwrap_or(' ');
+                if token == ")" || token == "]" || token == "}" || token.ends_with('`') {
+                    return false;
+                }
+                if is_identifier_char(last) || last == '\'' || last == '"' {
+                    return REGEX_PRECEDING_KEYWORDS.contains(&token.as_str());
+                }
+                // `x++ / 2` divides, but `/re/` after any other operator is a regex.
+                token != "++" && token != "--"
+            }
+        }
+    }
+
+    fn read_while(&mut self, predicate: fn(char) -> bool) -> String {
+        let mut value = String::new();
+        while let Some(char) = self.peek(0) {
+            if !predicate(char) {
+                break;
+            }
+            value.push(char);
+            self.position += 1;
+        }
+        value
+    }
+
+    fn read_string(&mut self, quote: char) -> String {
+        let mut value = String::from(quote);
+        self.position += 1;
+        while let Some(char) = self.peek(0) {
+            value.push(char);
+            self.position += 1;
+            if char == '\\' {
+                if let Some(escaped) = self.peek(0) {
+                    value.push(escaped);
+                    self.position += 1;
+                }
+            } else if char == quote || is_line_break(char) {
+                break;
+            }
+        }
+        value
+    }
+
+    // Reads template characters up to and including the closing backtick or the
+    // next `${`. `value` already holds the opening "`" or the resuming "}".
+    fn read_template(&mut self, mut value: String) -> String {
+        while let Some(char) = self.peek(0) {
+            if char == '\\' {
+                value.push(char);
+                self.position += 1;
+                if let Some(escaped) = self.peek(0) {
+                    value.push(escaped);
+                    self.position += 1;
+                }
+                continue;
+            }
+            if char == '`' {
+                value.push(char);
+                self.position += 1;
+                return value;
+            }
+            if self.starts_with("${") {
+                value += "${";
+                self.position += 2;
+                self.brace_stack.push(true);
+                return value;
+            }
+            value.push(char);
+            self.position += 1;
+        }
+        value
+    }
+
+    fn read_regex(&mut self) -> String {
+        let mut value = String::from("/");
+        self.position += 1;
+        let mut in_class = false;
+        while let Some(char) = self.peek(0) {
+            if is_line_break(char) {
+                break;
+            }
+            value.push(char);
+            self.position += 1;
+            match char {
+                '\\' => {
+                    if let Some(escaped) = self.peek(0) {
+                        value.push(escaped);
+                        self.position += 1;
+                    }
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => {}
+            }
+        }
+        value + &self.read_while(is_identifier_char)
+    }
+
+    fn read_number(&mut self) -> String {
+        let mut value = String::new();
+        while let Some(char) = self.peek(0) {
+            let is_exponent_sign = (char == '+' || char == '-')
+                && (value.ends_with('e') || value.ends_with('E'))
+                && !value.starts_with("0x")
+                && !value.starts_with("0X");
+            let is_decimal_point = char == '.' && !value.contains('.');
+            if is_identifier_char(char) || is_decimal_point || is_exponent_sign {
+                value.push(char);
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        value
+    }
+
+    fn skip_comment(&mut self) {
+        if self.starts_with("//") {
+            while let Some(char) = self.peek(0) {
+                if is_line_break(char) {
+                    break;
+                }
+                self.position += 1;
+            }
+            return;
+        }
+
+        self.position += 2;
+        while self.peek(0).is_some() && !self.starts_with("*/") {
+            if is_line_break(self.peek(0).unwrap()) {
+                self.push_line_break();
+            }
+            self.position += 1;
+        }
+        self.position += 2;
+    }
+
+    fn read_punctuator(&mut self) -> String {
+        for punctuator in PUNCTUATORS.iter() {
+            if self.starts_with(punctuator) {
+                // `a?.5:1` is a ternary, not optional chaining.
+                if *punctuator == "?." && self.peek(2).is_some_and(|c| c.is_ascii_digit()) {
+                    continue;
+                }
+                self.position += punctuator.len();
+                return punctuator.to_string();
+            }
+        }
+        let char = self.peek(0).unwrap();
+        self.position += 1;
+        char.to_string()
+    }
+
+    fn tokenize(mut self) -> Vec<String> {
+        if self.starts_with("#!") {
+            while self.peek(0).is_some_and(|char| !is_line_break(char)) {
+                self.position += 1;
+            }
+        }
+
+        while self.read_token() {}
+
+        match &self.jsx {
+            Some(jsx) => [jsx.runtime_import(), self.tokens].concat(),
+            None => self.tokens,
+        }
+    }
+
+    // Reads the next token, or skips white space or a comment. Returns
+    // `false` at the end of the source.
+    fn read_token(&mut self) -> bool {
+        let Some(char) = self.peek(0) else {
+            return false;
+        };
+        if is_line_break(char) {
+            self.push_line_break();
+            self.position += 1;
+            return true;
+        }
+        if char.is_whitespace() || char == '\u{feff}' {
+            self.position += 1;
+            return true;
+        }
+        if self.starts_with("//") || self.starts_with("/*") {
+            self.skip_comment();
+            return true;
+        }
+
+        let token = match char {
+            '\'' | '"' => self.read_string(char),
+            '`' => {
+                self.position += 1;
+                self.read_template(String::from("`"))
+            }
+            '{' => {
+                self.position += 1;
+                self.brace_stack.push(false);
+                String::from("{")
+            }
+            '}' => {
+                self.position += 1;
+                if self.brace_stack.pop() == Some(true) {
+                    self.read_template(String::from("}"))
+                } else {
+                    String::from("}")
+                }
+            }
+            '/' if self.is_regex_allowed() => self.read_regex(),
+            '<' if self.jsx.is_some() && self.is_regex_allowed() => {
+                if let Some(tokens) = self.read_jsx() {
+                    self.tokens.extend(tokens);
+                    return true;
+                }
+                self.read_punctuator()
+            }
+            '.' if self.peek(1).is_some_and(|c| c.is_ascii_digit()) => self.read_number(),
+            '#' if self.peek(1).is_some_and(is_identifier_char) => {
+                self.position += 1;
+                String::from("#") + &self.read_while(is_identifier_char)
+            }
+            _ if char.is_ascii_digit() => self.read_number(),
+            _ if is_identifier_char(char) => self.read_while(is_identifier_char),
+            _ => self.read_punctuator(),
+        };
+        self.push(token);
+        true
+    }
+
+    // A `<` where an expression can start is a JSX element, unless it can't
+    // be read as one, like the type parameters of `<T,>(a: T) => a`. Then
+    // everything read since is dropped and it is read as a `<` after all.
+    fn read_jsx(&mut self) -> Option<Vec<String>> {
+        let (position, length, brace_stack) =
+            (self.position, self.tokens.len(), self.brace_stack.clone());
+        match self.read_jsx_element() {
+            Some(element) => Some(
+                self.jsx
+                    .as_mut()
+                    .expect("Checked by caller")
+                    .element(&element),
+            ),
+            None => {
+                self.position = position;
+                self.tokens.truncate(length);
+                self.brace_stack = brace_stack;
+                None
+            }
+        }
+    }
+
+    fn skip_jsx_trivia(&mut self) {
+        while let Some(char) = self.peek(0) {
+            if self.starts_with("//") || self.starts_with("/*") {
+                let length = self.tokens.len();
+                self.skip_comment();
+                self.tokens.truncate(length);
+            } else if char.is_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    // `div`, `my-element`, `svg:rect` or `Foo.Bar`.
+    fn read_jsx_name(&mut self) -> Option<String> {
+        let name = self.read_while(|char| {
+            is_identifier_char(char) || char == '-' || char == '.' || char == ':'
+        });
+        match name.starts_with(|char: char| is_identifier_char(char) && !char.is_ascii_digit()) {
+            true => Some(name),
+            false => None,
+        }
+    }
+
+    fn read_jsx_element(&mut self) -> Option<JsxElement> {
+        self.position += 1;
+        self.skip_jsx_trivia();
+        if self.peek(0)? == '>' {
+            self.position += 1;
+            let children = self.read_jsx_children()?;
+            self.read_jsx_closing_tag(None)?;
+            return Some(JsxElement {
+                name: None,
+                attributes: vec![],
+                children,
+            });
+        }
+
+        let name = self.read_jsx_name()?;
+        let mut attributes = vec![];
+        loop {
+            self.skip_jsx_trivia();
+            match self.peek(0)? {
+                '/' if self.peek(1) == Some('>') => {
+                    self.position += 2;
+                    return Some(JsxElement {
+                        name: Some(name),
+                        attributes,
+                        children: vec![],
+                    });
+                }
+                '>' => {
+                    self.position += 1;
+                    let children = self.read_jsx_children()?;
+                    self.read_jsx_closing_tag(Some(&name))?;
+                    return Some(JsxElement {
+                        name: Some(name),
+                        attributes,
+                        children,
+                    });
+                }
+                '{' => match self.read_jsx_expression()? {
+                    JsxChild::Spread(expression) => {
+                        attributes.push(JsxAttribute::Spread(expression))
+                    }
+                    _ => return None,
+                },
+                _ => {
+                    let attribute = self.read_jsx_name()?;
+                    self.skip_jsx_trivia();
+                    if self.peek(0)? != '=' {
+                        attributes.push(JsxAttribute::Named(attribute, None));
+                        continue;
+                    }
+                    self.position += 1;
+                    self.skip_jsx_trivia();
+                    let value = match self.peek(0)? {
+                        quote @ ('\'' | '"') => {
+                            let start = self.position;
+                            self.position += 1;
+                            while self.peek(0)? != quote {
+                                self.position += 1;
+                            }
+                            self.position += 1;
+                            JsxChild::Text(self.chars[start..self.position].iter().collect())
+                        }
+                        '{' => self.read_jsx_expression()?,
+                        '<' => JsxChild::Element(self.read_jsx_element()?),
+                        _ => return None,
+                    };
+                    attributes.push(JsxAttribute::Named(attribute, Some(value)));
+                }
+            }
+        }
+    }
+
+    // Everything up to the `</` of the closing tag.
+    fn read_jsx_children(&mut self) -> Option<Vec<JsxChild>> {
+        let mut children = vec![];
+        loop {
+            match self.peek(0)? {
+                '<' => {
+                    let start = self.position;
+                    self.position += 1;
+                    self.skip_jsx_trivia();
+                    let is_closing = self.peek(0)? == '/';
+                    self.position = start;
+                    if is_closing {
+                        return Some(children);
+                    }
+                    children.push(JsxChild::Element(self.read_jsx_element()?));
+                }
+                '{' => children.push(self.read_jsx_expression()?),
+                _ => {
+                    let text = self.read_while(|char| char != '<' && char != '{');
+                    children.push(JsxChild::Text(text));
+                }
+            }
+        }
+    }
+
+    // `</name>`, or `</>` when there is no `name`.
+    fn read_jsx_closing_tag(&mut self, name: Option<&str>) -> Option<()> {
+        self.position += 1;
+        self.skip_jsx_trivia();
+        self.position += 1;
+        self.skip_jsx_trivia();
+        if let Some(name) = name {
+            if self.read_jsx_name()? != name {
+                return None;
+            }
+            self.skip_jsx_trivia();
+        }
+        if self.peek(0)? != '>' {
+            return None;
+        }
+        self.position += 1;
+        Some(())
+    }
+
+    // `{expression}` or `{...expression}`, read like any other code up to the
+    // `}` that closes it.
+    fn read_jsx_expression(&mut self) -> Option<JsxChild> {
+        self.position += 1;
+        let start = self.tokens.len();
+        // The first token inside starts an expression, as it would after `{`.
+        self.push(String::from("{"));
+        self.brace_stack.push(false);
+        let depth = self.brace_stack.len();
+        loop {
+            if self.peek(0)? == '}' && self.brace_stack.len() == depth {
+                self.brace_stack.pop();
+                self.position += 1;
+                break;
+            }
+            if !self.read_token() {
+                return None;
+            }
+        }
+
+        let mut tokens: Vec<String> = self.tokens.drain(start..).skip(1).collect();
+        while tokens.last().map(String::as_str) == Some("\n") {
+            tokens.pop();
+        }
+        let leading = tokens.iter().take_while(|token| *token == "\n").count();
+        tokens.drain(..leading);
+        match tokens.first().map(String::as_str) {
+            Some("...") => Some(JsxChild::Spread(tokens.split_off(1))),
+            _ => Some(JsxChild::Expression(tokens)),
+        }
+    }
+}
+
+/// Splits source text into tokens. Comments and whitespace are dropped, but
+/// every run of line breaks is kept as a single `"\n"` token because a line
+/// break can end a statement or a type annotation.
+pub fn tokenize(contents: &str) -> Vec<String> {
+    Lexer::new(contents).tokenize()
+}
+
+/// Like `tokenize`, for a `.tsx` file. JSX elements are tokens of the calls
+/// `jsx` writes them as.
+pub fn tokenize_jsx(contents: &str, jsx: JsxFactory) -> Vec<String> {
+    let mut lexer = Lexer::new(contents);
+    lexer.jsx = Some(jsx);
+    lexer.tokenize()
+}
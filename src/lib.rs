@@ -1,23 +1,85 @@
 extern crate libc;
 
+mod json;
+mod options;
 mod parser;
+mod resolver;
+mod tsconfig;
 
 use libc::c_char;
+use options::{ModuleKind, Options};
 use parser::parser;
+use resolver::Resolution;
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::fs;
+use std::path::Path;
+use std::ptr;
 
-#[no_mangle]
-pub extern "C" fn require(js_module: *const c_char) -> *const c_char {
-    let s1 = unsafe { CStr::from_ptr(js_module) };
-    let module_path = s1.to_str().unwrap();
+fn string_argument<'a>(pointer: *const c_char) -> &'a str {
+    return unsafe { CStr::from_ptr(pointer) }.to_str().unwrap();
+}
+
+fn transpile_file(module_path: &str, options: &Options) -> *const c_char {
     let contents =
         fs::read_to_string(module_path).expect("Should have been able to read the file at {}");
 
-    let updated_contents = parser(contents);
+    let updated_contents = parser(contents, Path::new(module_path), options);
 
     let c_str = CString::new(updated_contents).unwrap();
 
     return c_str.into_raw();
 }
+
+// The compiler options of the `tsconfig.json` closest to the module, if
+// there is one.
+fn module_options(module_path: &str) -> Options {
+    let mut options = Options::default();
+    if let Some(json) = tsconfig::options(Path::new(module_path)) {
+        options.update(&json);
+    }
+    return options;
+}
+
+/// Transpiles a module for the loader in `index.js`, which runs it as
+/// CommonJS whatever `module` its `tsconfig.json` asks for.
+#[no_mangle]
+pub extern "C" fn require(js_module: *const c_char) -> *const c_char {
+    let module_path = string_argument(js_module);
+    let options = Options {
+        module: ModuleKind::CommonJs,
+        ..module_options(module_path)
+    };
+
+    return transpile_file(module_path, &options);
+}
+
+/// Like `require`, with compiler options given as JSON that override the
+/// ones of `tsconfig.json`, e.g. `{"experimentalDecorators":false}`.
+#[no_mangle]
+pub extern "C" fn transpile(js_module: *const c_char, options: *const c_char) -> *const c_char {
+    let module_path = string_argument(js_module);
+    let mut module_options = module_options(module_path);
+    if let Some(json) = json::parse(string_argument(options)) {
+        module_options.update(&json);
+    }
+
+    return transpile_file(module_path, &module_options);
+}
+
+/// Finds the file the loader in `index.js` runs for `specifier` when it is
+/// required from `importer`, like `require.resolve` but also finding
+/// TypeScript files. Returns null when it can't be resolved, which leaves it
+/// to Node, e.g. for builtin modules.
+#[no_mangle]
+pub extern "C" fn resolve(specifier: *const c_char, importer: *const c_char) -> *const c_char {
+    let importer = Path::new(string_argument(importer));
+    let resolved = resolver::resolve(importer, string_argument(specifier), Resolution::Runtime);
+
+    return match resolved {
+        Some(path) => CString::new(path.to_string_lossy().into_owned())
+            .unwrap()
+            .into_raw(),
+        None => ptr::null(),
+    };
+}
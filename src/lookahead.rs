@@ -0,0 +1,497 @@
+//! Helpers that look forward through the token list. The tree only ever sees
+//! one token at a time, so anything that needs to know where a construct ends
+//! (like a type annotation) measures it here first.
+
+/// Words that start a type and still need an operand after them, e.g. `keyof T`.
+const TYPE_PREFIX_OPERATORS: [&str; 8] = [
+    "keyof", "typeof", "readonly", "unique", "infer", "asserts", "new", "abstract",
+];
+
+pub fn is_opening(token: &str) -> bool {
+    matches!(token, "(" | "[" | "{" | "<") || is_template_head(token)
+}
+
+pub fn is_closing(token: &str) -> bool {
+    matches!(token, ")" | "]" | "}" | ">") || is_template_tail(token)
+}
+
+/// "`abc${"
+pub fn is_template_head(token: &str) -> bool {
+    token.starts_with('`') && token.len() > 1 && token.ends_with("${")
+}
+
+/// "}abc`"
+pub fn is_template_tail(token: &str) -> bool {
+    token.starts_with('}') && token.len() > 1 && !token.ends_with("${")
+}
+
+pub fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|char| char.is_alphabetic() || char == '_' || char == '$')
+}
+
+fn is_literal(token: &str) -> bool {
+    let first = token.chars().next().unwrap_or(' ');
+    first.is_ascii_digit()
+        || first == '\''
+        || first == '"'
+        || (first == '.' && token.len() > 1)
+        || (first == '`' && !is_template_head(token))
+}
+
+/// Index of the first token at or after `position` that isn't a line break.
+pub fn next_index(tokens: &[String], position: usize) -> usize {
+    let mut index = position;
+    while index < tokens.len() && tokens[index] == "\n" {
+        index += 1;
+    }
+    index
+}
+
+pub fn next_token(tokens: &[String], position: usize) -> &str {
+    tokens
+        .get(next_index(tokens, position))
+        .map_or("", |token| token.as_str())
+}
+
+/// Index of the token that closes the bracket opened at `position`. Every kind
+/// of bracket is counted together, which is fine for well formed code. Angle
+/// brackets are only counted when we start on one because `<` is a comparison
+/// everywhere outside of types.
+pub fn matching_close(tokens: &[String], position: usize) -> usize {
+    let count_angles = tokens.get(position).is_some_and(|token| token == "<");
+    let mut depth = 0;
+    let mut index = position;
+    while index < tokens.len() {
+        let token = tokens[index].as_str();
+        let is_angle = token == "<" || token == ">";
+        if is_opening(token) && (count_angles || !is_angle) {
+            depth += 1;
+        } else if is_closing(token) && (count_angles || !is_angle) {
+            depth -= 1;
+            if depth == 0 {
+                return index;
+            }
+        }
+        index += 1;
+    }
+    tokens.len()
+}
+
+/// Returns the index just past the type that starts at `position`.
+///
+/// Brackets are skipped as a whole, so we only need to understand the handful
+/// of tokens that can join types together at the top level: unions,
+/// intersections, `[]` suffixes, generics, qualified names, function types and
+/// conditional types. Anything else ends the type.
+pub fn type_end(tokens: &[String], position: usize) -> usize {
+    let mut index = position;
+    let mut end = position;
+    let mut is_expecting_type = true;
+    let mut has_line_break = false;
+    // `(a: A) => B` only continues with `=>` right after a parenthesized group.
+    let mut is_after_parameters = false;
+    let mut pending_conditional_questions = 0;
+    let mut pending_conditional_colons = 0;
+    let mut previous = "";
+
+    while index < tokens.len() {
+        let token = tokens[index].as_str();
+
+        if token == "\n" {
+            has_line_break = !is_expecting_type;
+            index += 1;
+            continue;
+        }
+
+        if is_opening(token) {
+            let is_group = match token {
+                "(" => is_expecting_type || previous == "import",
+                "[" | "<" => is_expecting_type || !has_line_break,
+                _ => is_expecting_type,
+            };
+            if !is_group {
+                break;
+            }
+            index = matching_close(tokens, index);
+            // `<T>(arg: T) => T` still needs its parameters after the type parameters.
+            is_expecting_type = token == "<" && is_expecting_type;
+            is_after_parameters = token == "(" && previous != "import";
+        } else {
+            match token {
+                "|" | "&" => is_expecting_type = true,
+                "." if !is_expecting_type => is_expecting_type = true,
+                "-" if is_expecting_type => {}
+                "=>" if !is_expecting_type && is_after_parameters => is_expecting_type = true,
+                "extends" if !is_expecting_type => {
+                    pending_conditional_questions += 1;
+                    is_expecting_type = true;
+                }
+                "?" if !is_expecting_type && pending_conditional_questions > 0 => {
+                    pending_conditional_questions -= 1;
+                    pending_conditional_colons += 1;
+                    is_expecting_type = true;
+                }
+                ":" if !is_expecting_type && pending_conditional_colons > 0 => {
+                    pending_conditional_colons -= 1;
+                    is_expecting_type = true;
+                }
+                "is" if !is_expecting_type => is_expecting_type = true,
+                _ if is_expecting_type && TYPE_PREFIX_OPERATORS.contains(&token) && {
+                    let next = next_token(tokens, index + 1);
+                    is_identifier(next) || (is_opening(next) && next != "{")
+                } => {}
+                _ if is_expecting_type && (is_identifier(token) || is_literal(token)) => {
+                    is_expecting_type = false;
+                }
+                _ => break,
+            }
+            is_after_parameters = false;
+        }
+
+        has_line_break = false;
+        previous = token;
+        index += 1;
+        end = index;
+    }
+
+    end
+}
+
+/// Index of the `>` closing the type arguments that open at `position`, when
+/// everything in between is a comma separated list of types. `a < b && c > d`
+/// isn't, so it stays a comparison.
+pub fn type_arguments_close(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = position + 1;
+    loop {
+        let start = next_index(tokens, index);
+        let end = type_end(tokens, start);
+        if end == start {
+            return None;
+        }
+        index = next_index(tokens, end);
+        match next_token(tokens, index) {
+            "," => index += 1,
+            ">" => return Some(index),
+            _ => return None,
+        }
+    }
+}
+
+/// Modifiers that can come before the name of a class member.
+pub const CLASS_MEMBER_MODIFIERS: [&str; 12] = [
+    "public",
+    "private",
+    "protected",
+    "readonly",
+    "override",
+    "declare",
+    "abstract",
+    "static",
+    "async",
+    "accessor",
+    "get",
+    "set",
+];
+
+/// Modifiers that only exist in TypeScript and are dropped from the output.
+pub const TYPE_ONLY_MODIFIERS: [&str; 7] = [
+    "public",
+    "private",
+    "protected",
+    "readonly",
+    "override",
+    "declare",
+    "abstract",
+];
+
+/// `static` is only a modifier in `static foo() {}`, in `static() {}` or
+/// `static = 1` it is the name of the member.
+pub fn is_class_member_modifier(tokens: &[String], position: usize) -> bool {
+    if !CLASS_MEMBER_MODIFIERS.contains(&tokens[position].as_str()) {
+        return false;
+    }
+    let next = next_token(tokens, position + 1);
+    is_identifier(next) || is_literal(next) || next.starts_with('#') || next == "[" || next == "*"
+}
+
+/// Measures the class member starting at `position` when it only exists in the
+/// type system: `declare` and `abstract` members, index signatures and methods
+/// without a body (overloads and optional methods). Members that are written
+/// out return `None`.
+pub fn class_member_signature_end(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = next_index(tokens, position);
+    let mut is_signature = false;
+
+    while index < tokens.len() && is_class_member_modifier(tokens, index) {
+        if tokens[index] == "declare" || tokens[index] == "abstract" {
+            is_signature = true;
+        }
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) == "*" {
+        index = next_index(tokens, index + 1);
+    }
+
+    if next_token(tokens, index) == "[" {
+        // `[key: string]: T` is an index signature rather than a computed name.
+        let key = next_index(tokens, index + 1);
+        if is_identifier(next_token(tokens, key)) && next_token(tokens, key + 1) == ":" {
+            is_signature = true;
+        }
+        index = matching_close(tokens, index);
+    }
+    index = next_index(tokens, index + 1);
+
+    if matches!(next_token(tokens, index), "?" | "!") {
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) == "<" {
+        index = next_index(tokens, matching_close(tokens, index) + 1);
+    }
+
+    if next_token(tokens, index) == "(" {
+        index = matching_close(tokens, index) + 1;
+        if next_token(tokens, index) == ":" {
+            index = type_end(tokens, next_index(tokens, index) + 1);
+        }
+        if next_token(tokens, index) == "{" {
+            if !is_signature {
+                return None;
+            }
+            index = matching_close(tokens, next_index(tokens, index)) + 1;
+        }
+        is_signature = true;
+    } else if is_signature && next_token(tokens, index) == ":" {
+        index = type_end(tokens, next_index(tokens, index) + 1);
+    }
+
+    if !is_signature {
+        return None;
+    }
+    if next_token(tokens, index) == ";" {
+        index = next_index(tokens, index) + 1;
+    }
+    Some(index)
+}
+
+/// Measures `implements A, B<C>` up to the class body.
+pub fn implements_end(tokens: &[String], position: usize) -> usize {
+    let mut end = type_end(tokens, position + 1);
+    while next_token(tokens, end) == "," {
+        end = type_end(tokens, next_index(tokens, end) + 1);
+    }
+    end
+}
+
+/// Finds a `super(...)` call among the statements of the block opened at
+/// `position` and returns the index of its last token, the `;` if it has one.
+pub fn super_call_end(tokens: &[String], position: usize) -> Option<usize> {
+    let close = matching_close(tokens, position);
+    let mut index = position + 1;
+    while index < close {
+        let token = tokens[index].as_str();
+        if token == "super" && next_token(tokens, index + 1) == "(" {
+            let end = matching_close(tokens, next_index(tokens, index + 1));
+            let next = next_index(tokens, end + 1);
+            return Some(if next_token(tokens, next) == ";" {
+                next
+            } else {
+                end
+            });
+        }
+        if is_opening(token) && token != "<" {
+            index = matching_close(tokens, index);
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Measures the enum member starting at `position`, returning the index of the
+/// `,` or `}` that ends it.
+pub fn enum_member_end(tokens: &[String], position: usize) -> usize {
+    let mut index = position + 1;
+    while index < tokens.len() {
+        let token = tokens[index].as_str();
+        if token == "," || token == "}" {
+            return index;
+        }
+        if is_opening(token) && token != "<" {
+            index = matching_close(tokens, index);
+        }
+        index += 1;
+    }
+    index
+}
+
+/// Measures `type Name<T> = Type;` starting at the `type` keyword.
+pub fn type_alias_end(tokens: &[String], position: usize) -> usize {
+    let mut index = next_index(tokens, next_index(tokens, position + 1) + 1);
+    if next_token(tokens, index) == "<" {
+        index = next_index(tokens, matching_close(tokens, index) + 1);
+    }
+    let mut end = type_end(tokens, index + 1);
+    if next_token(tokens, end) == ";" {
+        end = next_index(tokens, end) + 1;
+    }
+    end
+}
+
+// Past an optional `;` at `index`.
+fn statement_end(tokens: &[String], index: usize) -> usize {
+    match next_token(tokens, index) {
+        ";" => next_index(tokens, index) + 1,
+        _ => index,
+    }
+}
+
+// Skips ahead to the body of a class, interface or enum and past it.
+fn body_end(tokens: &[String], position: usize) -> usize {
+    let mut index = position;
+    while index < tokens.len() && tokens[index] != "{" {
+        if matches!(tokens[index].as_str(), "<" | "(" | "[") {
+            index = matching_close(tokens, index);
+        }
+        index += 1;
+    }
+    statement_end(tokens, matching_close(tokens, index) + 1)
+}
+
+// Skips the name, type parameters, parameters and return type of the function
+// whose `function` keyword is at `index`.
+fn function_head_end(tokens: &[String], mut index: usize) -> usize {
+    loop {
+        index = next_index(tokens, index + 1);
+        match next_token(tokens, index) {
+            "(" | "" => break,
+            "<" => index = matching_close(tokens, index),
+            _ => {}
+        }
+    }
+    index = matching_close(tokens, index) + 1;
+    if next_token(tokens, index) == ":" {
+        index = type_end(tokens, next_index(tokens, index) + 1);
+    }
+    index
+}
+
+/// Measures an overload signature, a function declaration without a body,
+/// starting at `position` which may be its `export`, `default` or `async`.
+pub fn function_signature_end(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = next_index(tokens, position);
+    while matches!(next_token(tokens, index), "export" | "default" | "async") {
+        index = next_index(tokens, index + 1);
+    }
+    if next_token(tokens, index) != "function" {
+        return None;
+    }
+    let end = function_head_end(tokens, index);
+    match next_token(tokens, end) {
+        "{" => None,
+        _ => Some(statement_end(tokens, end)),
+    }
+}
+
+/// Measures an ambient declaration starting at its `declare` keyword, which
+/// has no runtime code at all.
+pub fn declare_end(tokens: &[String], position: usize) -> usize {
+    let mut index = next_index(tokens, position + 1);
+    match next_token(tokens, index) {
+        "global" | "module" | "namespace" => {
+            // The name, `global` has none and `module 'name'` is a string.
+            index += 1;
+            while index < tokens.len()
+                && (is_identifier(&tokens[index])
+                    || is_literal(&tokens[index])
+                    || tokens[index] == ".")
+            {
+                index += 1;
+            }
+            match next_token(tokens, index) {
+                "{" => statement_end(tokens, matching_close(tokens, index) + 1),
+                _ => statement_end(tokens, index),
+            }
+        }
+        "const" if next_token(tokens, index + 1) == "enum" => body_end(tokens, index),
+        "const" | "let" | "var" => {
+            loop {
+                index = next_index(tokens, index + 1);
+                if is_opening(next_token(tokens, index)) {
+                    index = matching_close(tokens, index);
+                }
+                index = next_index(tokens, index + 1);
+                if next_token(tokens, index) == ":" {
+                    index = type_end(tokens, index + 1);
+                }
+                if next_token(tokens, index) != "," {
+                    break;
+                }
+                index = next_index(tokens, index);
+            }
+            statement_end(tokens, index)
+        }
+        "function" => statement_end(tokens, function_head_end(tokens, index)),
+        "type" => type_alias_end(tokens, index),
+        _ => body_end(tokens, index),
+    }
+}
+
+/// The index of the `{` of `namespace A.B {` or `module A {` starting at
+/// `position`, if that's what it is.
+pub fn namespace_body(tokens: &[String], position: usize) -> Option<usize> {
+    let mut index = next_index(tokens, position + 1);
+    loop {
+        if !is_identifier(next_token(tokens, index)) {
+            return None;
+        }
+        index = next_index(tokens, index + 1);
+        match next_token(tokens, index) {
+            "." => index = next_index(tokens, index + 1),
+            "{" => return Some(index),
+            _ => return None,
+        }
+    }
+}
+
+/// Measures the declaration at `position` when it only exists in the type
+/// system: interfaces, type aliases, ambient declarations and namespaces that
+/// only declare types.
+pub fn type_only_declaration_end(tokens: &[String], position: usize) -> Option<usize> {
+    let next = next_token(tokens, position + 1);
+    match tokens[position].as_str() {
+        "interface" if is_identifier(next) => Some(body_end(tokens, position)),
+        "type" if is_identifier(next) => Some(type_alias_end(tokens, position)),
+        "declare" if is_identifier(next) => Some(declare_end(tokens, position)),
+        "namespace" | "module" => match namespace_body(tokens, position) {
+            Some(body) if is_type_only_namespace(tokens, body) => {
+                Some(matching_close(tokens, body) + 1)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the namespace body opened at `opening` only declares types, in
+/// which case there is nothing to create at runtime.
+pub fn is_type_only_namespace(tokens: &[String], opening: usize) -> bool {
+    let close = matching_close(tokens, opening);
+    let mut index = next_index(tokens, opening + 1);
+    while index < close {
+        if tokens[index] == ";" {
+            index = next_index(tokens, index + 1);
+            continue;
+        }
+        if tokens[index] == "export" {
+            index = next_index(tokens, index + 1);
+        }
+        match type_only_declaration_end(tokens, index) {
+            Some(end) => index = next_index(tokens, end),
+            None => return false,
+        }
+    }
+    true
+}
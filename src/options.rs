@@ -0,0 +1,207 @@
+use crate::json::Json;
+use std::path::PathBuf;
+
+/// The module system `import` and `export` are written for. Everything but
+/// ES modules is written as CommonJS first, UMD and IIFE wrap that.
+#[derive(Copy, Clone, PartialEq)]
+pub enum ModuleKind {
+    EsModule,
+    // `require` and `exports`, which is what the loader in `index.js` runs.
+    CommonJs,
+    // CommonJS or AMD, whichever loads it.
+    Umd,
+    // A function that is called right away with an object to export to,
+    // which is assigned to `globalName` when it is set.
+    Iife,
+}
+
+/// What JSX is written as, the `jsx` option of `tsc`. Node can't run JSX,
+/// so `preserve` isn't supported and is written like `react`.
+#[derive(Copy, Clone, PartialEq)]
+pub enum JsxEmit {
+    // `React.createElement`, or the `jsxFactory`.
+    React,
+    // The `jsx` functions of `<jsxImportSource>/jsx-runtime`.
+    ReactJsx,
+}
+
+/// The version of JavaScript that is written, with newer syntax lowered to
+/// it, see `downlevel`. Everything from ES2023 on is the same as `ESNext`
+/// here, and anything before ES2015 is ES2015, as classes, arrow functions
+/// and template literals aren't lowered.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+pub enum Target {
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    Es2019,
+    Es2020,
+    Es2021,
+    Es2022,
+    EsNext,
+}
+
+/// How a module is transpiled. The names and values follow the compiler
+/// options of `tsc`, except for the defaults, which keep what rs-node did
+/// before it had any options.
+#[derive(Clone)]
+pub struct Options {
+    // Legacy decorators (`__decorate`) rather than standard ones.
+    pub experimental_decorators: bool,
+    // `design:type` and the other `__metadata` of legacy decorators.
+    pub emit_decorator_metadata: bool,
+    pub module: ModuleKind,
+    pub global_name: Option<String>,
+    // The global variables that imports are read from in IIFE output, by
+    // module name, like the `globals` of Rollup.
+    pub globals: Vec<(String, String)>,
+    // Only used for `.tsx` files.
+    pub jsx: JsxEmit,
+    pub jsx_factory: String,
+    pub jsx_fragment_factory: String,
+    pub jsx_import_source: String,
+    pub target: Target,
+    // `None` to follow the target, fields are defined from ES2022 on and
+    // assigned in the constructor before it.
+    pub use_define_for_class_fields: Option<bool>,
+    // Imports are only left out when they are marked as types, rather than
+    // whenever none of their bindings are used as values.
+    pub verbatim_module_syntax: bool,
+    // Absolute, like the targets of `paths`, which are relative to `baseUrl`
+    // in `tsconfig.json`.
+    pub base_url: Option<PathBuf>,
+    pub paths: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            experimental_decorators: true,
+            emit_decorator_metadata: false,
+            module: ModuleKind::EsModule,
+            global_name: None,
+            globals: vec![],
+            jsx: JsxEmit::React,
+            jsx_factory: String::from("React.createElement"),
+            jsx_fragment_factory: String::from("React.Fragment"),
+            jsx_import_source: String::from("react"),
+            target: Target::EsNext,
+            use_define_for_class_fields: None,
+            verbatim_module_syntax: false,
+            base_url: None,
+            paths: vec![],
+        }
+    }
+}
+
+impl Options {
+    /// Reads options like `{ "experimentalDecorators": false }`, leaving the
+    /// ones that aren't set as they are. This is also how the options of
+    /// `tsconfig.json` are overridden.
+    pub fn update(&mut self, json: &Json) {
+        let options = self;
+        if let Some(value) = json.get("experimentalDecorators").and_then(Json::as_bool) {
+            options.experimental_decorators = value;
+        }
+        if let Some(value) = json.get("emitDecoratorMetadata").and_then(Json::as_bool) {
+            options.emit_decorator_metadata = value;
+        }
+        if let Some(value) = json.get("module").and_then(Json::as_str) {
+            options.module = match value.to_lowercase().as_str() {
+                "commonjs" => ModuleKind::CommonJs,
+                "umd" => ModuleKind::Umd,
+                "iife" => ModuleKind::Iife,
+                _ => ModuleKind::EsModule,
+            };
+        }
+        if let Some(value) = json.get("globalName").and_then(Json::as_str) {
+            options.global_name = Some(value.to_string());
+        }
+        // `{ "jquery": "$" }`, or `jquery:$,lodash:_` on the command line.
+        match json.get("globals") {
+            Some(Json::String(value)) => {
+                options.globals = value
+                    .split(',')
+                    .filter_map(|entry| entry.split_once(':'))
+                    .map(|(module, global)| (module.trim().to_string(), global.trim().to_string()))
+                    .collect();
+            }
+            Some(Json::Object(globals)) => {
+                options.globals = globals
+                    .iter()
+                    .filter_map(|(module, global)| {
+                        Some((module.clone(), global.as_str()?.to_string()))
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+        if let Some(value) = json.get("jsx").and_then(Json::as_str) {
+            options.jsx = match value.to_lowercase().as_str() {
+                "react-jsx" => JsxEmit::ReactJsx,
+                _ => JsxEmit::React,
+            };
+        }
+        if let Some(value) = json.get("jsxFactory").and_then(Json::as_str) {
+            options.jsx_factory = value.to_string();
+        }
+        if let Some(value) = json.get("jsxFragmentFactory").and_then(Json::as_str) {
+            options.jsx_fragment_factory = value.to_string();
+        }
+        if let Some(value) = json.get("jsxImportSource").and_then(Json::as_str) {
+            options.jsx_import_source = value.to_string();
+        }
+        if let Some(value) = json.get("target").and_then(Json::as_str) {
+            options.target = match value.to_lowercase().as_str() {
+                "es3" | "es5" | "es6" | "es2015" => Target::Es2015,
+                "es2016" => Target::Es2016,
+                "es2017" => Target::Es2017,
+                "es2018" => Target::Es2018,
+                "es2019" => Target::Es2019,
+                "es2020" => Target::Es2020,
+                "es2021" => Target::Es2021,
+                "es2022" => Target::Es2022,
+                _ => Target::EsNext,
+            };
+        }
+        if let Some(value) = json.get("useDefineForClassFields").and_then(Json::as_bool) {
+            options.use_define_for_class_fields = Some(value);
+        }
+        if let Some(value) = json.get("verbatimModuleSyntax").and_then(Json::as_bool) {
+            options.verbatim_module_syntax = value;
+        }
+        if let Some(value) = json.get("baseUrl").and_then(Json::as_str) {
+            options.base_url = Some(PathBuf::from(value));
+        }
+        if let Some(paths) = json.get("paths").and_then(Json::as_object) {
+            // Relative to `baseUrl`, or to the `tsconfig.json` that sets them.
+            let base = match &options.base_url {
+                Some(base_url) => base_url.clone(),
+                None => PathBuf::from(
+                    json.get("pathsBasePath")
+                        .and_then(Json::as_str)
+                        .unwrap_or(""),
+                ),
+            };
+            options.paths = paths
+                .iter()
+                .map(|(pattern, targets)| {
+                    let targets = targets.as_array().unwrap_or_default();
+                    let targets = targets.iter().filter_map(Json::as_str);
+                    (
+                        pattern.clone(),
+                        targets.map(|target| base.join(target)).collect(),
+                    )
+                })
+                .collect();
+        }
+    }
+
+    /// Whether class fields are written as fields or assigned to `this` in
+    /// the constructor, the way `tsc` decides it.
+    pub fn defines_class_fields(&self) -> bool {
+        self.use_define_for_class_fields
+            .unwrap_or(self.target >= Target::Es2022)
+    }
+}
@@ -1,160 +1,2523 @@
 #[path = "./ast.rs"]
 mod ast;
+#[path = "./commonjs.rs"]
+mod commonjs;
+#[path = "./const_enum.rs"]
+mod const_enum;
+#[path = "./decorators.rs"]
+mod decorators;
+#[path = "./downlevel.rs"]
+mod downlevel;
+#[path = "./es_decorators.rs"]
+mod es_decorators;
+#[path = "./helpers.rs"]
+mod helpers;
+#[path = "./import_export.rs"]
+mod import_export;
+#[path = "./jsx.rs"]
+mod jsx;
+#[path = "./lexer.rs"]
+mod lexer;
+#[path = "./lookahead.rs"]
+mod lookahead;
 #[path = "./pauser.rs"]
 mod pauser;
+#[path = "./scope.rs"]
+mod scope;
 
 use ast::NodeASTType;
-use pauser::{KeywordDeclarePauser, Pauser};
+use pauser::{KeywordInterfacePauser, Pauser};
 
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
 
-use self::pauser::{CommentPauser, KeywordAsPauser, KeywordTypePauser};
+use self::commonjs::{
+    declarators, export_all_statement, export_getter, exported_declaration, global_name,
+    import_statement, member, module_variable, reexport_statement,
+};
+use self::const_enum::{declared_const_enums, imported_const_enums, string_value, ConstEnum};
+use crate::options::{ModuleKind, Options, Target};
+
+use self::decorators::{
+    class_body, class_decoration, decorator_end, member_metadata, parameter_decorators,
+    parameter_types, ClassDecoration, MemberKind,
+};
+use self::downlevel::downlevel;
+use self::es_decorators::{lower_accessors, standard_decoration, ClassRewrite, Insertion};
+use self::helpers::{dependencies, HELPERS};
+use self::import_export::{
+    is_json, is_module_specifier, parse_export_all, parse_export_list, parse_import,
+    parse_import_equals, type_only_export_all_end, ExportList, ImportDeclaration, ImportEquals,
+};
+use self::jsx::JsxFactory;
+use self::lexer::is_identifier_char;
+use self::lookahead::{
+    class_member_signature_end, declare_end, enum_member_end, function_signature_end,
+    implements_end, is_class_member_modifier, is_type_only_namespace, matching_close,
+    namespace_body, next_index, next_token, super_call_end, type_alias_end, type_arguments_close,
+    type_end, type_only_declaration_end, TYPE_ONLY_MODIFIERS,
+};
+use self::pauser::LookaheadPauser;
+use self::scope::{find_scopes, Scopes};
+use crate::resolver::{relative_specifier, resolve, resolve_alias, Resolution};
+
+/// Keywords that are followed by an expression, so a `{` after them is an
+/// object literal and a `(` after them isn't a call.
+const EXPRESSION_KEYWORDS: [&str; 12] = [
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "yield",
+    "await",
+];
+
+const MODULE_NODE: Node = Node {
+    value: String::new(),
+    parent: None,
+    node_type: NodeASTType::Module,
+};
+
+/// Where a binding (a variable or a parameter) is in its grammar. Type
+/// annotations can only follow the name of a binding, which is how we tell
+/// them apart from every other `:`.
+#[derive(Copy, Clone, PartialEq)]
+enum BindingState {
+    None,
+    ExpectingName,
+    Named,
+    Initializer,
+}
+
+/// Every opening bracket starts a new context and the module is the outermost
+/// one. Bindings are tracked per context so that commas inside an initializer
+/// like `f(a, b)` don't start a new declarator, the same goes for the `?` of a
+/// ternary and the `case` that are waiting for their `:`.
+struct Context {
+    opener: usize,
+    binding: BindingState,
+    pending_ternaries: u32,
+    is_pending_case: bool,
+    // Whether the closing bracket ends an expression (an object literal or the
+    // body of a function expression) rather than a statement.
+    ends_expression: bool,
+}
+
+impl Context {
+    fn new(opener: usize, binding: BindingState) -> Context {
+        Context {
+            opener,
+            binding,
+            pending_ternaries: 0,
+            is_pending_case: false,
+            ends_expression: false,
+        }
+    }
+}
+
+/// The enum whose body is being written. Members are written as they are seen
+/// and an initializer is written in place between `E[E["A"]=` and `]="A"`.
+struct EnumDeclaration {
+    name: String,
+    argument: String,
+    // Identifier members declared so far, references to them get qualified.
+    members: Vec<String>,
+    string_members: Vec<String>,
+    // The value of a member without an initializer when the previous value
+    // is a known number, otherwise it is computed from the previous member.
+    next_value: Option<i64>,
+    previous_key: Option<String>,
+    // What follows the initializer of the current member.
+    pending_suffix: Option<String>,
+}
+
+/// A namespace whose body is being written. `namespace A.B {` opens one for
+/// each name, and all of them close at the same `}`.
+struct Namespace {
+    name: String,
+    // The node of the `{` that opened the body, `None` until it is seen.
+    opener: Option<usize>,
+    // What the function is called with, e.g. `A||(A={})`.
+    argument: String,
+    // Exported variables only exist as properties, references to them become `A.x`.
+    exported_variables: Vec<String>,
+    // Exported functions and classes, which are declared in the body as well
+    // and only become `A.f` in the declarations that merge with it.
+    exported_declarations: Vec<String>,
+}
+
+/// A class declaration with decorators, whose `__decorate` calls are written
+/// once its body is closed.
+struct DecoratedClass {
+    // The number of contexts open at `class`, which is back once it closes.
+    depth: usize,
+    name: String,
+    decorators: Vec<String>,
+    decoration: ClassDecoration,
+    // `Some(true)` for `export default`, the class is exported after it has
+    // been decorated.
+    export: Option<bool>,
+    // A class expression, which only standard decorators are lowered for.
+    is_expression: bool,
+    // Where the class refers to itself, see `class_self_references`, and
+    // where `let A=` starts in the output.
+    self_references: Vec<usize>,
+    offset: usize,
+    // The end of the IIFE of a class with standard decorators, which are
+    // applied inside of it rather than after it.
+    epilogue: Option<String>,
+}
+
+impl DecoratedClass {
+    // Decorators on the class itself or on its constructor parameters
+    // replace the class.
+    fn is_decorated(&self) -> bool {
+        let constructor = self.decoration.constructor.iter().flatten();
+        return !self.decorators.is_empty()
+            || constructor
+                .into_iter()
+                .any(|parameter| !parameter.decorators.is_empty());
+    }
+}
+
+/// The instance fields of a class that are assigned in its constructor
+/// rather than defined, see `Options::defines_class_fields`. Each field is
+/// written as usual and then taken out of the output again, and the
+/// assignments are added to the constructor once the class is closed.
+struct AssignedFields {
+    // The number of contexts open at `class`, which is back once it closes.
+    depth: usize,
+    fields: Vec<AssignedField>,
+    // Index of the token the assignments follow: the `{` of the constructor
+    // body or its `super()` call, or the `{` of the class body when it has
+    // no constructor.
+    position: usize,
+    has_constructor: bool,
+    is_derived: bool,
+    // Where that token ends in the output, once it has been written.
+    offset: Option<usize>,
+    // The length of the output, the last written token and its type when
+    // the field being written started.
+    field_start: Option<(usize, String, NodeASTType)>,
+    assignments: String,
+}
+
+struct AssignedField {
+    // Indexes of its first token and of its last one, the `;` if it has one.
+    start: usize,
+    end: usize,
+    // As it was written, `[` for a computed name.
+    name: String,
+    has_initializer: bool,
+}
+
+/// An import or an export list as it was written, see `Tree::finish`.
+enum ModuleDeclaration {
+    Import(ImportDeclaration),
+    Export(ExportList),
+    ImportEquals(ImportEquals),
+}
+
+struct WrittenModuleDeclaration {
+    declaration: ModuleDeclaration,
+    // The index of its `import` or `export` keyword.
+    position: usize,
+    // Where it is in the output, including the seperator before it.
+    start: usize,
+    end: usize,
+}
+
+struct Tree {
+    output: String,
+    current_token: String,
+    nodes: Vec<Node>,
+    tokens: Vec<String>,
+    position: usize,
+    contexts: Vec<Context>,
+
+    // private
+    is_paused: bool,
+    paused_node_type: NodeASTType,
+    has_line_break: bool,
+    last_written: String,
+    last_written_type: NodeASTType,
+    is_expression_body: bool,
+    has_closed_expression: bool,
+    // The number of contexts open when `class` was seen, the class body is the
+    // next `{` at that depth.
+    class_heritage_depth: Option<usize>,
+    // Constructor parameters declared with a modifier, like `private db: Db`,
+    // and the position of the token the `this.db=db;` assignments follow.
+    parameter_properties: Vec<String>,
+    parameter_properties_position: Option<usize>,
+    enum_declaration: Option<EnumDeclaration>,
+    namespaces: Vec<Namespace>,
+    // An exported function or class of a namespace is assigned to it once
+    // its body has been written.
+    pending_namespace_export: Option<String>,
+    // The exports of every namespace that has been closed, by its path, for
+    // the declarations that merge with it.
+    namespace_exports: HashMap<String, Vec<String>>,
+    is_exporting_variables: bool,
+    // Enums (and namespaces) merge with an earlier declaration of the same
+    // name, only the first one declares the `var`. Keyed by context opener.
+    declared_names: Vec<(usize, String)>,
+    // Const enums declared in or imported into this module, by local name,
+    // and the ones of the modules imported with `import * as`.
+    const_enums: HashMap<String, ConstEnum>,
+    namespace_const_enums: HashMap<String, HashMap<String, ConstEnum>>,
+    scopes: Scopes,
+    module_declarations: Vec<WrittenModuleDeclaration>,
+    // Every identifier written outside of a type.
+    value_references: HashSet<String>,
+    type_declarations: HashSet<String>,
+    // Decorators written before `class`, and whether it is exported.
+    pending_decorators: Vec<String>,
+    decorated_class_export: Option<bool>,
+    decorated_classes: Vec<DecoratedClass>,
+    assigned_fields: Vec<AssignedFields>,
+    // The names of the runtime helpers the module needs.
+    helpers: HashSet<&'static str>,
+    // Tokens of class bodies written as something else, and text written
+    // after them, see `ClassRewrite`.
+    rewrites: HashMap<usize, String>,
+    insertions: Vec<Insertion>,
+    options: Options,
+    // Imported bindings by local name, and what they are read as once their
+    // module has been required, e.g. `a_1.b`. Only used for CommonJS.
+    import_bindings: HashMap<String, String>,
+    // The variable a module is required into, by the position of the
+    // `import` or `export` that requires it.
+    module_variables: HashMap<usize, String>,
+    // Names exported with a getter, and what the getter returns.
+    commonjs_exports: Vec<(String, String)>,
+    // The sources of the modules that are required, by the position that
+    // requires them. They are the dependencies of a UMD module.
+    required_modules: Vec<(usize, String)>,
+    // The positions of the imports that only require a module for its side
+    // effects, like `import './polyfill'`.
+    side_effect_imports: HashSet<usize>,
+    // Whether there is any `import` or `export` at all.
+    is_module: bool,
+
+    // pausers
+    keyword_interface_pauser: KeywordInterfacePauser,
+    lookahead_pauser: LookaheadPauser,
+}
+
+impl Tree {
+    fn new(tokens: Vec<String>, options: Options) -> Tree {
+        let scopes = find_scopes(&tokens);
+        return Tree {
+            output: String::new(),
+            current_token: String::new(),
+            nodes: vec![MODULE_NODE],
+            tokens,
+            position: 0,
+            contexts: vec![Context::new(0, BindingState::None)],
+            is_paused: false,
+            paused_node_type: NodeASTType::Unknown,
+            has_line_break: false,
+            last_written: String::new(),
+            last_written_type: NodeASTType::Module,
+            is_expression_body: false,
+            has_closed_expression: false,
+            class_heritage_depth: None,
+            parameter_properties: vec![],
+            parameter_properties_position: None,
+            enum_declaration: None,
+            namespaces: vec![],
+            pending_namespace_export: None,
+            namespace_exports: HashMap::new(),
+            is_exporting_variables: false,
+            declared_names: vec![],
+            const_enums: HashMap::new(),
+            namespace_const_enums: HashMap::new(),
+            scopes,
+            module_declarations: vec![],
+            value_references: HashSet::new(),
+            type_declarations: HashSet::new(),
+            pending_decorators: vec![],
+            decorated_class_export: None,
+            decorated_classes: vec![],
+            assigned_fields: vec![],
+            helpers: HashSet::new(),
+            rewrites: HashMap::new(),
+            insertions: vec![],
+            options,
+            import_bindings: HashMap::new(),
+            module_variables: HashMap::new(),
+            commonjs_exports: vec![],
+            required_modules: vec![],
+            side_effect_imports: HashSet::new(),
+            is_module: false,
+
+            // pausers
+            keyword_interface_pauser: KeywordInterfacePauser::new(),
+            lookahead_pauser: LookaheadPauser::new(),
+        };
+    }
+
+    pub fn last_node(&self) -> &Node {
+        return self.nodes.last().expect("Must exist");
+    }
+
+    fn context(&self) -> &Context {
+        return self
+            .contexts
+            .last()
+            .expect("The module context always exists");
+    }
+
+    fn context_mut(&mut self) -> &mut Context {
+        return self
+            .contexts
+            .last_mut()
+            .expect("The module context always exists");
+    }
+
+    // The node type of the bracket that opened the current context.
+    fn context_type(&self) -> NodeASTType {
+        return self.nodes[self.context().opener].node_type;
+    }
+
+    pub fn pause_writing(&mut self, node_type: NodeASTType) {
+        self.is_paused = true;
+        self.paused_node_type = node_type;
+    }
+
+    // For a given node type that paused writing, this will handle the logic
+    // that would consider resuming. For example, if the node is paused for a declare keyword
+    // it will only resume once the number of opening and closing brackets are equal.
+    pub fn consider_resuming_writing(&mut self, node_type: NodeASTType) {
+        if self.is_paused {
+            self.is_paused = match self.paused_node_type {
+                _ if is_measured_ahead(self.paused_node_type) => {
+                    self.lookahead_pauser.is_paused_after_evaluating(node_type)
+                }
+                NodeASTType::KeywordInterface => self
+                    .keyword_interface_pauser
+                    .is_paused_after_evaluating(node_type),
+                _ => self.is_paused,
+            };
+        }
+    }
+
+    // Where the span paused by `node_type` ends, for nodes that are measured
+    // with lookahead before we start skipping them.
+    fn measured_end(&self, node_type: NodeASTType) -> usize {
+        let tokens = &self.tokens;
+        match node_type {
+            NodeASTType::KeywordType => type_alias_end(tokens, self.position),
+            NodeASTType::KeywordDeclare => declare_end(tokens, self.position),
+            NodeASTType::TypeOnlyNamespace => {
+                let body = namespace_body(tokens, self.position).expect("Checked in detect");
+                matching_close(tokens, body) + 1
+            }
+            NodeASTType::TypeParameters
+            | NodeASTType::TypeArguments
+            | NodeASTType::TypeAssertion => matching_close(tokens, self.position) + 1,
+            NodeASTType::KeywordImplements => implements_end(tokens, self.position),
+            NodeASTType::Decorator => decorator_end(tokens, self.position),
+            NodeASTType::DecoratedClassExport => self
+                .decorated_class_export_end()
+                .expect("Checked in detect"),
+            NodeASTType::FunctionSignature => {
+                function_signature_end(tokens, self.position).expect("Checked in detect")
+            }
+            NodeASTType::ThisParameter => {
+                let end = type_end(tokens, next_index(tokens, self.position + 1) + 1);
+                match next_token(tokens, end) {
+                    "," => next_index(tokens, end) + 1,
+                    _ => end,
+                }
+            }
+            NodeASTType::ClassMemberSignature => {
+                class_member_signature_end(tokens, self.position).unwrap_or(self.position + 1)
+            }
+            NodeASTType::ConstEnumDeclaration => {
+                let opening = self.tokens[self.position..]
+                    .iter()
+                    .position(|token| token == "{")
+                    .map_or(tokens.len(), |index| self.position + index);
+                matching_close(tokens, opening) + 1
+            }
+            NodeASTType::ImportDeclaration => {
+                parse_import(tokens, self.position)
+                    .expect("Checked in detect")
+                    .end
+            }
+            NodeASTType::ExportList => {
+                parse_export_list(tokens, self.position)
+                    .expect("Checked in detect")
+                    .end
+            }
+            NodeASTType::TypeOnlyExport => self.type_only_export_end().expect("Checked in detect"),
+            NodeASTType::ImportEquals => {
+                parse_import_equals(tokens, self.position)
+                    .expect("Checked in detect")
+                    .end
+            }
+            NodeASTType::DynamicImport => self.position + 1,
+            NodeASTType::ImportMeta => {
+                let meta = next_index(tokens, next_index(tokens, self.position + 1) + 1);
+                let property = next_index(tokens, meta + 1);
+                match (
+                    next_token(tokens, property),
+                    next_token(tokens, property + 1),
+                ) {
+                    (".", "url" | "dirname" | "filename" | "resolve") => {
+                        next_index(tokens, property + 1) + 1
+                    }
+                    _ => meta + 1,
+                }
+            }
+            // `export =`, what it exports is written as usual.
+            NodeASTType::ExportAssignment => next_index(tokens, self.position + 1) + 1,
+            NodeASTType::ExportAll => {
+                parse_export_all(tokens, self.position)
+                    .expect("Checked in detect")
+                    .end
+            }
+            // Just the keywords, what they export is written as usual.
+            NodeASTType::CommonJsExport => {
+                let index = next_index(tokens, self.position + 1);
+                match next_token(tokens, index) {
+                    "default" => index + 1,
+                    _ => self.position + 1,
+                }
+            }
+            NodeASTType::ConstEnumReference => {
+                self.const_enum_reference().expect("Checked in detect").0
+            }
+            _ => type_end(tokens, self.position + 1),
+        }
+    }
+
+    fn is_class_heritage(&self) -> bool {
+        return self.class_heritage_depth == Some(self.contexts.len());
+    }
+
+    fn is_class_body(&self) -> bool {
+        return self.context_type() == NodeASTType::ClassOpeningBracket;
+    }
+
+    // The first tokens of a class member: modifiers, then its name. Returns
+    // `None` once the name has been seen.
+    fn detect_class_member_type(&self, value: &str) -> Option<NodeASTType> {
+        if !self.is_class_body() || self.context().binding != BindingState::ExpectingName {
+            return None;
+        }
+        if matches!(value, "\n" | "}" | ";" | "{") {
+            return None;
+        }
+        if class_member_signature_end(&self.tokens, self.position).is_some() {
+            return Some(NodeASTType::ClassMemberSignature);
+        }
+        if is_class_member_modifier(&self.tokens, self.position) {
+            if TYPE_ONLY_MODIFIERS.contains(&value) {
+                return Some(NodeASTType::KeywordModifier);
+            }
+            return Some(NodeASTType::Identifier);
+        }
+        match value {
+            "[" | "*" => None,
+            _ => Some(NodeASTType::ClassMemberName),
+        }
+    }
+
+    // `export` or `export default` before a decorated class, which is exported
+    // once it has been decorated. Returns the index after the keywords.
+    fn decorated_class_export_end(&self) -> Option<usize> {
+        let tokens = &self.tokens;
+        let mut index = next_index(tokens, self.position + 1);
+        if tokens.get(index).map(String::as_str) == Some("default") {
+            index = next_index(tokens, index + 1);
+        }
+        let is_decorated = match next_token(tokens, index) {
+            "@" => true,
+            "class" | "abstract" => {
+                !self.pending_decorators.is_empty()
+                    || (!self.options.experimental_decorators
+                        && class_body(tokens, index).is_some_and(|body| {
+                            class_decoration(tokens, body)
+                                .members
+                                .iter()
+                                .any(|member| !member.decorators.is_empty())
+                        }))
+            }
+            _ => false,
+        };
+        return is_decorated.then_some(index);
+    }
+
+    // Decorators are skipped, the ones before `class` are kept for its
+    // `__decorate` call. The members and parameters are measured from the
+    // class body instead.
+    fn record_decorator(&mut self) {
+        let end = decorator_end(&self.tokens, self.position);
+        for index in self.position + 1..end {
+            let token = &self.tokens[index];
+            if lookahead::is_identifier(token) && self.tokens[index - 1] != "." {
+                self.value_references.insert(token.clone());
+            }
+        }
+        if !self.is_class_body() && self.context_type() != NodeASTType::FunctionOpeningParenthesis {
+            let expression: Vec<String> = self.tokens[self.position + 1..end]
+                .iter()
+                .filter(|token| token.as_str() != "\n")
+                .cloned()
+                .collect();
+            self.pending_decorators.push(join_tokens(&expression));
+        }
+    }
+
+    // Decorators and their metadata are written away from where they are,
+    // so imported bindings in them are read from their module here rather
+    // than as they are seen, see `write_import_binding_reference`.
+    fn with_import_references(&self, expression: &str) -> String {
+        if self.import_bindings.is_empty() {
+            return expression.to_string();
+        }
+        let tokens = lexer::tokenize(expression);
+        let mut written = vec![];
+        for (index, token) in tokens.iter().enumerate() {
+            let previous = written.last().map_or("", String::as_str);
+            let next = next_token(&tokens, index + 1);
+            let reference = match self.import_bindings.get(token) {
+                Some(reference) if previous != "." && previous != "?." && next != ":" => reference,
+                _ => {
+                    written.push(token.clone());
+                    continue;
+                }
+            };
+            written.push(match next {
+                "(" if previous != "new" => format!("(0,{})", reference),
+                _ => reference.clone(),
+            });
+        }
+        return join_tokens(&written);
+    }
+
+    // A class declaration with decorators on itself or its constructor
+    // parameters becomes `let A=class A{}` so that it can be replaced by
+    // what they return. With standard decorators a class with any decorators
+    // becomes an IIFE instead, see `standard_decoration`.
+    fn start_decorated_class(&mut self) {
+        let Some(body) = class_body(&self.tokens, self.position) else {
+            return;
+        };
+        let decorators = std::mem::take(&mut self.pending_decorators);
+        let export = self.decorated_class_export.take();
+        let decoration = class_decoration(&self.tokens, body);
+        // Standard decorators initialize the fields they decorate themselves.
+        let is_standard_decorated = !self.options.experimental_decorators
+            && (!decorators.is_empty()
+                || decoration
+                    .members
+                    .iter()
+                    .any(|member| member.is_decorated()));
+        if !self.options.defines_class_fields() && !is_standard_decorated {
+            self.start_assigned_fields(body, &decoration);
+        }
+        let is_expression = self.is_expression_body;
+        if is_expression && !is_standard_decorated {
+            let mut rewrite = ClassRewrite::default();
+            lower_accessors(&self.tokens, &decoration, "this", &mut rewrite);
+            self.apply_class_rewrite(rewrite);
+            return;
+        }
+        let next = next_token(&self.tokens, self.position + 1);
+        let name = match next {
+            "extends" | "implements" => self.anonymous_class_name(is_expression),
+            _ if lookahead::is_identifier(next) => next.to_string(),
+            _ => self.anonymous_class_name(is_expression),
+        };
+        let mut class = DecoratedClass {
+            depth: self.contexts.len(),
+            name,
+            decorators,
+            decoration,
+            export,
+            is_expression,
+            self_references: vec![],
+            offset: 0,
+            epilogue: None,
+        };
+        let has_decorated_members = class
+            .decoration
+            .members
+            .iter()
+            .any(|member| member.is_decorated());
+
+        if !self.options.experimental_decorators {
+            if class.decorators.is_empty() && !has_decorated_members {
+                let mut rewrite = ClassRewrite::default();
+                lower_accessors(&self.tokens, &class.decoration, &class.name, &mut rewrite);
+                self.apply_class_rewrite(rewrite);
+                return;
+            }
+            for decorator in class.decorators.iter_mut().chain(
+                class
+                    .decoration
+                    .members
+                    .iter_mut()
+                    .flat_map(|member| member.decorators.iter_mut()),
+            ) {
+                *decorator = self.with_import_references(decorator);
+            }
+            let decoration = standard_decoration(
+                &self.tokens,
+                self.position,
+                body,
+                &class.name,
+                &class.decorators,
+                &class.decoration,
+                class.is_expression,
+            );
+            self.write(&decoration.prologue);
+            self.is_expression_body = true;
+            self.apply_class_rewrite(decoration.rewrite);
+            self.helpers.extend(["__esDecorate", "__runInitializers"]);
+            class.epilogue = Some(decoration.epilogue);
+            self.decorated_classes.push(class);
+            return;
+        }
+
+        let mut rewrite = ClassRewrite::default();
+        lower_accessors(&self.tokens, &class.decoration, &class.name, &mut rewrite);
+        self.apply_class_rewrite(rewrite);
+        if !class.is_decorated() && !has_decorated_members {
+            return;
+        }
+        if class.is_decorated() {
+            // Inside the class its name is what the decorators return, like
+            // `var A_1;let A=A_1=class A{…new A_1()…}`. Whether any of the
+            // references is a value rather than a type is known once the class
+            // has been written, see `write_class_alias`.
+            let alias = format!("{}_1", class.name);
+            class.self_references = self.class_self_references(body, &class.name);
+            for position in class.self_references.iter() {
+                self.rewrites.insert(*position, alias.clone());
+            }
+            class.offset = self.output.len();
+            self.write("let");
+            self.write(&class.name);
+            self.write("=");
+            self.is_expression_body = true;
+        }
+        self.decorated_classes.push(class);
+    }
+
+    // The uses of the name of a class in its own body, but not of members or
+    // keys like `this.A` or `{ A: 1 }` that share it.
+    fn class_self_references(&self, body: usize, name: &str) -> Vec<usize> {
+        let tokens = &self.tokens;
+        let close = matching_close(tokens, body);
+        return (body + 1..close)
+            .filter(|index| tokens[*index] == name)
+            .filter(|index| {
+                let previous = tokens[..*index]
+                    .iter()
+                    .rfind(|token| token.as_str() != "\n")
+                    .map_or("", String::as_str);
+                let is_member = matches!(previous, "." | "?.");
+                let is_key = matches!(previous, "{" | ",") && next_token(tokens, index + 1) == ":";
+                !is_member && !is_key
+            })
+            .collect();
+    }
+
+    // The name of a class without one: the variable it is assigned to, like
+    // `const A = class {}`, or what `tsc` calls it otherwise.
+    fn anonymous_class_name(&self, is_expression: bool) -> String {
+        if !is_expression {
+            return String::from("default_1");
+        }
+        let assigned = match self.last_written.as_str() {
+            "=" => self.output.strip_suffix('=').unwrap_or_default().trim_end(),
+            _ => "",
+        };
+        let start = assigned
+            .rfind(|character: char| {
+                !(character.is_alphanumeric() || character == '_' || character == '$')
+            })
+            .map_or(0, |index| index + 1);
+        let name = &assigned[start..];
+        let is_property = assigned[..start].trim_end().ends_with('.');
+        if name.is_empty() || is_property || !lookahead::is_identifier(name) {
+            return String::from("class_1");
+        }
+        return name.to_string();
+    }
+
+    fn start_assigned_fields(&mut self, body: usize, decoration: &ClassDecoration) {
+        let fields: Vec<AssignedField> = decoration
+            .members
+            .iter()
+            // Static fields are left as they are, unless there is nothing to
+            // assign to them. Private fields only move when they are lowered
+            // anyway, see `downlevel`.
+            .filter(|member| {
+                member.kind == MemberKind::Field
+                    && match (member.is_static, member.is_private()) {
+                        (false, false) => true,
+                        (false, true) => self.options.target < Target::Es2022,
+                        (true, false) => member.initializer.is_none(),
+                        (true, true) => false,
+                    }
+            })
+            // A `static {}` block reads as a field named `static`.
+            .filter(|member| next_token(&self.tokens, member.name_end + 1) != "{")
+            .map(|member| AssignedField {
+                start: member.start,
+                end: match member.has_semicolon {
+                    true => next_index(&self.tokens, member.last + 1),
+                    false => member.last,
+                },
+                name: member.name.clone(),
+                has_initializer: member.initializer.is_some(),
+            })
+            .collect();
+        if fields.is_empty() {
+            return;
+        }
+        let position = match decoration.constructor_body {
+            Some(constructor_body) => {
+                super_call_end(&self.tokens, constructor_body).unwrap_or(constructor_body)
+            }
+            None => body,
+        };
+        self.assigned_fields.push(AssignedFields {
+            depth: self.contexts.len(),
+            fields,
+            position,
+            has_constructor: decoration.constructor_body.is_some(),
+            is_derived: self.tokens[self.position..body]
+                .iter()
+                .any(|token| token == "extends"),
+            offset: None,
+            field_start: None,
+            assignments: String::new(),
+        });
+    }
+
+    // Remembers where a field starts in the output, to take it out again
+    // once it has been written.
+    fn start_assigned_field(&mut self) {
+        let Some(class) = self.assigned_fields.last_mut() else {
+            return;
+        };
+        if class
+            .fields
+            .iter()
+            .any(|field| field.start == self.position)
+        {
+            class.field_start = Some((
+                self.output.len(),
+                self.last_written.clone(),
+                self.last_written_type,
+            ));
+        }
+    }
+
+    // Takes the field that ends at the current token out of the output and
+    // keeps its initializer as an assignment to `this`.
+    fn end_assigned_field(&mut self) {
+        let Some(class) = self.assigned_fields.last_mut() else {
+            return;
+        };
+        if class.position == self.position {
+            class.offset = Some(self.output.len());
+        }
+        let Some(field) = class.fields.iter().find(|field| field.end == self.position) else {
+            return;
+        };
+        let (start, last_written, last_written_type) = class
+            .field_start
+            .take()
+            .expect("Started at its first token");
+        let written = self.output.split_off(start);
+        let written = written.trim_start().trim_end_matches(';');
+        // A private field is kept as `#a=1;` or `#a;`, which `downlevel`
+        // initializes in its place.
+        if field.has_initializer || field.name.starts_with('#') {
+            class.assignments += &match field.name.as_str() {
+                "[" => format!("this{};", written),
+                name if name.starts_with('#') => format!("{};", written),
+                name if lookahead::is_identifier(name) => format!("this.{};", written),
+                name => format!("this[{}]{};", name, &written[name.len()..]),
+            };
+        }
+        self.last_written = last_written;
+        self.last_written_type = last_written_type;
+    }
+
+    // Adds the assignments to the constructor once the class is closed, or
+    // a constructor with them, `constructor(){this.a=1;}`.
+    fn write_field_assignments(&mut self) {
+        if self
+            .assigned_fields
+            .last()
+            .is_none_or(|class| class.depth != self.contexts.len())
+        {
+            return;
+        }
+        let class = self.assigned_fields.pop().expect("Checked above");
+        if class.assignments.is_empty() {
+            return;
+        }
+        let offset = class.offset.expect("Written before the class closes");
+        let assignments = match (class.has_constructor, class.is_derived) {
+            (true, _) if self.output[..offset].ends_with(')') => {
+                format!(";{}", class.assignments)
+            }
+            (true, _) => class.assignments,
+            (false, true) => format!("constructor(){{super(...arguments);{}}}", class.assignments),
+            (false, false) => format!("constructor(){{{}}}", class.assignments),
+        };
+        self.output.insert_str(offset, &assignments);
+    }
+
+    fn apply_class_rewrite(&mut self, rewrite: ClassRewrite) {
+        self.rewrites.extend(rewrite.rewrites);
+        self.insertions.extend(rewrite.insertions);
+    }
+
+    // Text a class rewrite adds after the current token.
+    fn write_insertions(&mut self) {
+        if self.insertions.is_empty() {
+            return;
+        }
+        let position = self.position;
+        let (insertions, rest): (Vec<Insertion>, Vec<Insertion>) =
+            std::mem::take(&mut self.insertions)
+                .into_iter()
+                .partition(|insertion| insertion.position == position);
+        self.insertions = rest;
+        for insertion in insertions {
+            if insertion.is_statement && self.last_written == ")" {
+                self.write(";");
+            }
+            self.write(&insertion.text);
+        }
+    }
+
+    // `__decorate([a],A.prototype,"b",null);` for every decorated member,
+    // instance members first, then `A=__decorate([c],A);` for the class.
+    fn write_decorations(&mut self) {
+        if self
+            .decorated_classes
+            .last()
+            .is_none_or(|class| class.depth != self.contexts.len())
+        {
+            return;
+        }
+        let class = self.decorated_classes.pop().expect("Checked above");
+        match &class.epilogue {
+            Some(epilogue) => {
+                self.write(epilogue);
+                if !class.is_expression {
+                    self.write(";");
+                }
+            }
+            None => self.write_legacy_decorations(&class),
+        }
+
+        if let Some(is_default) = class.export {
+            self.write_class_export(&class.name, is_default);
+        }
+    }
+
+    // A decorated class is exported once it has been replaced by what its
+    // decorators return.
+    fn write_class_export(&mut self, name: &str, is_default: bool) {
+        if self.is_commonjs() {
+            let exported = if is_default { "default" } else { name };
+            self.commonjs_exports
+                .push((exported.to_string(), name.to_string()));
+            return;
+        }
+        match is_default {
+            true => self.write(&format!("export default {}", name)),
+            false => self.write(&format!("export{{{}}}", name)),
+        }
+        self.write(";");
+    }
+
+    fn write_legacy_decorations(&mut self, class: &DecoratedClass) {
+        let name = &class.name;
+        let mut references = vec![];
+
+        // The class is an expression assigned to `let A=`.
+        if class.is_decorated() {
+            self.write(";");
+        }
+        let members = class
+            .decoration
+            .members
+            .iter()
+            .filter(|member| member.is_decorated());
+        let (instance, statics): (Vec<_>, Vec<_>) = members.partition(|member| !member.is_static);
+        for member in instance.into_iter().chain(statics) {
+            let mut decorators = member.decorators.clone();
+            decorators.extend(parameter_decorators(&member.parameters));
+            if self.options.emit_decorator_metadata {
+                decorators.extend(member_metadata(member, &self.tokens, &mut references));
+            }
+            let decorators: Vec<String> = decorators
+                .iter()
+                .map(|decorator| self.with_import_references(decorator))
+                .collect();
+            let target = match member.is_static {
+                true => name.clone(),
+                false => format!("{}.prototype", name),
+            };
+            let descriptor = match member.kind {
+                MemberKind::Field => "void 0",
+                _ => "null",
+            };
+            self.write(&format!(
+                "__decorate([{}],{},{},{})",
+                decorators.join(","),
+                target,
+                member.key,
+                descriptor
+            ));
+            self.write(";");
+            self.use_decorator_helpers(&decorators);
+        }
+
+        if class.is_decorated() {
+            let mut decorators = class.decorators.clone();
+            if let Some(parameters) = &class.decoration.constructor {
+                decorators.extend(parameter_decorators(parameters));
+                if self.options.emit_decorator_metadata {
+                    let types = parameter_types(parameters, &self.tokens, &mut references);
+                    decorators.push(format!("__metadata(\"design:paramtypes\",{})", types));
+                }
+            }
+            let decorators: Vec<String> = decorators
+                .iter()
+                .map(|decorator| self.with_import_references(decorator))
+                .collect();
+            let assigned = match self.write_class_alias(class) {
+                Some(alias) => format!("{}={}", name, alias),
+                None => name.clone(),
+            };
+            self.write(&format!(
+                "{}=__decorate([{}],{})",
+                assigned,
+                decorators.join(","),
+                name
+            ));
+            self.write(";");
+            self.use_decorator_helpers(&decorators);
+        }
+        self.value_references.extend(references);
+    }
+
+    // `var A_1;let A=A_1=` when the class refers to itself as a value, which
+    // rewrote those references to `A_1`. Returns the alias when it is used.
+    fn write_class_alias(&mut self, class: &DecoratedClass) -> Option<String> {
+        let mut is_used = false;
+        for position in class.self_references.iter() {
+            is_used |= self.rewrites.remove(position).is_none();
+        }
+        if !is_used {
+            return None;
+        }
+        let alias = format!("{}_1", class.name);
+        let assignment = format!("let {}=", class.name);
+        let start = class.offset + self.output[class.offset..].find(&assignment)?;
+        self.output
+            .insert_str(start + assignment.len(), &format!("{}=", alias));
+        self.output.insert_str(start, &format!("var {};", alias));
+        return Some(alias);
+    }
+
+    fn use_decorator_helpers(&mut self, decorators: &[String]) {
+        self.helpers.insert("__decorate");
+        for helper in ["__param", "__metadata"] {
+            if decorators
+                .iter()
+                .any(|decorator| decorator.starts_with(helper))
+            {
+                self.helpers.insert(helper);
+            }
+        }
+    }
+
+    fn is_constructor_parameters(&self) -> bool {
+        let opener = self.context().opener;
+        return self.context_type() == NodeASTType::FunctionOpeningParenthesis
+            && self.nodes[opener - 1].node_type == NodeASTType::ClassMemberName
+            && self.nodes[opener - 1].value == "constructor";
+    }
+
+    // `constructor(private db: Db)` is shorthand for declaring and assigning a
+    // field, so the modifier is dropped and the assignment is written out.
+    fn is_parameter_property_modifier(&self, value: &str) -> bool {
+        return TYPE_ONLY_MODIFIERS.contains(&value)
+            && self.context().binding == BindingState::ExpectingName
+            && self.is_constructor_parameters()
+            && is_class_member_modifier(&self.tokens, self.position);
+    }
+
+    // The assignments go at the start of the constructor body, or after the
+    // `super()` call when there is one.
+    fn parameter_properties_position(&self) -> usize {
+        return super_call_end(&self.tokens, self.position).unwrap_or(self.position);
+    }
+
+    fn write_parameter_properties(&mut self) {
+        if self.last_written == ")" {
+            self.write(";");
+        }
+        for name in std::mem::take(&mut self.parameter_properties) {
+            for value in ["this", ".", &name, "=", &name, ";"] {
+                self.write(value);
+            }
+        }
+        self.parameter_properties_position = None;
+    }
+
+    fn is_enum_body(&self) -> bool {
+        return self.context_type() == NodeASTType::EnumOpeningBracket;
+    }
+
+    fn detect_enum_type(&self, value: &str) -> Option<NodeASTType> {
+        if self.is_enum_body() {
+            return match (value, self.context().binding) {
+                ("\n", _) => None,
+                ("," | "}", _) => None,
+                ("=", BindingState::Named) => Some(NodeASTType::EnumMemberInitializer),
+                (_, BindingState::ExpectingName) => Some(NodeASTType::EnumMember),
+                (_, BindingState::Initializer) => self.detect_enum_member_reference(value),
+                _ => None,
+            };
+        }
+        return self.detect_enum_member_reference(value);
+    }
+
+    // `B = A` refers to the member `A` of the enum, which is `E.A` at runtime.
+    fn detect_enum_member_reference(&self, value: &str) -> Option<NodeASTType> {
+        let declaration = self.enum_declaration.as_ref()?;
+        if declaration.members.iter().any(|member| member == value)
+            && self.last_written != "."
+            && self.last_written != "?."
+        {
+            return Some(NodeASTType::EnumMemberReference);
+        }
+        return None;
+    }
+
+    // `var E;(function(E){` on the name, `E[E["A"]=0]="A";` for every member
+    // and `})(E||(E={}));` at the end.
+    fn write_enum(&mut self, node_type: NodeASTType) {
+        let value = self.current_token.clone();
+        match node_type {
+            NodeASTType::EnumDeclaration => {
+                let parent = self.exporting_namespace();
+                let argument = self.write_iife_start(&value, Some(self.context().opener), parent);
+                self.enum_declaration = Some(EnumDeclaration {
+                    name: value,
+                    argument,
+                    members: vec![],
+                    string_members: vec![],
+                    next_value: Some(0),
+                    previous_key: None,
+                    pending_suffix: None,
+                });
+            }
+            NodeASTType::EnumMember => self.write_enum_member(),
+            NodeASTType::EnumMemberReference => {
+                let name = &self
+                    .enum_declaration
+                    .as_ref()
+                    .expect("Checked in detect")
+                    .name;
+                self.write(&format!("{}.{}", name, value));
+            }
+            NodeASTType::EnumMemberSeperator | NodeASTType::EnumClosingBracket => {
+                let declaration = self.enum_declaration.as_mut().expect("Inside an enum");
+                if let Some(suffix) = declaration.pending_suffix.take() {
+                    if !suffix.is_empty() {
+                        self.write(&suffix);
+                    }
+                    self.write(";");
+                }
+                if node_type == NodeASTType::EnumClosingBracket {
+                    let argument = self
+                        .enum_declaration
+                        .take()
+                        .expect("Inside an enum")
+                        .argument;
+                    self.write(&format!("}})({})", argument));
+                    self.write(";");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `var E;(function(E){`, shared by enums and namespaces. A declaration in
+    // `scope` merges with an earlier one of the same name, `None` is a scope of
+    // its own. Returns what the function is called with: `E||(E={})`, or
+    // `E=N.E||(N.E={})` when it is a member of the namespace `N`.
+    fn write_iife_start(
+        &mut self,
+        name: &str,
+        scope: Option<usize>,
+        parent: Option<String>,
+    ) -> String {
+        let is_declared = scope.is_some_and(|scope| {
+            let declaration = (scope, name.to_string());
+            let is_declared = self.declared_names.contains(&declaration);
+            self.declared_names.push(declaration);
+            is_declared
+        });
+        if !is_declared {
+            self.write(if scope == Some(0) { "var" } else { "let" });
+            self.write(name);
+            self.write(";");
+        }
+        self.write(&format!("(function({}){{", name));
+        match parent {
+            Some(parent) => format!("{0}={1}.{0}||({1}.{0}={{}})", name, parent),
+            None => format!("{0}||({0}={{}})", name),
+        }
+    }
+
+    fn is_namespace_body(&self) -> bool {
+        return self.context_type() == NodeASTType::NamespaceOpeningBracket;
+    }
+
+    // The namespace a declaration is exported from, if it is, by looking back
+    // past the keywords of the declaration for an `export`.
+    fn exporting_namespace(&self) -> Option<String> {
+        if !self.is_namespace_body() {
+            return None;
+        }
+        let export = self.nodes.iter().rev().skip(1).find(|node| {
+            node.node_type != NodeASTType::KeywordModifier
+                && !matches!(
+                    node.value.as_str(),
+                    "async" | "function" | "*" | "class" | "enum" | "namespace" | "module"
+                )
+        })?;
+        if export.node_type != NodeASTType::NamespaceExport {
+            return None;
+        }
+        return self
+            .namespaces
+            .last()
+            .map(|namespace| namespace.name.clone());
+    }
+
+    // The namespace that exports a variable named `value`, for a reference to it.
+    fn namespace_of_variable(&self, value: &str) -> Option<&str> {
+        if self.last_written == "." || self.last_written == "?." {
+            return None;
+        }
+        // `{ x: 1 }` is a key rather than a reference.
+        if self.context_type() == NodeASTType::ObjectOpeningBracket
+            && next_token(&self.tokens, self.position + 1) == ":"
+        {
+            return None;
+        }
+        return self
+            .namespaces
+            .iter()
+            .rev()
+            .filter(|namespace| namespace.opener.is_some())
+            .find(|namespace| {
+                namespace
+                    .exported_variables
+                    .iter()
+                    .any(|name| name == value)
+            })
+            .map(|namespace| namespace.name.as_str());
+    }
+
+    // `A.B` for the namespace `B` inside of the ones that are open.
+    fn namespace_path(&self, name: &str) -> String {
+        let mut path: Vec<&str> = self
+            .namespaces
+            .iter()
+            .map(|namespace| namespace.name.as_str())
+            .collect();
+        path.push(name);
+        return path.join(".");
+    }
+
+    // `export const { a, b } = c` in a namespace assigns to its members as
+    // `({a:N.a,b:N.b}=c)`, the names in the pattern being references to them.
+    fn export_namespace_patterns(&mut self) {
+        for declarator in declarators(&self.tokens, self.position + 1) {
+            if !matches!(self.tokens[declarator.start].as_str(), "{" | "[") {
+                continue;
+            }
+            let namespace = self.namespaces.last_mut().expect("Inside a namespace");
+            namespace.exported_variables.extend(declarator.names);
+            let before = |index: usize| {
+                self.tokens[..index]
+                    .iter()
+                    .rposition(|token| token != "\n")
+                    .expect("After the declarator")
+            };
+            for (position, text) in [
+                (before(declarator.start), "("),
+                (before(declarator.end), ")"),
+            ] {
+                self.insertions.push(Insertion {
+                    position,
+                    text: text.to_string(),
+                    is_statement: false,
+                });
+            }
+        }
+    }
+
+    // `{ x }`, which is shorthand for `{ x: x }`.
+    fn is_shorthand_property(&self) -> bool {
+        return self.context_type() == NodeASTType::ObjectOpeningBracket
+            && matches!(self.last_written.as_str(), "{" | ",")
+            && matches!(next_token(&self.tokens, self.position + 1), "," | "}");
+    }
+
+    // `var A;(function(A){` for every name of `namespace A.B {`, and
+    // `})(A||(A={}));` for each of them at its `}`.
+    fn write_namespace(&mut self, node_type: NodeASTType) {
+        let value = self.current_token.clone();
+        match node_type {
+            NodeASTType::NamespaceDeclaration => {
+                let is_dotted = self.last_node().node_type == NodeASTType::NamespaceNameSeperator;
+                let (scope, parent) = match is_dotted {
+                    true => (
+                        None,
+                        self.namespaces
+                            .last()
+                            .map(|namespace| namespace.name.clone()),
+                    ),
+                    false => (Some(self.context().opener), self.exporting_namespace()),
+                };
+                let argument = self.write_iife_start(&value, scope, parent);
+                // What an earlier declaration of the same namespace exported.
+                let exported_variables = self
+                    .namespace_exports
+                    .get(&self.namespace_path(&value))
+                    .cloned()
+                    .unwrap_or_default();
+                self.namespaces.push(Namespace {
+                    name: value,
+                    opener: None,
+                    argument,
+                    exported_variables,
+                    exported_declarations: vec![],
+                });
+            }
+            NodeASTType::NamespaceClosingBracket => {
+                // The context of the body is already closed, which closes
+                // every name of `namespace A.B {`.
+                while let Some(namespace) = self.namespaces.last() {
+                    let opener = namespace.opener;
+                    if self
+                        .contexts
+                        .iter()
+                        .any(|context| Some(context.opener) == opener)
+                    {
+                        break;
+                    }
+                    let mut namespace = self.namespaces.pop().expect("Checked above");
+                    self.write(&format!("}})({})", namespace.argument));
+                    let path = self.namespace_path(&namespace.name);
+                    namespace
+                        .exported_variables
+                        .append(&mut namespace.exported_declarations);
+                    self.namespace_exports
+                        .insert(path, namespace.exported_variables);
+                    self.write(";");
+                }
+            }
+            NodeASTType::NamespaceMemberReference => {
+                let namespace = self
+                    .namespace_of_variable(&value)
+                    .expect("Checked in detect");
+                let reference = format!("{}.{}", namespace, value);
+                match self.is_shorthand_property() {
+                    true => self.write(&format!("{}:{}", value, reference)),
+                    false => self.write(&reference),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn write_enum_member(&mut self) {
+        let tokens = &self.tokens;
+        let value = self.current_token.clone();
+        let is_identifier = lookahead::is_identifier(&value);
+        let key = if is_identifier {
+            format!("\"{}\"", value)
+        } else {
+            value.clone()
+        };
+        let initializer_start = next_index(tokens, self.position + 1);
+        let has_initializer = next_token(tokens, initializer_start) == "=";
+        let initializer: Vec<&str> = match has_initializer {
+            true => tokens[initializer_start + 1..enum_member_end(tokens, self.position)]
+                .iter()
+                .map(|token| token.as_str())
+                .filter(|token| *token != "\n")
+                .collect(),
+            false => vec![],
+        };
+
+        let declaration = self.enum_declaration.as_mut().expect("Inside an enum");
+        let name = declaration.name.clone();
+        let is_string =
+            has_initializer && is_string_initializer(&initializer, &declaration.string_members);
+        if is_identifier {
+            declaration.members.push(value.clone());
+            if is_string {
+                declaration.string_members.push(value.clone());
+            }
+        }
+        let auto_value = match (declaration.next_value, &declaration.previous_key) {
+            (Some(next_value), _) => next_value.to_string(),
+            (None, Some(previous_key)) => format!("{}[{}]+1", name, previous_key),
+            (None, None) => String::from("0"),
+        };
+        declaration.next_value = match has_initializer {
+            true => numeric_initializer(&initializer).map(|value| value + 1),
+            false => declaration.next_value.map(|value| value + 1),
+        };
+        declaration.previous_key = Some(key.clone());
+
+        if !has_initializer {
+            self.write(&format!(
+                "{}[{}[{}]={}]={}",
+                name, name, key, auto_value, key
+            ));
+            self.write(";");
+        } else if is_string {
+            declaration.pending_suffix = Some(String::new());
+            self.write(&format!("{}[{}]=", name, key));
+        } else {
+            declaration.pending_suffix = Some(format!("]={}", key));
+            self.write(&format!("{}[{}[{}]=", name, name, key));
+        }
+    }
+
+    // Nothing resolves the `paths` and `baseUrl` of `tsconfig.json` at
+    // runtime, so specifiers like `@app/util` are made relative to this module
+    // when they are one of its aliases.
+    fn rewrite_aliases(&mut self, module_path: &Path) {
+        if self.options.paths.is_empty() && self.options.base_url.is_none() {
+            return;
+        }
+        for position in 0..self.tokens.len() {
+            if !is_module_specifier(&self.tokens, position) {
+                continue;
+            }
+            let specifier = string_value(&self.tokens[position]).unwrap_or_default();
+            if let Some(path) = resolve_alias(&specifier, &self.options) {
+                let quote = &self.tokens[position][..1];
+                let relative = relative_specifier(module_path, &path, &specifier);
+                self.tokens[position] = format!("{}{}{}", quote, relative, quote);
+            }
+        }
+    }
+
+    // Const enums are erased, so their values are looked up ahead of time in
+    // this module and in the modules it imports them from.
+    fn collect_const_enums(&mut self, module_path: &Path) {
+        self.const_enums = declared_const_enums(&self.tokens, false);
+        for position in 0..self.tokens.len() {
+            if self.tokens[position] != "import" {
+                continue;
+            }
+            let declaration = match parse_import(&self.tokens, position) {
+                Some(declaration) => declaration,
+                None => continue,
+            };
+            let source = string_value(&declaration.source).unwrap_or_default();
+            let enums = match resolve(module_path, &source, Resolution::Types) {
+                Some(path) => imported_const_enums(&path),
+                None => continue,
+            };
+            if let Some(namespace) = &declaration.namespace_binding {
+                self.namespace_const_enums
+                    .insert(namespace.clone(), enums.clone());
+            }
+            for specifier in declaration.named.iter().flatten() {
+                if let Some(members) = enums.get(&specifier.name) {
+                    self.const_enums
+                        .insert(specifier.alias.clone(), members.clone());
+                }
+            }
+        }
+    }
+
+    // With CommonJS the bindings of an import are read from the module they
+    // come from wherever they are used, which keeps them live like they are
+    // with ES modules: `import { a } from './b'` makes `a` into `b_1.a`.
+    fn collect_import_bindings(&mut self) {
+        for position in 0..self.tokens.len() {
+            if self.tokens[position] != "import"
+                || (position > 0 && self.tokens[position - 1] == ".")
+            {
+                continue;
+            }
+            let declaration = match parse_import(&self.tokens, position) {
+                Some(declaration) if !declaration.is_type_only => declaration,
+                _ => continue,
+            };
+            if declaration.is_side_effect_only {
+                continue;
+            }
+            // `import * as a` is required into `a` itself.
+            let variable = match &declaration.namespace_binding {
+                Some(namespace) => namespace.clone(),
+                None => module_variable(&declaration.source, |name| {
+                    self.module_variables.values().any(|used| used == name)
+                }),
+            };
+            let named = declaration.named.iter().flatten();
+            let bindings = named
+                .filter(|specifier| !specifier.is_type_only)
+                .map(|specifier| (&specifier.alias, member(&variable, &specifier.name)))
+                .chain(declaration.default_binding.iter().map(|binding| {
+                    // A JSON module is required as the JSON itself.
+                    let is_json = is_json(&declaration.attributes)
+                        && declaration.named.is_none()
+                        && declaration.namespace_binding.is_none();
+                    match is_json {
+                        true => (binding, variable.clone()),
+                        false => (binding, format!("{}.default", variable)),
+                    }
+                }));
+            for (binding, reference) in bindings {
+                if !self.const_enums.contains_key(binding) {
+                    self.import_bindings.insert(binding.clone(), reference);
+                }
+            }
+            self.module_variables.insert(position, variable);
+        }
+    }
+
+    // UMD and IIFE modules are CommonJS inside of their wrapper.
+    fn is_commonjs(&self) -> bool {
+        return self.options.module != ModuleKind::EsModule;
+    }
+
+    // A use of an imported binding, see `collect_import_bindings`. Keys like
+    // `{ a: 1 }`, `{ a() {} }` or `const { a: b } = c` only share its name,
+    // as do parameters and variables declared inside functions and blocks.
+    fn is_import_binding_reference(&self, value: &str) -> bool {
+        if !self.import_bindings.contains_key(value)
+            || self.last_written == "."
+            || self.last_written == "?."
+            || self.scopes.is_shadowed(value, self.position)
+        {
+            return false;
+        }
+        return !self.is_property_key();
+    }
+
+    // `{ a: 1 }`, `{ a() {} }` or the `a` of `const { a: b } = c`, which is
+    // only the name of a property.
+    fn is_property_key(&self) -> bool {
+        let next = next_token(&self.tokens, self.position + 1);
+        return matches!(self.last_written.as_str(), "{" | ",")
+            && (next == ":"
+                || (next == "(" && self.context_type() == NodeASTType::ObjectOpeningBracket));
+    }
+
+    // `b_1.a`, or `(0,b_1.a)` when it is called so that it isn't called with
+    // the module as `this`.
+    fn write_import_binding_reference(&mut self) {
+        let value = self.current_token.clone();
+        let reference = self.import_bindings[&value].clone();
+        self.value_references.insert(value.clone());
+        let next = next_token(&self.tokens, self.position + 1);
+        let is_called = next == "(" || next.starts_with('`');
+        if self.is_shorthand_property() {
+            self.write(&format!("{}:{}", value, reference));
+        } else if is_called && self.last_written != "new" {
+            self.write(&format!("(0,{})", reference));
+        } else {
+            self.write(&reference);
+        }
+    }
+
+    // `E.A` or `E["A"]` of a const enum, or `M.E.A` of one imported with
+    // `import * as M`. Returns the index past it and the value it is inlined as.
+    fn const_enum_reference(&self) -> Option<(usize, &String)> {
+        if self.last_written == "."
+            || self.last_written == "?."
+            || self.scopes.is_shadowed(&self.current_token, self.position)
+        {
+            return None;
+        }
+        let tokens = &self.tokens;
+        let mut accessor = next_index(tokens, self.position + 1);
+        let members = match self.namespace_const_enums.get(&self.current_token) {
+            Some(enums) if next_token(tokens, accessor) == "." => {
+                let name = next_index(tokens, accessor + 1);
+                accessor = next_index(tokens, name + 1);
+                enums.get(next_token(tokens, name))?
+            }
+            _ => self.const_enums.get(&self.current_token)?,
+        };
+        let member = next_index(tokens, accessor + 1);
+        let (member, end) = match next_token(tokens, accessor) {
+            "." => (next_token(tokens, member).to_string(), member + 1),
+            "[" if next_token(tokens, member + 1) == "]" => (
+                string_value(next_token(tokens, member))?,
+                next_index(tokens, member + 1) + 1,
+            ),
+            _ => return None,
+        };
+        return Some((end, members.get(&member)?));
+    }
+
+    // What is written in place of a node that is measured ahead and skipped.
+    fn replacement(&self, node_type: NodeASTType) -> Vec<String> {
+        match node_type {
+            // CommonJS has the path of the module in `__filename` instead, and
+            // resolves modules with `require.resolve`.
+            NodeASTType::ImportMeta => {
+                let end = self.measured_end(node_type);
+                let url = "require(\"url\").pathToFileURL(__filename).href";
+                let resolve = "s=>require(\"url\").pathToFileURL(require.resolve(s)).href";
+                let meta = match self.tokens[end - 1].as_str() {
+                    "url" => url.to_string(),
+                    "dirname" => String::from("__dirname"),
+                    "filename" => String::from("__filename"),
+                    "resolve" => format!("({})", resolve),
+                    _ => format!(
+                        "{{url:{},dirname:__dirname,filename:__filename,resolve:{}}}",
+                        url, resolve
+                    ),
+                };
+                return vec![meta];
+            }
+            NodeASTType::ConstEnumReference => {
+                let (_, value) = self.const_enum_reference().expect("Checked in detect");
+                return vec![value.clone()];
+            }
+            _ => vec![],
+        }
+    }
+
+    // Imports and export lists are written out in full but only kept as far as
+    // their bindings turn out to be values, which `finish` works out once the
+    // whole module has been written.
+    fn write_module_declaration(&mut self, node_type: NodeASTType) {
+        let start = self.output.len();
+        let declaration = match node_type {
+            NodeASTType::ImportDeclaration => {
+                let mut declaration =
+                    parse_import(&self.tokens, self.position).expect("Checked in detect");
+                // An import of a const enum has nothing to import at runtime.
+                declaration.retain(|binding| !self.const_enums.contains_key(binding));
+                ModuleDeclaration::Import(declaration)
+            }
+            _ => ModuleDeclaration::Export(
+                parse_export_list(&self.tokens, self.position).expect("Checked in detect"),
+            ),
+        };
+        let tokens = match &declaration {
+            ModuleDeclaration::Import(declaration) => declaration.to_tokens(),
+            ModuleDeclaration::Export(list) => list.to_tokens(),
+            ModuleDeclaration::ImportEquals(_) => unreachable!("See write_import_equals"),
+        };
+        for value in tokens {
+            self.write(&value);
+        }
+        if let ModuleDeclaration::Export(ExportList {
+            source: Some(source),
+            ..
+        }) = &declaration
+        {
+            if self.is_commonjs() {
+                let variable = module_variable(source, |name| {
+                    self.module_variables.values().any(|used| used == name)
+                });
+                self.module_variables.insert(self.position, variable);
+            }
+        }
+        self.is_module = true;
+        self.module_declarations.push(WrittenModuleDeclaration {
+            declaration,
+            position: self.position,
+            start,
+            end: self.output.len(),
+        });
+    }
+
+    // With CommonJS `export` is left out and what it exports is read with a
+    // getter, see `finish`. `export default` of an expression assigns it.
+    fn write_commonjs_export(&mut self) {
+        let end = self.measured_end(NodeASTType::CommonJsExport);
+        let is_default = end > self.position + 1;
+        self.is_module = true;
+        let Some(declaration) = exported_declaration(&self.tokens, end) else {
+            if is_default {
+                self.write("exports.default");
+                self.write("=");
+            }
+            return;
+        };
+        // `export default function() {}` needs a name to be read by.
+        if let Some(position) = declaration.anonymous {
+            self.insertions.push(Insertion {
+                position,
+                text: String::from("default_1"),
+                is_statement: false,
+            });
+        }
+        for name in declaration.names {
+            let exported = match is_default {
+                true => String::from("default"),
+                false => name.clone(),
+            };
+            self.commonjs_exports.push((exported, name));
+        }
+    }
+
+    // `__exportStar(require('./a'),exports);` for CommonJS. It is measured
+    // for ES modules too, the braces of its attributes aren't a block.
+    fn write_export_all(&mut self) {
+        let export = parse_export_all(&self.tokens, self.position).expect("Checked in detect");
+        self.is_module = true;
+        if !self.is_commonjs() {
+            for value in export.to_tokens() {
+                self.write(&value);
+            }
+            return;
+        }
+        let (statement, helper) = export_all_statement(&export);
+        self.use_helper(helper);
+        self.required_modules.push((self.position, export.source));
+        self.write(&statement);
+        self.write(";");
+    }
+
+    // `import('./a')` becomes a `require` that happens once a promise has
+    // resolved, like `tsc` writes it. A specifier that isn't a string is
+    // passed through the promise so that it is evaluated right away.
+    fn write_dynamic_import(&mut self) {
+        let opening = next_index(&self.tokens, self.position + 1);
+        let close = matching_close(&self.tokens, opening);
+        let argument = next_index(&self.tokens, opening + 1);
+        let is_string = self.tokens[argument].starts_with(['\'', '"'])
+            && next_index(&self.tokens, argument + 1) == close;
+        let (start, end) = match is_string {
+            true => ("Promise.resolve().then(()=>__importStar(require", "))"),
+            false => ("Promise.resolve", ".then(s=>__importStar(require(s)))"),
+        };
+        self.use_helper("__importStar");
+        self.write(start);
+        self.insertions.push(Insertion {
+            position: close,
+            text: end.to_string(),
+            is_statement: false,
+        });
+    }
+
+    // `import fs = require('fs')` is a `require` of its own, which ES modules
+    // get from `createRequire`, and `import A = N.B` is a variable. Unless
+    // they are exported, they are left out when they are only used as types,
+    // see `finish`.
+    fn write_import_equals(&mut self) {
+        let declaration =
+            parse_import_equals(&self.tokens, self.position).expect("Checked in detect");
+        if declaration.is_type_only {
+            return;
+        }
+        let name = declaration.name.clone();
+        let value = match &declaration.source {
+            Some(source) if self.is_commonjs() => format!("require({})", source),
+            Some(source) => format!("__require({})", source),
+            None => self.with_import_references(&join_tokens(&declaration.entity)),
+        };
+        if declaration.is_exported && self.is_namespace_body() {
+            let namespace = self.namespaces.last_mut().expect("Inside a namespace");
+            namespace.exported_variables.push(name.clone());
+            let assignment = format!("{}.{}={}", namespace.name, name, value);
+            self.write(&assignment);
+            self.write(";");
+            return;
+        }
+        let keyword = match declaration.source {
+            Some(_) => "const",
+            None => "var",
+        };
+        let mut statement = format!("{} {}={}", keyword, name, value);
+        if declaration.is_exported {
+            match self.is_commonjs() {
+                true => self.commonjs_exports.push((name.clone(), name)),
+                false => statement.insert_str(0, "export "),
+            }
+            self.is_module = true;
+        }
+        let start = self.output.len();
+        self.write(&statement);
+        self.write(";");
+        self.module_declarations.push(WrittenModuleDeclaration {
+            declaration: ModuleDeclaration::ImportEquals(declaration),
+            position: self.position,
+            start,
+            end: self.output.len(),
+        });
+    }
+
+    fn use_helper(&mut self, helper: &'static str) {
+        self.helpers.insert(helper);
+        self.helpers.extend(dependencies(helper));
+    }
+
+    // `export type { A }`, `export type * from`, `export interface A {}` and
+    // everything else that is exported but only exists as a type.
+    fn type_only_export_end(&self) -> Option<usize> {
+        let tokens = &self.tokens;
+        if let Some(end) = type_only_export_all_end(tokens, self.position) {
+            return Some(end);
+        }
+        if let Some(list) = parse_export_list(tokens, self.position) {
+            return Some(list.end).filter(|_| list.is_type_only);
+        }
+        let mut index = next_index(tokens, self.position + 1);
+        if next_token(tokens, index) == "default" {
+            index = next_index(tokens, index + 1);
+            if next_token(tokens, index) != "interface" {
+                return None;
+            }
+        }
+        return type_only_declaration_end(tokens, index);
+    }
+
+    // Remembers the name of an interface, a type alias or a type-only
+    // namespace, whose `export { A }` has nothing to export at runtime.
+    fn record_type_declaration(&mut self, node_type: NodeASTType) {
+        let tokens = &self.tokens;
+        let mut keyword = self.position;
+        if node_type == NodeASTType::TypeOnlyExport {
+            keyword = next_index(tokens, keyword + 1);
+            if next_token(tokens, keyword) == "default" {
+                keyword = next_index(tokens, keyword + 1);
+            }
+        }
+        if matches!(
+            next_token(tokens, keyword),
+            "interface" | "type" | "namespace" | "module"
+        ) {
+            let name = next_token(tokens, keyword + 1).to_string();
+            self.type_declarations.insert(name);
+        }
+    }
+
+    // Rewrites the imports and export lists without the bindings that were
+    // only ever used as types, and as `require` and getters for CommonJS.
+    fn finish(&mut self) {
+        let declarations = std::mem::take(&mut self.module_declarations);
+        let mut import_bindings = HashSet::new();
+        let mut exported_bindings = HashSet::new();
+        for written in declarations.iter() {
+            match &written.declaration {
+                ModuleDeclaration::Import(declaration) => {
+                    import_bindings.extend(declaration.bindings().into_iter().cloned())
+                }
+                ModuleDeclaration::Export(list) if list.source.is_none() => exported_bindings
+                    .extend(
+                        list.specifiers
+                            .iter()
+                            .map(|specifier| specifier.name.clone()),
+                    ),
+                ModuleDeclaration::Export(_) => {}
+                ModuleDeclaration::ImportEquals(declaration) => {
+                    import_bindings.insert(declaration.name.clone());
+                }
+            }
+        }
+
+        let mut list_exports = vec![];
+        let mut has_create_require = false;
+        for mut written in declarations.into_iter().rev() {
+            let replacement = match &mut written.declaration {
+                ModuleDeclaration::Import(declaration) => {
+                    // `export { A }` keeps the import, we can't tell whether
+                    // it is a type from here.
+                    let is_verbatim = self.options.verbatim_module_syntax;
+                    declaration.retain(|binding| {
+                        is_verbatim
+                            || self.value_references.contains(binding)
+                            || exported_bindings.contains(binding)
+                    });
+                    // `import { type A } from './a'` still loads the module.
+                    if is_verbatim && !declaration.is_type_only && declaration.bindings().is_empty()
+                    {
+                        declaration.is_side_effect_only = true;
+                    }
+                    match self.is_commonjs() {
+                        true => self.commonjs_import(declaration, written.position),
+                        false => join_tokens(&declaration.to_tokens()),
+                    }
+                }
+                ModuleDeclaration::Export(list) => {
+                    if list.source.is_none() {
+                        list.specifiers.retain(|specifier| {
+                            let name = &specifier.name;
+                            !self.type_declarations.contains(name)
+                                || self.value_references.contains(name)
+                                || import_bindings.contains(name)
+                        });
+                    }
+                    match (self.is_commonjs(), &list.source) {
+                        (true, Some(_)) => self.commonjs_reexport(list, written.position),
+                        (true, None) => {
+                            let exports = self.commonjs_export_list(list);
+                            list_exports.splice(0..0, exports);
+                            String::new()
+                        }
+                        (false, _) => join_tokens(&list.to_tokens()),
+                    }
+                }
+                ModuleDeclaration::ImportEquals(declaration) => {
+                    let name = &declaration.name;
+                    if !declaration.is_exported
+                        && !self.value_references.contains(name)
+                        && !exported_bindings.contains(name)
+                    {
+                        String::new()
+                    } else {
+                        // `import A = N.B` keeps whatever `N` is imported from.
+                        if let Some(namespace) = declaration.entity.first() {
+                            self.value_references.insert(namespace.clone());
+                        }
+                        if let Some(source) = &declaration.source {
+                            has_create_require = !self.is_commonjs();
+                            self.required_modules
+                                .push((written.position, source.clone()));
+                        }
+                        continue;
+                    }
+                }
+            };
+            let text = &self.output[written.start..written.end];
+            let start = written.start + text.len() - text.trim_start().len();
+            self.output.replace_range(start..written.end, &replacement);
+        }
+        self.commonjs_exports.extend(list_exports);
+
+        if self.options.target < Target::Es2022 {
+            let (output, helpers) = downlevel(&self.output, &self.options);
+            self.output = output;
+            self.helpers.extend(helpers);
+        }
+
+        let mut prologue = String::new();
+        if self.is_commonjs() {
+            prologue += "\"use strict\";";
+        }
+        prologue.extend(
+            HELPERS
+                .iter()
+                .filter(|(name, _)| self.helpers.contains(name))
+                .map(|(_, helper)| *helper),
+        );
+        if has_create_require {
+            prologue += "import{createRequire as _createRequire}from\"module\";const __require=_createRequire(import.meta.url);";
+        }
+        if self.is_commonjs() && self.is_module {
+            prologue += "Object.defineProperty(exports,\"__esModule\",{value:true});";
+            for (name, expression) in self.commonjs_exports.iter() {
+                prologue += &export_getter(name, expression);
+            }
+        }
+        self.output.insert_str(0, &prologue);
+        self.wrap_module();
+    }
+
+    // UMD runs the module as CommonJS when there is a `module` to export to
+    // and defines it for AMD otherwise, and IIFE calls it right away with an
+    // object to export to, like `tsc` and bundlers write them.
+    fn wrap_module(&mut self) {
+        let (start, end) = match self.options.module {
+            ModuleKind::Umd => {
+                self.required_modules.sort();
+                let dependencies: String = self
+                    .required_modules
+                    .iter()
+                    .map(|(_, source)| format!(",{}", source))
+                    .collect();
+                (
+                    format!(
+                        "(function(factory){{if(typeof module===\"object\"&&typeof module.exports===\"object\"){{var v=factory(require,exports);if(v!==undefined)module.exports=v;}}else if(typeof define===\"function\"&&define.amd){{define([\"require\",\"exports\"{}],factory);}}}})(function(require,exports){{",
+                        dependencies
+                    ),
+                    String::from("});"),
+                )
+            }
+            ModuleKind::Iife => {
+                let assignment = match &self.options.global_name {
+                    Some(name) => format!("var {}=", name),
+                    None => String::new(),
+                };
+                // The last statement might be missing its `;`.
+                let seperator = match self.output.ends_with([';', '}']) {
+                    true => "",
+                    false => ";",
+                };
+                // Imports are read from global variables instead, through a
+                // `require` of its own.
+                let (parameters, arguments) = match self.required_modules.is_empty() {
+                    true => ("exports", String::from("{}")),
+                    false => ("exports,require", format!("{{}},{}", self.global_require())),
+                };
+                (
+                    format!("{}(function({}){{", assignment, parameters),
+                    format!("{}return exports;}})({});", seperator, arguments),
+                )
+            }
+            ModuleKind::EsModule | ModuleKind::CommonJs => return,
+        };
+        self.output.insert_str(0, &start);
+        self.output += &end;
+    }
+
+    // `function(id){switch(id){case"jquery":return $;}}`, with the global
+    // variable of each module from the `globals` option. Others are guessed
+    // from their name, `lodash-es` is `lodashEs`, unless nothing is read from
+    // the module, which then has no case and is `undefined`.
+    fn global_require(&mut self) -> String {
+        self.required_modules.sort();
+        let mut cases = String::new();
+        let mut seen = HashSet::new();
+        for (_, source) in self.required_modules.iter() {
+            if !seen.insert(source) {
+                continue;
+            }
+            let is_side_effect_only = self.required_modules.iter().all(|(position, other)| {
+                other != source || self.side_effect_imports.contains(position)
+            });
+            let module = &source[1..source.len() - 1];
+            let global = match self.options.globals.iter().find(|(name, _)| name == module) {
+                Some((_, global)) => global.clone(),
+                None if is_side_effect_only => continue,
+                None => global_name(module),
+            };
+            cases += &format!("case{}:return {};", source, global);
+        }
+        return format!("function(id){{switch(id){{{}}}}}", cases);
+    }
 
-const MODULE_NODE: Node = Node {
-    value: String::new(),
-    parent: None,
-    node_type: NodeASTType::Module,
-};
+    fn commonjs_import(&mut self, declaration: &ImportDeclaration, position: usize) -> String {
+        let variable = self.module_variables.get(&position).cloned();
+        let (statement, helper) = import_statement(declaration, &variable.unwrap_or_default());
+        if let Some(helper) = helper {
+            self.use_helper(helper);
+        }
+        if !statement.is_empty() {
+            self.required_modules
+                .push((position, declaration.source.clone()));
+        }
+        if declaration.is_side_effect_only {
+            self.side_effect_imports.insert(position);
+        }
+        return statement;
+    }
 
-struct Tree {
-    output: String,
-    current_token: String,
-    nodes: Vec<Node>,
+    // `export { a } from './b'` requires the module in place, with getters
+    // that read from it.
+    fn commonjs_reexport(&mut self, list: &ExportList, position: usize) -> String {
+        let variable = &self.module_variables[&position];
+        let (statement, helper) = reexport_statement(list, variable);
+        if let Some(helper) = helper {
+            self.use_helper(helper);
+        }
+        if let Some(source) = list.source.as_ref().filter(|_| !statement.is_empty()) {
+            self.required_modules.push((position, source.clone()));
+        }
+        return statement;
+    }
 
-    // private
-    is_paused: bool,
-    paused_node_type: NodeASTType,
+    // The getters of `export { a, b as c }`, which read imported bindings
+    // from their module.
+    fn commonjs_export_list(&self, list: &ExportList) -> Vec<(String, String)> {
+        if list.is_type_only {
+            return vec![];
+        }
+        return list
+            .specifiers
+            .iter()
+            .filter(|specifier| !specifier.is_type_only)
+            .map(|specifier| {
+                let expression = match self.import_bindings.get(&specifier.name) {
+                    Some(reference) => reference.clone(),
+                    None => specifier.name.clone(),
+                };
+                (specifier.alias.clone(), expression)
+            })
+            .collect();
+    }
 
-    // pausers
-    keyword_declare_pauser: KeywordDeclarePauser,
-    keyword_type_pauser: KeywordTypePauser,
-    keyword_as_pauser: KeywordAsPauser,
-    comment_pauser: CommentPauser,
-}
+    pub fn update_current_value(&mut self, value: &str) {
+        self.current_token = value.to_string();
+    }
 
-impl Tree {
-    fn new() -> Tree {
-        return Tree {
-            output: String::new(),
-            current_token: String::new(),
-            nodes: vec![MODULE_NODE],
-            is_paused: false,
-            paused_node_type: NodeASTType::Unknown,
+    // `enum E {` as opposed to `enum` being used as a plain identifier.
+    fn is_enum_declaration(&self) -> bool {
+        let name = next_index(&self.tokens, self.position + 1);
+        return self
+            .tokens
+            .get(name)
+            .is_some_and(|t| lookahead::is_identifier(t))
+            && next_token(&self.tokens, name + 1) == "{";
+    }
 
-            // pausers
-            keyword_declare_pauser: KeywordDeclarePauser::new(),
-            keyword_type_pauser: KeywordTypePauser::new(),
-            keyword_as_pauser: KeywordAsPauser::new(),
-            comment_pauser: CommentPauser::new(),
-        };
+    // `class A {` or `class {`, as opposed to a property named `class` like
+    // `this.class` or `{ class: 1 }`.
+    fn is_class_keyword(&self) -> bool {
+        let next = next_token(&self.tokens, self.position + 1);
+        return !matches!(self.last_written.as_str(), "." | "?.")
+            && (lookahead::is_identifier(next) || next == "{");
+    }
+
+    // `const enum E {` or `export const enum E {`, which only exist at compile time.
+    fn is_const_enum_declaration(&self) -> bool {
+        let tokens = &self.tokens;
+        let mut index = next_index(tokens, self.position);
+        if tokens[index] == "export" {
+            index = next_index(tokens, index + 1);
+        }
+        return next_token(tokens, index) == "const" && next_token(tokens, index + 1) == "enum";
+    }
+
+    // `type Foo<T> = ...` as opposed to `type` being used as a plain identifier.
+    fn is_type_alias(&self) -> bool {
+        let name = next_index(&self.tokens, self.position + 1);
+        if !self
+            .tokens
+            .get(name)
+            .is_some_and(|t| lookahead::is_identifier(t))
+        {
+            return false;
+        }
+        let next = next_token(&self.tokens, name + 1);
+        return next == "=" || next == "<";
     }
 
-    pub fn debug(&self) {
-        for token in self.nodes.iter() {
-            let t = token.clone();
-            println!("node: {}", t);
+    // Whether the last written token leaves us waiting for an expression,
+    // e.g. after `=`, `(` or `return` but not after `;`, `)` or an identifier.
+    fn expects_expression(&self) -> bool {
+        let previous = self.last_written.as_str();
+        match previous {
+            "" | ";" | "{" | "}" | ")" | "]" => false,
+            ":" => matches!(
+                self.last_written_type,
+                NodeASTType::ObjectPropertySeperator | NodeASTType::TernaryElseSeperator
+            ),
+            _ if can_end_expression(previous) => EXPRESSION_KEYWORDS.contains(&previous),
+            _ => true,
         }
     }
 
-    pub fn last_node(&self) -> Node {
-        let node = self.nodes.last().expect("Must exist");
-        return Node {
-            value: node.value.clone(),
-            parent: Some(Box::new(node.get_parent())),
-            node_type: node.node_type,
+    // `value as T` or `value satisfies T`, as opposed to a binding named `as`.
+    fn is_type_operator(&self) -> bool {
+        let previous = self.last_written.as_str();
+        let next = next_token(&self.tokens, self.position + 1);
+        return can_end_expression(previous)
+            && !EXPRESSION_KEYWORDS.contains(&previous)
+            && (can_start_expression(next) || next == "{");
+    }
+
+    // `<T>value` where a `<` can't be a comparison. The lexer splits `<<` in
+    // two, so the second half of a shift is never an assertion.
+    fn is_type_assertion(&self) -> bool {
+        let previous = self.last_written.as_str();
+        return previous != "<"
+            && (self.expects_expression() || matches!(previous, "" | ";" | "{"));
+    }
+
+    // `f<T>(a)`, `new Map<K, V>()` or `` sql<Row>`...` `` as opposed to a
+    // comparison. Like `tsc`, the `>` has to be followed by something that
+    // can't continue a comparison: a call, a template or the end of the
+    // expression.
+    fn is_type_arguments(&self) -> bool {
+        let previous = self.last_written.as_str();
+        if !can_end_expression(previous) || EXPRESSION_KEYWORDS.contains(&previous) {
+            return false;
+        }
+        let Some(close) = type_arguments_close(&self.tokens, self.position) else {
+            return false;
         };
+        let next = next_token(&self.tokens, close + 1);
+        match next {
+            "(" => true,
+            _ if next.starts_with('`') => true,
+            "<" | ">" | "+" | "-" | "{" => false,
+            _ => {
+                self.tokens.get(close + 1).map(String::as_str) == Some("\n")
+                    || !can_start_expression(next)
+            }
+        }
     }
 
-    pub fn pause_writing(&mut self, node_type: NodeASTType) {
-        self.is_paused = true;
-        self.paused_node_type = node_type;
+    // `value!`, which can't start on a new line since that would be `!value`.
+    fn is_non_null_assertion(&self) -> bool {
+        let previous = self.last_written.as_str();
+        let is_after_line_break = self.position > 0 && self.tokens[self.position - 1] == "\n";
+        return can_end_expression(previous)
+            && !EXPRESSION_KEYWORDS.contains(&previous)
+            && !is_after_line_break;
     }
 
-    // For a given node type that paused writing, this will handle the logic
-    // that would consider resuming. For example, if the node is paused for a declare keyword
-    // it will only resume once the number of opening and closing brackets are equal.
-    pub fn consider_resuming_writing(&mut self, node_type: NodeASTType) {
-        if self.is_paused {
-            self.is_paused = match self.paused_node_type {
-                NodeASTType::KeywordType => self
-                    .keyword_type_pauser
-                    .is_paused_after_evaluating(node_type),
-                NodeASTType::KeywordInterface | NodeASTType::KeywordDeclare => self
-                    .keyword_declare_pauser
-                    .is_paused_after_evaluating(node_type),
-                NodeASTType::KeywordAs => {
-                    self.keyword_as_pauser.is_paused_after_evaluating(node_type)
-                }
-                NodeASTType::CommentLine => {
-                    self.comment_pauser.is_paused_after_evaluating(node_type)
+    // `{ m(a: T) {} }`, the parameters of an object literal method as opposed
+    // to a call in one of its values. Looks back past the name of the method
+    // and its modifiers for the `{` or `,` that starts a property.
+    fn is_object_method_parameters(&self) -> bool {
+        if self.context_type() != NodeASTType::ObjectOpeningBracket {
+            return false;
+        }
+        let tokens = &self.tokens;
+        let mut index = self.position;
+        let mut previous = || {
+            index = tokens[..index].iter().rposition(|token| token != "\n")?;
+            Some(tokens[index].as_str())
+        };
+        let mut token = previous();
+        // Type parameters like `m<T>()`, then a computed name like `[key]`.
+        for (close, open) in [(">", "<"), ("]", "[")] {
+            if token != Some(close) {
+                continue;
+            }
+            let mut depth = 0;
+            while let Some(value) = token {
+                if value == close {
+                    depth += 1;
+                } else if value == open {
+                    depth -= 1;
                 }
-                NodeASTType::CommentMultilineOpener => {
-                    self.comment_pauser.is_paused_after_evaluating(node_type)
+                if depth == 0 {
+                    break;
                 }
-                _ => self.is_paused,
-            };
+                token = previous();
+            }
+            if close == ">" {
+                token = previous();
+            }
+        }
+        if token != Some("[") && !token.is_some_and(can_end_expression) {
+            return false;
         }
+        token = previous();
+        while matches!(token, Some("*" | "async" | "get" | "set")) {
+            token = previous();
+        }
+        return matches!(token, Some("{" | ","));
     }
 
-    pub fn update_current_value(&mut self, value: &str) {
-        self.current_token = value.to_string();
+    fn is_async_arrow_type_parameters(&self) -> bool {
+        let close = matching_close(&self.tokens, self.position);
+        let parameters = next_index(&self.tokens, close + 1);
+        return next_token(&self.tokens, parameters) == "("
+            && self.is_followed_by_arrow(parameters);
+    }
+
+    // `(a: T) => a` as opposed to a call or a parenthesized expression.
+    fn is_arrow_parameters(&self) -> bool {
+        let previous = self.last_written.as_str();
+        if can_end_expression(previous)
+            && !EXPRESSION_KEYWORDS.contains(&previous)
+            && previous != "async"
+        {
+            return false;
+        }
+
+        return self.is_followed_by_arrow(self.position);
+    }
+
+    // Whether the parentheses opened at `opening` are followed by the `=>` of
+    // an arrow function.
+    fn is_followed_by_arrow(&self, opening: usize) -> bool {
+        let close = matching_close(&self.tokens, opening);
+        let next = next_index(&self.tokens, close + 1);
+        match next_token(&self.tokens, next) {
+            "=>" => true,
+            // `(a): T => a`, where a `:` of a ternary like `b ? (a) : c` has no
+            // `=>` after what would be the type.
+            ":" => next_token(&self.tokens, type_end(&self.tokens, next + 1)) == "=>",
+            _ => false,
+        }
+    }
+
+    fn detect_colon_type(&self) -> NodeASTType {
+        let context = self.context();
+        let context_type = self.context_type();
+        let last_node = self.last_node();
+
+        if context.binding == BindingState::Named {
+            if context_type == NodeASTType::FunctionOpeningParenthesis {
+                return NodeASTType::FunctionIdentifierTypeSeperator;
+            }
+            return NodeASTType::VariableTypeSeperator;
+        }
+        // Before the ternary, for `b ? (): T => a : c`.
+        if last_node.node_type == NodeASTType::FunctionClosingParenthesis {
+            return NodeASTType::FunctionReturnTypeSeperator;
+        }
+        if context.pending_ternaries > 0 {
+            return NodeASTType::TernaryElseSeperator;
+        }
+        if context.is_pending_case {
+            return NodeASTType::CaseSeperator;
+        }
+        if context_type == NodeASTType::ObjectOpeningBracket {
+            return NodeASTType::ObjectPropertySeperator;
+        }
+
+        let is_statement_context = matches!(
+            context_type,
+            NodeASTType::Module | NodeASTType::OpeningBracket
+        );
+        if is_statement_context && last_node.value == "default" {
+            return NodeASTType::CaseSeperator;
+        }
+        if is_statement_context && last_node.node_type == NodeASTType::Identifier {
+            return NodeASTType::LabelSeperator;
+        }
+
+        return NodeASTType::Unknown;
     }
 
     pub fn detect_type(&self, value: String) -> NodeASTType {
+        if self.is_paused {
+            match self.paused_node_type {
+                NodeASTType::FunctionIdentifierTypeSeperator => {
+                    return NodeASTType::FunctionIdentifierType
+                }
+                node_type if is_measured_ahead(node_type) => return NodeASTType::TypeAnnotation,
+                // Paused nodes don't update the contexts, so plain brackets are all
+                // the pausers need to see.
+                _ => match value.as_str() {
+                    "{" => return NodeASTType::OpeningBracket,
+                    "}" => return NodeASTType::ClosingBracket,
+                    _ => {}
+                },
+            }
+        }
+
+        if value == "@" {
+            return NodeASTType::Decorator;
+        }
+        if let Some(node_type) = self.detect_class_member_type(&value) {
+            return node_type;
+        }
+        if let Some(node_type) = self.detect_enum_type(&value) {
+            return node_type;
+        }
+        if self.is_parameter_property_modifier(&value) {
+            return NodeASTType::KeywordModifier;
+        }
+
+        if self.const_enum_reference().is_some() {
+            return NodeASTType::ConstEnumReference;
+        }
+
+        // `{ class: "a" }` or `a.export`, where even a keyword is just the name
+        // of a property.
+        let is_key = self.context_type() == NodeASTType::ObjectOpeningBracket
+            && matches!(self.last_written.as_str(), "{" | ",")
+            && next_token(&self.tokens, self.position + 1) == ":";
+        let is_member = matches!(self.last_written.as_str(), "." | "?.");
+        if (is_key || is_member) && lookahead::is_identifier(&value) {
+            return NodeASTType::Identifier;
+        }
+
         match value.as_str() {
-            "declare" => NodeASTType::KeywordDeclare,
+            "declare" if lookahead::is_identifier(next_token(&self.tokens, self.position + 1)) => {
+                NodeASTType::KeywordDeclare
+            }
+            "export" | "async" | "function"
+                if self.last_written != "."
+                    && function_signature_end(&self.tokens, self.position).is_some() =>
+            {
+                NodeASTType::FunctionSignature
+            }
+            "this"
+                if self.context_type() == NodeASTType::FunctionOpeningParenthesis
+                    && self.last_written == "("
+                    && next_token(&self.tokens, self.position + 1) == ":" =>
+            {
+                NodeASTType::ThisParameter
+            }
+            "export" | "const" if self.is_const_enum_declaration() => {
+                NodeASTType::ConstEnumDeclaration
+            }
+            "export" if self.decorated_class_export_end().is_some() => {
+                NodeASTType::DecoratedClassExport
+            }
+            "export" | "import"
+                if self.last_written != "."
+                    && parse_import_equals(&self.tokens, self.position).is_some() =>
+            {
+                NodeASTType::ImportEquals
+            }
+            "export" if next_token(&self.tokens, self.position + 1) == "=" => {
+                NodeASTType::ExportAssignment
+            }
+            "export" if self.is_namespace_body() => NodeASTType::NamespaceExport,
+            "export" if self.type_only_export_end().is_some() => NodeASTType::TypeOnlyExport,
+            "export" if parse_export_list(&self.tokens, self.position).is_some() => {
+                NodeASTType::ExportList
+            }
+            "export" if parse_export_all(&self.tokens, self.position).is_some() => {
+                NodeASTType::ExportAll
+            }
+            "export" if self.is_commonjs() => NodeASTType::CommonJsExport,
             "export" => NodeASTType::ExportDeclaration,
+            "namespace" | "module" if self.last_written != "." => {
+                match namespace_body(&self.tokens, self.position) {
+                    Some(body) if is_type_only_namespace(&self.tokens, body) => {
+                        NodeASTType::TypeOnlyNamespace
+                    }
+                    Some(_) => NodeASTType::KeywordNamespace,
+                    None => NodeASTType::Identifier,
+                }
+            }
+            "." if self.last_written_type == NodeASTType::NamespaceDeclaration => {
+                NodeASTType::NamespaceNameSeperator
+            }
+            "import"
+                if self.last_written != "."
+                    && parse_import(&self.tokens, self.position).is_some() =>
+            {
+                NodeASTType::ImportDeclaration
+            }
+            "import" if self.is_commonjs() && self.last_written != "." => {
+                let next = next_index(&self.tokens, self.position + 1);
+                match (
+                    next_token(&self.tokens, next),
+                    next_token(&self.tokens, next + 1),
+                ) {
+                    ("(", _) => NodeASTType::DynamicImport,
+                    (".", "meta") => NodeASTType::ImportMeta,
+                    _ => NodeASTType::Identifier,
+                }
+            }
             "const" => NodeASTType::VariableDeclarator,
             "let" => NodeASTType::VariableDeclarator,
             "var" => NodeASTType::VariableDeclarator,
             "function" => NodeASTType::KeywordFunction,
-            "as" => NodeASTType::KeywordAs,
+            "as" if self.is_type_operator() => NodeASTType::KeywordAs,
+            "satisfies" if self.is_type_operator() => NodeASTType::KeywordSatisfies,
             "interface" => NodeASTType::KeywordInterface,
-            "type" => NodeASTType::KeywordType,
-            "{" => NodeASTType::OpeningBracket,
+            "type" if self.is_type_alias() => NodeASTType::KeywordType,
+            "case" => NodeASTType::KeywordCase,
+            "enum" if self.is_enum_declaration() => NodeASTType::KeywordEnum,
+            "class" if self.is_class_keyword() => NodeASTType::KeywordClass,
+            "abstract" if next_token(&self.tokens, self.position + 1) == "class" => {
+                NodeASTType::KeywordModifier
+            }
+            "implements" if self.is_class_heritage() => NodeASTType::KeywordImplements,
+            "<" if self.is_class_heritage() => match self.last_written_type {
+                NodeASTType::ClassDeclaration => NodeASTType::TypeParameters,
+                _ => NodeASTType::TypeArguments,
+            },
+            "<" if self.last_written_type == NodeASTType::FunctionDeclaration
+                || (self.is_class_body() && self.context().binding == BindingState::Named) =>
+            {
+                NodeASTType::TypeParameters
+            }
+            // `async <T,>(a: T) => a`, which `async<T>(a)` would call otherwise.
+            "<" if self.last_written == "async" && self.is_async_arrow_type_parameters() => {
+                NodeASTType::TypeParameters
+            }
+            // `<T>value`, or the type parameters of `<T>(a: T) => a`.
+            "<" if self.is_type_assertion() => NodeASTType::TypeAssertion,
+            "<" if self.is_type_arguments() => NodeASTType::TypeArguments,
+            "{" if self.last_written_type == NodeASTType::EnumDeclaration => {
+                NodeASTType::EnumOpeningBracket
+            }
+            // The name was written last, rather than `export const` of a
+            // pattern like `export const { a } = b` inside the namespace.
+            "{" if self
+                .nodes
+                .last()
+                .is_some_and(|node| node.node_type == NodeASTType::NamespaceDeclaration) =>
+            {
+                NodeASTType::NamespaceOpeningBracket
+            }
+            "{" => {
+                if self.is_class_heritage() {
+                    return NodeASTType::ClassOpeningBracket;
+                }
+                if self.last_written != "=>" && self.expects_expression() {
+                    return NodeASTType::ObjectOpeningBracket;
+                }
+                return NodeASTType::OpeningBracket;
+            }
+            "," if self.is_enum_body() => NodeASTType::EnumMemberSeperator,
             "," => NodeASTType::CommaSeperator,
-            "}" => NodeASTType::ClosingBracket,
-            "(" => match self.last_node().node_type {
-                NodeASTType::FunctionDeclaration | NodeASTType::FunctionIdentifier => {
+            "}" => match self.context_type() {
+                NodeASTType::EnumOpeningBracket => NodeASTType::EnumClosingBracket,
+                NodeASTType::NamespaceOpeningBracket => NodeASTType::NamespaceClosingBracket,
+                NodeASTType::ObjectOpeningBracket => NodeASTType::ObjectClosingBracket,
+                NodeASTType::ClassOpeningBracket => NodeASTType::ClassClosingBracket,
+                _ => NodeASTType::ClosingBracket,
+            },
+            "(" => match self.last_written_type {
+                NodeASTType::FunctionDeclaration | NodeASTType::KeywordFunction => {
+                    NodeASTType::FunctionOpeningParenthesis
+                }
+                // The parameters of a method
+                _ if self.is_class_body() && self.context().binding == BindingState::Named => {
+                    NodeASTType::FunctionOpeningParenthesis
+                }
+                _ if self.is_arrow_parameters() || self.is_object_method_parameters() => {
                     NodeASTType::FunctionOpeningParenthesis
                 }
                 _ => NodeASTType::OpeningParenthesis,
             },
-            ")" => NodeASTType::ClosingParenthesis,
+            "?" if self.is_class_body() && self.context().binding == BindingState::Named => {
+                NodeASTType::OptionalMarker
+            }
+            // `(a?: T)`, which can't be followed by a default value.
+            "?" if self.context_type() == NodeASTType::FunctionOpeningParenthesis
+                && self.context().binding == BindingState::Named
+                && matches!(next_token(&self.tokens, self.position + 1), ":" | "," | ")") =>
+            {
+                NodeASTType::OptionalMarker
+            }
+            "?" => NodeASTType::TernaryOperator,
+            "=>" => NodeASTType::ArrowFunction,
+            ")" => match self.context_type() {
+                NodeASTType::FunctionOpeningParenthesis => NodeASTType::FunctionClosingParenthesis,
+                _ => NodeASTType::ClosingParenthesis,
+            },
             "[" => NodeASTType::OpeningBrace,
             "||" => NodeASTType::OrStatement,
             "|" => NodeASTType::TypeUnionSeperator,
             "]" => NodeASTType::ClosingBrace,
             ";" => NodeASTType::Terminator,
-            "//" => NodeASTType::CommentLine,
-            "/*" => NodeASTType::CommentMultilineOpener,
-            "*/" => NodeASTType::CommentMultilineCloser,
-            "'" => NodeASTType::SingleQuote,
-            "\"" => NodeASTType::DoubleQuote,
-            ":" => {
-                let parent = self.last_node();
-                let parent_type = parent.node_type;
-                if parent_type == NodeASTType::VariableDeclaration {
-                    return NodeASTType::VariableTypeSeperator;
-                }
-
-                if parent_type == NodeASTType::FunctionIdentifier {
-                    return NodeASTType::FunctionIdentifierTypeSeperator;
-                }
-                if parent_type == NodeASTType::Identifier {
-                    let parent = self.last_node();
-                    let grandparent = parent.get_parent();
-                    let grandparent_type = grandparent.node_type;
-                    if grandparent_type == NodeASTType::OpeningParenthesis {
-                        return NodeASTType::VariableTypeSeperator;
-                    }
-                }
-                if parent_type == NodeASTType::ClosingParenthesis {
-                    return NodeASTType::VariableTypeSeperator;
-                }
-
-                return NodeASTType::Unknown;
+            "!" if self.context().binding == BindingState::Named
+                && next_token(&self.tokens, self.position + 1) == ":" =>
+            {
+                NodeASTType::DefiniteAssignmentAssertion
             }
+            "!" if self.is_non_null_assertion() => NodeASTType::NonNullAssertion,
+            ":" => self.detect_colon_type(),
             "\n" => NodeASTType::EOL,
             "" => NodeASTType::WhiteSpace,
             "=" => {
@@ -172,51 +2535,220 @@ impl Tree {
                 }
                 return NodeASTType::AssignmentOperator;
             }
-            _ => {
+            _ if value.starts_with('\'') => NodeASTType::SingleQuote,
+            _ if value.starts_with('"') => NodeASTType::DoubleQuote,
+            _ if value.starts_with('`') || lookahead::is_template_tail(&value) => {
+                NodeASTType::BacktickQuote
+            }
+            _ if value.starts_with(is_identifier_char) || value.starts_with('#') => {
                 let parent_type = self.last_node().node_type;
                 match parent_type {
-                    NodeASTType::FunctionOpeningParenthesis => NodeASTType::FunctionIdentifier,
                     NodeASTType::KeywordFunction => NodeASTType::FunctionDeclaration,
-                    NodeASTType::VariableTypeSeperator => NodeASTType::TypeAnnotation,
+                    NodeASTType::KeywordClass => NodeASTType::ClassDeclaration,
+                    NodeASTType::KeywordEnum => NodeASTType::EnumDeclaration,
+                    NodeASTType::KeywordNamespace | NodeASTType::NamespaceNameSeperator => {
+                        NodeASTType::NamespaceDeclaration
+                    }
                     NodeASTType::VariableDeclarator => NodeASTType::VariableDeclaration,
-                    NodeASTType::FunctionIdentifierTypeSeperator => {
-                        NodeASTType::FunctionIdentifierType
+                    _ if self.context().binding == BindingState::ExpectingName => {
+                        if self.context_type() == NodeASTType::FunctionOpeningParenthesis {
+                            NodeASTType::FunctionIdentifier
+                        } else {
+                            NodeASTType::VariableDeclaration
+                        }
+                    }
+                    _ if self.namespace_of_variable(&value).is_some() => {
+                        NodeASTType::NamespaceMemberReference
+                    }
+                    _ if self.is_import_binding_reference(&value) => {
+                        NodeASTType::ImportBindingReference
                     }
                     _ => NodeASTType::Identifier,
                 }
             }
+            _ => NodeASTType::Unknown,
         }
     }
 
-    pub fn derive_parent(&self, node_type: NodeASTType) -> Node {
-        match node_type {
-            NodeASTType::FunctionOpeningParenthesis => {
-                let parent = self.last_node();
-                let grand_parent = self
-                    .nodes
-                    .get(self.nodes.len() - 2)
-                    .expect("should have another parent");
+    // Keeps track of brackets and of where we are inside a binding. This only
+    // runs for tokens that are written, everything inside a paused node is
+    // invisible to the contexts. It runs before the token is written, so
+    // `last_written` is still the token before it.
+    fn update_context(&mut self, node_type: NodeASTType) {
+        let value = self.current_token.as_str();
+        let opener = self.nodes.len();
+        let binding = self.context().binding;
 
-                if parent.node_type == NodeASTType::KeywordFunction {
-                    return parent;
+        match node_type {
+            NodeASTType::VariableDeclarator => {
+                self.is_exporting_variables = self.is_namespace_body()
+                    && self.last_node().node_type == NodeASTType::NamespaceExport;
+                if self.is_exporting_variables {
+                    self.export_namespace_patterns();
                 }
-                if grand_parent.node_type == NodeASTType::KeywordFunction {
-                    return grand_parent.clone();
+                self.context_mut().binding = BindingState::ExpectingName
+            }
+            NodeASTType::FunctionDeclaration | NodeASTType::ClassDeclaration
+                if self.exporting_namespace().is_some() =>
+            {
+                self.pending_namespace_export = Some(value.to_string());
+            }
+            NodeASTType::NamespaceOpeningBracket => {
+                for namespace in self.namespaces.iter_mut().filter(|n| n.opener.is_none()) {
+                    namespace.opener = Some(opener);
                 }
-                return MODULE_NODE;
+                self.contexts.push(Context::new(opener, BindingState::None));
             }
-            NodeASTType::FunctionIdentifier => match self.last_node().parent {
-                Some(p) => Node::from(p),
-                None => self.last_node(),
-            },
-            NodeASTType::FunctionIdentifierTypeSeperator => {
-                Node::from(self.last_node().parent.expect("parent will exist here"))
+            NodeASTType::VariableDeclaration
+            | NodeASTType::FunctionIdentifier
+            | NodeASTType::ClassMemberName
+            | NodeASTType::EnumMember => {
+                if node_type == NodeASTType::FunctionIdentifier
+                    && self.last_node().node_type == NodeASTType::KeywordModifier
+                {
+                    self.parameter_properties.push(value.to_string());
+                }
+                if node_type == NodeASTType::VariableDeclaration
+                    && self.is_exporting_variables
+                    && self.is_namespace_body()
+                {
+                    let namespace = self.namespaces.last_mut().expect("Inside a namespace");
+                    namespace.exported_variables.push(value.to_string());
+                }
+                if binding == BindingState::ExpectingName {
+                    self.context_mut().binding = BindingState::Named;
+                }
+            }
+            NodeASTType::AssignmentOperator | NodeASTType::EnumMemberInitializer
+                if binding == BindingState::Named =>
+            {
+                self.context_mut().binding = BindingState::Initializer;
+            }
+            NodeASTType::CommaSeperator | NodeASTType::EnumMemberSeperator
+                if binding == BindingState::Named || binding == BindingState::Initializer =>
+            {
+                self.context_mut().binding = BindingState::ExpectingName;
+            }
+            NodeASTType::Terminator => self.context_mut().binding = self.next_statement_binding(),
+            NodeASTType::TernaryOperator => self.context_mut().pending_ternaries += 1,
+            NodeASTType::TernaryElseSeperator => self.context_mut().pending_ternaries -= 1,
+            NodeASTType::KeywordCase => self.context_mut().is_pending_case = true,
+            NodeASTType::CaseSeperator => self.context_mut().is_pending_case = false,
+            NodeASTType::KeywordFunction => {
+                self.is_expression_body = self.expects_expression();
+            }
+            NodeASTType::KeywordClass => {
+                self.is_expression_body = self.expects_expression();
+                self.class_heritage_depth = Some(self.contexts.len());
+            }
+            NodeASTType::ClassOpeningBracket => {
+                let mut context = Context::new(opener, BindingState::ExpectingName);
+                context.ends_expression = self.is_expression_body;
+                self.is_expression_body = false;
+                self.class_heritage_depth = None;
+                self.contexts.push(context);
+            }
+            NodeASTType::EnumOpeningBracket => self
+                .contexts
+                .push(Context::new(opener, BindingState::ExpectingName)),
+            NodeASTType::FunctionOpeningParenthesis => self
+                .contexts
+                .push(Context::new(opener, BindingState::ExpectingName)),
+            NodeASTType::OpeningParenthesis | NodeASTType::OpeningBrace => {
+                self.contexts.push(Context::new(opener, BindingState::None))
+            }
+            NodeASTType::ObjectOpeningBracket | NodeASTType::OpeningBracket => {
+                if !self.parameter_properties.is_empty()
+                    && self.last_written_type == NodeASTType::FunctionClosingParenthesis
+                {
+                    self.parameter_properties_position = Some(self.parameter_properties_position());
+                }
+                let mut context = Context::new(opener, BindingState::None);
+                context.ends_expression = node_type == NodeASTType::ObjectOpeningBracket
+                    || self.last_written == "=>"
+                    || self.is_expression_body;
+                self.is_expression_body = false;
+                self.contexts.push(context);
+            }
+            NodeASTType::ClosingParenthesis
+            | NodeASTType::FunctionClosingParenthesis
+            | NodeASTType::ClosingBracket
+            | NodeASTType::ObjectClosingBracket
+            | NodeASTType::ClassClosingBracket
+            | NodeASTType::EnumClosingBracket
+            | NodeASTType::NamespaceClosingBracket
+            | NodeASTType::ClosingBrace => self.close_context(),
+            NodeASTType::BacktickQuote => {
+                if lookahead::is_template_tail(value) {
+                    self.close_context();
+                } else if lookahead::is_template_head(value) {
+                    self.contexts.push(Context::new(opener, BindingState::None));
+                }
             }
-            NodeASTType::FunctionIdentifierType => {
-                Node::from(self.last_node().parent.expect("parent will exist here"))
+            _ => {}
+        }
+    }
+
+    fn close_context(&mut self) {
+        if self.contexts.len() > 1 {
+            let context = self.contexts.pop().expect("Checked above");
+            self.has_closed_expression = context.ends_expression;
+            // The end of a method body is the start of the next class member.
+            if self.is_class_body()
+                && self.context().binding == BindingState::Named
+                && self.nodes[context.opener].node_type == NodeASTType::OpeningBracket
+            {
+                self.context_mut().binding = BindingState::ExpectingName;
+                return;
             }
-            _ => self.last_node(),
         }
+        // A destructuring pattern like `{ a, b }` is the name of its binding.
+        if self.context().binding == BindingState::ExpectingName {
+            self.context_mut().binding = BindingState::Named;
+        }
+    }
+
+    // Statements start without a binding, class members start expecting a name.
+    fn next_statement_binding(&self) -> BindingState {
+        if self.is_class_body() {
+            return BindingState::ExpectingName;
+        }
+        return BindingState::None;
+    }
+
+    // Without semicolons a line break can end a declaration, in which case
+    // a following `label:` must not be mistaken for a type annotation.
+    fn consider_ending_binding_at_line_break(&mut self) {
+        let binding = self.context().binding;
+        if binding != BindingState::Named && binding != BindingState::Initializer {
+            return;
+        }
+        if matches!(
+            self.context_type(),
+            NodeASTType::FunctionOpeningParenthesis | NodeASTType::EnumOpeningBracket
+        ) {
+            return;
+        }
+        let previous = self.last_node().value.clone();
+        let next = next_token(&self.tokens, self.position + 1);
+        let is_continued = matches!(next, "in" | "instanceof" | "as" | "satisfies" | "of");
+        if can_end_expression(&previous)
+            && (next.starts_with(is_identifier_char) || next.starts_with('#'))
+            && !is_continued
+        {
+            self.context_mut().binding = self.next_statement_binding();
+        }
+    }
+
+    fn write(&mut self, value: &str) {
+        // A line break after a statement block (`function a() {}`) never changes
+        // its meaning, but one after `const a = {}` might.
+        let is_after_block = self.last_written == "}" && !self.has_closed_expression;
+        let has_line_break = self.has_line_break && !is_after_block;
+        self.output += seperator(&self.last_written, value, has_line_break);
+        self.output += value;
+        self.last_written = value.to_string();
+        self.has_line_break = false;
     }
 
     pub fn commit(&mut self) {
@@ -224,52 +2756,178 @@ impl Tree {
         let value = self.current_token.clone();
         let node_type = self.detect_type(value);
 
-        if self.is_paused == false {
+        if node_type == NodeASTType::EOL {
+            self.has_line_break = true;
+            if !self.is_paused {
+                self.consider_ending_binding_at_line_break();
+            }
+        }
+
+        self.start_assigned_field();
+
+        if !self.is_paused {
             match node_type {
-                NodeASTType::KeywordDeclare
-                | NodeASTType::KeywordType
-                | NodeASTType::KeywordInterface
-                | NodeASTType::CommentLine
-                | NodeASTType::CommentMultilineOpener
-                | NodeASTType::KeywordAs => self.pause_writing(node_type),
+                NodeASTType::KeywordInterface => {
+                    self.record_type_declaration(node_type);
+                    self.pause_writing(node_type)
+                }
+                _ if is_measured_ahead(node_type) => {
+                    for value in self.replacement(node_type) {
+                        self.write(&value);
+                    }
+                    if matches!(
+                        node_type,
+                        NodeASTType::ImportDeclaration | NodeASTType::ExportList
+                    ) {
+                        self.write_module_declaration(node_type);
+                    }
+                    match node_type {
+                        NodeASTType::Decorator => self.record_decorator(),
+                        NodeASTType::CommonJsExport => self.write_commonjs_export(),
+                        NodeASTType::ExportAll => self.write_export_all(),
+                        NodeASTType::ImportEquals => self.write_import_equals(),
+                        NodeASTType::DynamicImport => self.write_dynamic_import(),
+                        NodeASTType::ExportAssignment => match self.is_commonjs() {
+                            true => {
+                                self.write("module.exports");
+                                self.write("=");
+                            }
+                            false => {
+                                self.write("export");
+                                self.write("default");
+                            }
+                        },
+                        NodeASTType::DecoratedClassExport => {
+                            let end = self.measured_end(node_type);
+                            let is_default = self.tokens[self.position..end]
+                                .iter()
+                                .any(|token| token == "default");
+                            self.decorated_class_export = Some(is_default);
+                        }
+                        _ => {}
+                    }
+                    if matches!(
+                        node_type,
+                        NodeASTType::KeywordType
+                            | NodeASTType::TypeOnlyNamespace
+                            | NodeASTType::TypeOnlyExport
+                    ) {
+                        self.record_type_declaration(node_type);
+                    }
+                    let end = self.measured_end(node_type);
+                    self.lookahead_pauser.skip(end - self.position);
+                    self.pause_writing(node_type);
+                }
                 _ => {}
             }
 
-            if self.is_paused == false {
+            if !self.is_paused {
+                self.update_context(node_type);
                 match node_type {
                     NodeASTType::WhiteSpace => {}
                     NodeASTType::EOL => {}
-                    NodeASTType::TypeAnnotation => {}
-                    NodeASTType::KeywordInterface => {}
-                    NodeASTType::KeywordType => {}
-                    NodeASTType::KeywordDeclare => {}
-                    NodeASTType::VariableTypeSeperator => {}
+                    NodeASTType::DefiniteAssignmentAssertion => {}
+                    NodeASTType::NonNullAssertion => {}
+                    NodeASTType::KeywordModifier => {}
+                    NodeASTType::OptionalMarker => {}
+                    NodeASTType::KeywordNamespace => {}
+                    NodeASTType::NamespaceNameSeperator => {}
+                    NodeASTType::NamespaceOpeningBracket => {}
+                    NodeASTType::NamespaceExport => {}
+                    NodeASTType::VariableDeclarator if self.is_exporting_variables => {}
+                    NodeASTType::VariableDeclaration
+                        if self.is_exporting_variables && self.is_namespace_body() =>
+                    {
+                        let namespace = &self.namespaces.last().expect("Inside a namespace").name;
+                        let name = format!("{}.{}", namespace, self.current_token);
+                        self.write(&name);
+                        self.last_written_type = node_type;
+                    }
+                    NodeASTType::NamespaceDeclaration
+                    | NodeASTType::NamespaceClosingBracket
+                    | NodeASTType::NamespaceMemberReference => {
+                        self.write_namespace(node_type);
+                        self.last_written_type = node_type;
+                    }
+                    NodeASTType::KeywordEnum
+                    | NodeASTType::EnumDeclaration
+                    | NodeASTType::EnumOpeningBracket
+                    | NodeASTType::EnumClosingBracket
+                    | NodeASTType::EnumMember
+                    | NodeASTType::EnumMemberInitializer
+                    | NodeASTType::EnumMemberSeperator
+                    | NodeASTType::EnumMemberReference => {
+                        self.write_enum(node_type);
+                        self.last_written_type = node_type;
+                    }
+                    NodeASTType::ImportBindingReference => {
+                        self.write_import_binding_reference();
+                        self.last_written_type = node_type;
+                    }
+                    NodeASTType::KeywordClass => {
+                        self.start_decorated_class();
+                        self.write("class");
+                        self.last_written_type = node_type;
+                    }
                     _ => {
-                        self.output += self.current_token.as_str();
-                        self.output += seperator(node_type);
+                        let value = match self.rewrites.remove(&self.position) {
+                            Some(value) => value,
+                            None => self.current_token.clone(),
+                        };
+                        // Names of members and keys aren't references to a binding.
+                        let is_reference = lookahead::is_identifier(&value)
+                            && !matches!(self.last_written.as_str(), "." | "?.")
+                            && !self.is_property_key()
+                            && node_type != NodeASTType::ClassMemberName;
+                        if !value.is_empty() {
+                            self.write(value.as_str());
+                        }
+                        self.last_written_type = node_type;
+                        if is_reference {
+                            self.value_references.insert(value);
+                        }
+                    }
+                }
+                if self.parameter_properties_position == Some(self.position) {
+                    self.write_parameter_properties();
+                }
+                if node_type == NodeASTType::ClassClosingBracket {
+                    self.write_field_assignments();
+                    self.write_decorations();
+                }
+                let is_closing_body = matches!(
+                    node_type,
+                    NodeASTType::ClosingBracket | NodeASTType::ClassClosingBracket
+                );
+                if is_closing_body && self.is_namespace_body() {
+                    if let Some(name) = self.pending_namespace_export.take() {
+                        let namespace = self.namespaces.last_mut().expect("Inside a namespace");
+                        namespace.exported_declarations.push(name.clone());
+                        let assignment = format!("{}.{}={}", namespace.name, name, name);
+                        self.write(&assignment);
+                        self.write(";");
                     }
                 }
             }
         }
 
+        self.write_insertions();
+        self.end_assigned_field();
+
         // If the node was just white space we dont want to keep it in our list
         // of nodes to print
-        if self.current_token.trim() == "" {
-            self.current_token = String::new();
-        } else {
+        if self.current_token.trim() != "" {
             // commit and
             // reset the current node tree for the next characters
-            let parent = self.derive_parent(node_type);
-            let token = self.current_token.clone();
-            let node_type = self.detect_type(token.clone());
             let new_node = Node {
-                parent: Some(Box::new(parent)),
-                value: token,
+                parent: Some(self.context().opener),
+                value: self.current_token.clone(),
                 node_type: node_type,
             };
-            self.current_token = String::new();
             self.nodes.push(new_node);
         }
+        self.current_token = String::new();
+        self.position += 1;
 
         self.consider_resuming_writing(node_type);
     }
@@ -277,188 +2935,171 @@ impl Tree {
 
 #[derive(Clone)]
 struct Node {
-    parent: Option<Box<Node>>,
+    // Index of the node that opened the context this node lives in.
+    parent: Option<usize>,
     value: String,
     node_type: NodeASTType,
 }
 
-fn seperator(node_type: NodeASTType) -> &'static str {
-    match node_type {
-        NodeASTType::KeywordFunction => " ",
-        NodeASTType::ExportDeclaration => " ",
-        NodeASTType::VariableDeclarator => " ",
-        _ => "",
+// Members initialized with a string don't get a reverse mapping, which is the
+// case for string literals and for concatenations of them.
+fn is_string_initializer(initializer: &[&str], string_members: &[String]) -> bool {
+    return initializer
+        .iter()
+        .enumerate()
+        .all(|(index, token)| match index % 2 {
+            0 => {
+                let first = token.chars().next().unwrap_or(' ');
+                first == '\''
+                    || first == '"'
+                    || (first == '`' && token.len() > 1 && !token.ends_with("${"))
+                    || string_members.iter().any(|member| member == token)
+            }
+            _ => *token == "+",
+        });
+}
+
+// The value of `1`, `-1` or `0x10`, so the next member can continue from it.
+fn numeric_initializer(initializer: &[&str]) -> Option<i64> {
+    let (sign, literal) = match initializer {
+        [literal] => (1, *literal),
+        ["-", literal] => (-1, *literal),
+        _ => return None,
+    };
+    let literal = literal.to_lowercase();
+    let value = match literal.get(0..2) {
+        Some("0x") => i64::from_str_radix(&literal[2..], 16).ok(),
+        Some("0o") => i64::from_str_radix(&literal[2..], 8).ok(),
+        Some("0b") => i64::from_str_radix(&literal[2..], 2).ok(),
+        _ => literal.parse::<i64>().ok(),
+    };
+    return value.map(|value| value * sign);
+}
+
+// Nodes that pause writing for a span measured by `Tree::measured_end`.
+fn is_measured_ahead(node_type: NodeASTType) -> bool {
+    return matches!(
+        node_type,
+        NodeASTType::KeywordType
+            | NodeASTType::KeywordDeclare
+            | NodeASTType::TypeOnlyNamespace
+            | NodeASTType::KeywordImplements
+            | NodeASTType::ClassMemberSignature
+            | NodeASTType::FunctionSignature
+            | NodeASTType::ThisParameter
+            | NodeASTType::Decorator
+            | NodeASTType::DecoratedClassExport
+            | NodeASTType::TypeParameters
+            | NodeASTType::TypeArguments
+            | NodeASTType::TypeAssertion
+            | NodeASTType::KeywordAs
+            | NodeASTType::KeywordSatisfies
+            | NodeASTType::VariableTypeSeperator
+            | NodeASTType::FunctionIdentifierTypeSeperator
+            | NodeASTType::FunctionReturnTypeSeperator
+            | NodeASTType::ConstEnumDeclaration
+            | NodeASTType::ConstEnumReference
+            | NodeASTType::ImportDeclaration
+            | NodeASTType::ExportList
+            | NodeASTType::TypeOnlyExport
+            | NodeASTType::ExportAll
+            | NodeASTType::CommonJsExport
+            | NodeASTType::ImportEquals
+            | NodeASTType::ExportAssignment
+            | NodeASTType::DynamicImport
+            | NodeASTType::ImportMeta
+    );
+}
+
+fn can_end_expression(token: &str) -> bool {
+    let last = token.chars().last().unwrap_or(' ');
+    return is_identifier_char(last)
+        || matches!(last, ')' | ']' | '}' | '\'' | '"' | '`')
+        || token == "++"
+        || token == "--";
+}
+
+fn can_start_expression(token: &str) -> bool {
+    let first = token.chars().next().unwrap_or(' ');
+    return is_identifier_char(first)
+        || matches!(
+            first,
+            '(' | '[' | '\'' | '"' | '`' | '+' | '-' | '!' | '~' | '#' | '@' | '/'
+        );
+}
+
+// Everything is written without whitespace, so this decides when two tokens
+// would run into each other. A line break is kept when the tokens around it
+// could otherwise be joined into one statement.
+fn join_tokens(tokens: &[String]) -> String {
+    let mut output = String::new();
+    let mut previous = "";
+    for token in tokens {
+        output += seperator(previous, token, false);
+        output += token;
+        previous = token;
     }
+    return output;
 }
 
-impl Node {
-    fn from(node: Box<Node>) -> Node {
-        Node {
-            value: node.value,
-            parent: node.parent,
-            node_type: node.node_type,
-        }
+fn seperator(previous: &str, next: &str, has_line_break: bool) -> &'static str {
+    let (last, first) = match (previous.chars().last(), next.chars().next()) {
+        (Some(last), Some(first)) => (last, first),
+        _ => return "",
+    };
+
+    if has_line_break && can_end_expression(previous) && can_start_expression(next) {
+        return "\n";
     }
-    fn get_parent(&self) -> Node {
-        match &self.parent {
-            Some(parent) => {
-                let parent_type = parent.node_type;
-                if parent_type == NodeASTType::WhiteSpace || parent_type == NodeASTType::EOL {
-                    return parent.get_parent();
-                }
 
-                return Node {
-                    value: parent.value.clone(),
-                    parent: Some(Box::new(parent.get_parent())),
-                    node_type: parent.node_type,
-                };
-            }
-            None => MODULE_NODE,
-        }
+    let is_integer = previous.chars().all(|char| char.is_ascii_digit());
+    if (is_identifier_char(last) && is_identifier_char(first))
+        || (last == '+' && first == '+')
+        || (last == '-' && first == '-')
+        || (last == '/' && (first == '/' || first == '*'))
+        || (is_integer && first == '.')
+    {
+        return " ";
     }
+
+    ""
 }
 
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let parent_type = match self.parent.clone() {
-            Some(p) => p.node_type,
-            None => NodeASTType::Unknown,
-        };
         write!(
             f,
-            "({}: {}, seperator: \"{}\", parent: {})",
+            "({}: {}, parent: {})",
             self.node_type,
             self.value,
-            seperator(self.node_type),
-            parent_type
+            self.parent.map_or(String::from("none"), |p| p.to_string())
         )
     }
 }
 
 /// This should parse
-pub fn parser(contents: String) -> String {
-    let mut tree = Tree::new();
-
-    for char in contents.chars() {
-        let parent = tree.last_node();
-        match parent.node_type {
-            NodeASTType::SingleQuote => {
-                let mut value = tree.current_token.clone();
-                value.push(char);
-
-                tree.update_current_value(value.as_str());
-
-                if char == '\'' {
-                    tree.commit();
-                }
-                continue;
-            }
-            NodeASTType::DoubleQuote => {
-                let mut value = tree.current_token.clone();
-                value.push(char);
-
-                tree.update_current_value(value.as_str());
-
-                if char == '"' {
-                    tree.commit();
-                }
-                continue;
-            }
-            _ => {}
-        };
-
-        match char {
-            ' ' => {
-                // end of previous node, commit this one.
-                tree.commit();
-            }
-            '\n' => {
-                // end of previous node, commit this one.
-                tree.commit();
-                tree.update_current_value("\n");
-                tree.commit();
-            }
-            '\'' => {
-                tree.commit();
-                tree.update_current_value("'");
-                tree.commit();
-            }
-            '"' => {
-                tree.commit();
-                tree.update_current_value("\"");
-                tree.commit();
-            }
-            '{' => {
-                tree.commit();
-                tree.update_current_value("{");
-                tree.commit();
-            }
-            '}' => {
-                tree.commit();
-                tree.update_current_value("}");
-                tree.commit();
-            }
-            '.' => {
-                // end of previous node, commit this one.
-                tree.commit();
-                tree.update_current_value(".");
-                tree.commit();
-            }
-            ':' => {
-                // start of annotation node.
-                // commit previous node.
-                tree.commit();
-                tree.update_current_value(":");
-                // tree.commit();
-            }
-            ';' => {
-                // end of previous node, commit this one.
-                tree.commit();
-                tree.update_current_value(";");
-                tree.commit();
-            }
-            '[' => {
-                tree.commit();
-                tree.update_current_value("[");
-                tree.commit();
-            }
-            ']' => {
-                tree.commit();
-                tree.update_current_value("]");
-                tree.commit();
-            }
-            '(' => {
-                tree.commit();
-                tree.update_current_value("(");
-                tree.commit();
-            }
-            ')' => {
-                tree.commit();
-                tree.update_current_value(")");
-                tree.commit();
-            }
-            _ => {
-                let mut value = tree.current_token.clone();
-                value.push(char);
-
-                tree.update_current_value(value.as_str());
+pub fn parser(contents: String, module_path: &Path, options: &Options) -> String {
+    let is_jsx = module_path
+        .extension()
+        .is_some_and(|extension| extension == "tsx" || extension == "jsx");
+    let tokens = match is_jsx {
+        true => lexer::tokenize_jsx(&contents, JsxFactory::new(&contents, options)),
+        false => lexer::tokenize(&contents),
+    };
+    let mut tree = Tree::new(tokens, options.clone());
+    tree.rewrite_aliases(module_path);
+    tree.collect_const_enums(module_path);
+    if tree.is_commonjs() {
+        tree.collect_import_bindings();
+    }
 
-                // Have to handle this here because rust doesnt like me creating a string with
-                // single quotes with // in it.
-                if tree.current_token == "//".to_string() {
-                    tree.commit();
-                }
-                if tree.current_token == "/*".to_string() {
-                    tree.commit();
-                }
-                if tree.current_token == "*/".to_string() {
-                    tree.commit();
-                }
-            }
-        }
+    for index in 0..tree.tokens.len() {
+        let token = tree.tokens[index].clone();
+        tree.update_current_value(token.as_str());
+        tree.commit();
     }
 
-    tree.debug();
+    tree.finish();
 
     return tree.output;
 }
@@ -466,6 +3107,10 @@ pub fn parser(contents: String) -> String {
 #[cfg(test)]
 #[test]
 fn it_works() {
-    let result = parser(String::from("const A: boolean = true;"));
+    let result = parser(
+        String::from("const A: boolean = true;"),
+        Path::new("test.ts"),
+        &Options::default(),
+    );
     assert_eq!(result, String::from(""));
 }
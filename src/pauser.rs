@@ -16,180 +16,43 @@ impl Pauser for NotPauser {
     }
 }
 
-pub struct CommentPauser {
-    is_singline: bool,
-    is_multiline: bool,
-}
-impl CommentPauser {
-    pub fn new() -> CommentPauser {
-        CommentPauser {
-            is_singline: false,
-            is_multiline: false,
-        }
-    }
-
-    fn reset(&mut self) {
-        self.is_singline = false;
-        self.is_multiline = false;
-    }
-}
-impl Pauser for CommentPauser {
-    fn is_paused_after_evaluating(&mut self, next_type: NodeASTType) -> bool {
-        let is_paused = match next_type {
-            NodeASTType::CommentLine => {
-                self.is_singline = true;
-                true
-            }
-            NodeASTType::CommentMultilineOpener => {
-                self.is_multiline = true;
-                true
-            }
-            _ => {
-                if self.is_singline {
-                    return next_type != NodeASTType::EOL;
-                } else if self.is_multiline {
-                    return next_type != NodeASTType::CommentMultilineCloser;
-                }
-
-                false
-            }
-        };
-
-        if !is_paused {
-            self.reset()
-        }
-
-        is_paused
-    }
-}
-
-/// `type` pause writing until type definition is completed.
-pub struct KeywordTypePauser {
-    opening_node: NodeASTType,
-    is_passed_assignment_operator: bool,
-    opening_bracket_count: u32,
-    closing_bracket_count: u32,
-    opening_brace_count: u32,
-    closing_brace_count: u32,
+/// Pauses writing for a span that was measured ahead of time in `lookahead`,
+/// like a type annotation or a class member that only exists in the type
+/// system. The trigger (`:` for annotations, `type` for aliases) counts as the
+/// first token, every token after it counts down until the whole span has been
+/// skipped.
+pub struct LookaheadPauser {
+    remaining_tokens: usize,
 }
 
-impl KeywordTypePauser {
-    pub fn new() -> KeywordTypePauser {
-        KeywordTypePauser {
-            opening_node: NodeASTType::Unknown,
-            is_passed_assignment_operator: false,
-            opening_bracket_count: 0,
-            closing_bracket_count: 0,
-            opening_brace_count: 0,
-            closing_brace_count: 0,
+impl LookaheadPauser {
+    pub fn new() -> LookaheadPauser {
+        LookaheadPauser {
+            remaining_tokens: 0,
         }
     }
 
-    fn reset(&mut self) {
-        self.opening_node = NodeASTType::Unknown;
-        self.is_passed_assignment_operator = false;
-        self.opening_bracket_count = 0;
-        self.closing_bracket_count = 0;
-        self.opening_brace_count = 0;
-        self.closing_brace_count = 0;
-    }
-
-    fn evaluate_array_type(&mut self, next_type: NodeASTType) -> bool {
-        if next_type == NodeASTType::OpeningBrace {
-            self.opening_brace_count += 1;
-        }
-        if next_type == NodeASTType::ClosingBrace {
-            self.closing_brace_count += 1;
-        }
-
-        match next_type {
-            NodeASTType::Terminator => {
-                self.reset();
-
-                if self.opening_brace_count != self.closing_brace_count {
-                    return true;
-                }
-                false
-            }
-            _ => true,
-        }
-    }
-
-    fn evaluate_object_type(&mut self, next_type: NodeASTType) -> bool {
-        if next_type == NodeASTType::OpeningBracket {
-            self.opening_bracket_count += 1;
-        }
-        if next_type == NodeASTType::ClosingBracket {
-            self.closing_bracket_count += 1;
-        }
-
-        if self.opening_bracket_count != self.closing_bracket_count {
-            // still paused
-            return true;
-        }
-
-        match next_type {
-            NodeASTType::Terminator => {
-                self.reset();
-                false
-            }
-            _ => true,
-        }
+    pub fn skip(&mut self, token_count: usize) {
+        self.remaining_tokens = token_count;
     }
 }
-impl Pauser for KeywordTypePauser {
-    fn is_paused_after_evaluating(&mut self, next_type: NodeASTType) -> bool {
-        if self.is_passed_assignment_operator == false {
-            // We dont care about anything before the assignment operator. Thats when things get real.
-            if next_type == NodeASTType::AssignmentOperator {
-                self.is_passed_assignment_operator = true;
-            } else {
-                return true;
-            }
-        }
 
-        // This is the block at which we are getting real.
-        // Let's figure out what type of operators were going to be planning forward for.
-        if self.is_passed_assignment_operator && self.opening_node == NodeASTType::Unknown {
-            self.opening_node = match next_type {
-                NodeASTType::OpeningBracket => next_type,
-                NodeASTType::OpeningBrace => next_type,
-                NodeASTType::Identifier => next_type,
-                unknown => {
-                    println!("{}", unknown);
-                    NodeASTType::Unknown
-                }
-            };
-        }
-
-        // This handles object types
-        if self.opening_node == NodeASTType::OpeningBracket {
-            return self.evaluate_object_type(next_type);
-        }
-        if self.opening_node == NodeASTType::OpeningBrace {
-            return self.evaluate_array_type(next_type);
-        }
-
-        if self.opening_node == NodeASTType::Identifier {
-            self.reset();
-            return false;
-        }
-
-        return true;
+impl Pauser for LookaheadPauser {
+    fn is_paused_after_evaluating(&mut self, _next_type: NodeASTType) -> bool {
+        self.remaining_tokens = self.remaining_tokens.saturating_sub(1);
+        self.remaining_tokens > 0
     }
 }
 
-/// `declare` pause writing until type definition is completed.
-/// We also use this same pauser for the `interface` keyword
-/// because they follow the same grammar
-pub struct KeywordDeclarePauser {
+/// `interface` pause writing until its body is completed.
+pub struct KeywordInterfacePauser {
     opening_brace_count: u32,
     closing_brace_count: u32,
 }
 
-impl KeywordDeclarePauser {
-    pub fn new() -> KeywordDeclarePauser {
-        KeywordDeclarePauser {
+impl KeywordInterfacePauser {
+    pub fn new() -> KeywordInterfacePauser {
+        KeywordInterfacePauser {
             opening_brace_count: 0,
             closing_brace_count: 0,
         }
@@ -201,7 +64,7 @@ impl KeywordDeclarePauser {
     }
 }
 
-impl Pauser for KeywordDeclarePauser {
+impl Pauser for KeywordInterfacePauser {
     fn is_paused_after_evaluating(&mut self, next_type: NodeASTType) -> bool {
         if next_type == NodeASTType::OpeningBracket {
             self.opening_brace_count += 1;
@@ -217,19 +80,3 @@ impl Pauser for KeywordDeclarePauser {
         true
     }
 }
-
-/// `as` pause writing until type definition is completed.
-pub struct KeywordAsPauser {}
-impl KeywordAsPauser {
-    pub fn new() -> KeywordAsPauser {
-        KeywordAsPauser {}
-    }
-}
-impl Pauser for KeywordAsPauser {
-    fn is_paused_after_evaluating(&mut self, next_type: NodeASTType) -> bool {
-        match next_type {
-            NodeASTType::Terminator => false,
-            _ => true,
-        }
-    }
-}
@@ -0,0 +1,337 @@
+use crate::json::Json;
+use crate::options::Options;
+use crate::tsconfig;
+use std::path::{Component, Path, PathBuf};
+
+/// What a module is resolved for: the file that is run, or the one its types
+/// come from, which is where const enums are read from.
+#[derive(Copy, Clone, PartialEq)]
+pub enum Resolution {
+    Runtime,
+    Types,
+}
+
+impl Resolution {
+    // Extensions tried, in order, for a specifier without one.
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Resolution::Runtime => &[
+                ".ts", ".tsx", ".mts", ".cts", ".js", ".mjs", ".cjs", ".json",
+            ],
+            Resolution::Types => &[".ts", ".tsx", ".d.ts", ".mts", ".cts"],
+        }
+    }
+
+    // `./enums.js` is how ESM code refers to `./enums.ts`.
+    fn replaced_extensions(self) -> &'static [(&'static str, &'static [&'static str])] {
+        match self {
+            Resolution::Runtime => &[
+                (".js", &[".ts", ".tsx"]),
+                (".jsx", &[".tsx"]),
+                (".mjs", &[".mts"]),
+                (".cjs", &[".cts"]),
+            ],
+            Resolution::Types => &[
+                (".js", &[".ts", ".tsx", ".d.ts"]),
+                (".jsx", &[".tsx", ".d.ts"]),
+                (".mjs", &[".mts", ".d.mts"]),
+                (".cjs", &[".cts", ".d.cts"]),
+            ],
+        }
+    }
+
+    // The conditions of `exports` that match. The loader runs every module as
+    // CommonJS, so that is `require` rather than `import`.
+    fn conditions(self) -> &'static [&'static str] {
+        match self {
+            Resolution::Runtime => &["node", "require", "default"],
+            Resolution::Types => &["types", "node", "import", "require", "default"],
+        }
+    }
+
+    // The fields of a `package.json` without `exports` that its entry point
+    // is read from, in order.
+    fn main_fields(self) -> &'static [&'static str] {
+        match self {
+            Resolution::Runtime => &["main"],
+            Resolution::Types => &["types", "typings", "main"],
+        }
+    }
+}
+
+fn is_relative(specifier: &str) -> bool {
+    specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier == "."
+        || specifier == ".."
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut value = path.as_os_str().to_os_string();
+    value.push(suffix);
+    PathBuf::from(value)
+}
+
+// `path` without any `.` and with every `..` taken out along with the
+// directory before it, like Node's `path.resolve`, so a module that is
+// imported from two places is still loaded once.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+// The file `base` is, or that it is with an extension added or replaced.
+fn load_file(base: &Path, resolution: Resolution) -> Option<PathBuf> {
+    let mut candidates = vec![base.to_path_buf()];
+    let path = base.to_string_lossy();
+    for (extension, replacements) in resolution.replaced_extensions() {
+        if let Some(stem) = path.strip_suffix(extension) {
+            candidates.extend(
+                replacements
+                    .iter()
+                    .map(|replacement| with_suffix(Path::new(stem), replacement)),
+            );
+        }
+    }
+    candidates.extend(
+        resolution
+            .extensions()
+            .iter()
+            .map(|extension| with_suffix(base, extension)),
+    );
+
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+// The entry point of the directory `base`: what the `main` of its
+// `package.json` refers to, or its `index` file.
+fn load_directory(base: &Path, resolution: Resolution) -> Option<PathBuf> {
+    let manifest = tsconfig::read(&base.join("package.json"));
+    let main = resolution.main_fields().iter().find_map(|field| {
+        let main = base.join(manifest.as_ref()?.get(field)?.as_str()?);
+        load_file(&main, resolution).or_else(|| load_index(&main, resolution))
+    });
+    main.or_else(|| load_index(base, resolution))
+}
+
+fn load_index(base: &Path, resolution: Resolution) -> Option<PathBuf> {
+    load_file(&base.join("index"), resolution)
+}
+
+// The file `base` refers to, either as a file or as a directory.
+fn resolve_path(base: &Path, resolution: Resolution) -> Option<PathBuf> {
+    load_file(base, resolution).or_else(|| load_directory(base, resolution))
+}
+
+// `@scope/name/sub/path` as `@scope/name` and `/sub/path`.
+fn package_name(specifier: &str) -> (&str, &str) {
+    let length = specifier
+        .match_indices('/')
+        .map(|(index, _)| index)
+        .nth(match specifier.starts_with('@') {
+            true => 1,
+            false => 0,
+        })
+        .unwrap_or(specifier.len());
+    specifier.split_at(length)
+}
+
+// The file a `package.json` target like `"./dist/*.js"` refers to, trying
+// every target of an array and the ones under the conditions that match.
+fn resolve_target(
+    package: &Path,
+    target: &Json,
+    matched: &str,
+    resolution: Resolution,
+) -> Option<PathBuf> {
+    match target {
+        Json::String(target) => {
+            let target = target.strip_prefix("./")?.replace('*', matched);
+            load_file(&package.join(target), resolution)
+        }
+        Json::Array(targets) => targets
+            .iter()
+            .find_map(|target| resolve_target(package, target, matched, resolution)),
+        Json::Object(conditions) => conditions
+            .iter()
+            .filter(|(condition, _)| resolution.conditions().contains(&condition.as_str()))
+            .find_map(|(_, target)| resolve_target(package, target, matched, resolution)),
+        _ => None,
+    }
+}
+
+// The file the `exports` of a package export `subpath`, like `.` or
+// `./utils`, as. Exact subpaths come first, then the pattern with the longest
+// prefix.
+fn resolve_exports(
+    package: &Path,
+    exports: &Json,
+    subpath: &str,
+    resolution: Resolution,
+) -> Option<PathBuf> {
+    let subpaths = exports
+        .as_object()
+        .filter(|entries| entries.iter().all(|(key, _)| key.starts_with('.')));
+    // `"exports": "./index.js"` or just conditions are what `.` exports.
+    let Some(subpaths) = subpaths else {
+        return match subpath {
+            "." => resolve_target(package, exports, "", resolution),
+            _ => None,
+        };
+    };
+    if let Some((_, target)) = subpaths.iter().find(|(key, _)| key == subpath) {
+        return resolve_target(package, target, "", resolution);
+    }
+    let (_, matched, target) = subpaths
+        .iter()
+        .filter(|(key, _)| key.contains('*'))
+        .filter_map(|(key, target)| {
+            match_pattern(key, subpath).map(|matched| (key, matched, target))
+        })
+        .max_by_key(|(key, _, _)| key.find('*'))?;
+    resolve_target(package, target, matched, resolution)
+}
+
+// A bare specifier like `lodash/get`, looked up in every `node_modules`
+// from `directory` up, and for types in `@types` as well.
+fn resolve_package(directory: &Path, specifier: &str, resolution: Resolution) -> Option<PathBuf> {
+    let (name, subpath) = package_name(specifier);
+    let mut names = vec![name.to_string()];
+    if resolution == Resolution::Types {
+        names.push(format!(
+            "@types/{}",
+            name.trim_start_matches('@').replacen('/', "__", 1)
+        ));
+    }
+    names.iter().find_map(|name| {
+        directory.ancestors().find_map(|ancestor| {
+            let package = ancestor.join("node_modules").join(name);
+            if !package.is_dir() {
+                return None;
+            }
+            let manifest = tsconfig::read(&package.join("package.json"));
+            match manifest
+                .as_ref()
+                .and_then(|manifest| manifest.get("exports"))
+            {
+                Some(exports) => {
+                    resolve_exports(&package, exports, &format!(".{}", subpath), resolution)
+                }
+                None => resolve_path(&package.join(subpath.trim_start_matches('/')), resolution),
+            }
+        })
+    })
+}
+
+/// Finds the file `specifier` refers to from `importer` the way Node's
+/// `require.resolve` does, reading the `main` and `exports` of packages, and
+/// the way `tsc` does: `./enums.js` is `./enums.ts` when that is what exists,
+/// and `index.ts` is a directory's entry point. Builtin modules like `node:fs`
+/// and the `#imports` of a package aren't resolved.
+pub fn resolve(importer: &Path, specifier: &str, resolution: Resolution) -> Option<PathBuf> {
+    let directory = importer.parent().unwrap_or(Path::new(""));
+    let path = if is_relative(specifier) || Path::new(specifier).is_absolute() {
+        resolve_path(&directory.join(specifier), resolution)
+    } else if specifier.starts_with("node:") || specifier.starts_with('#') {
+        None
+    } else {
+        resolve_package(directory, specifier, resolution)
+    };
+    path.map(|path| normalize(&path))
+}
+
+// What the `*` of `pattern` matches in `specifier`, or `""` for a pattern
+// without one that is the specifier.
+fn match_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier.strip_prefix(prefix)?.strip_suffix(suffix),
+        None if pattern == specifier => Some(""),
+        None => None,
+    }
+}
+
+/// Finds the file a bare specifier like `@app/enums` refers to through the
+/// `paths` of `tsconfig.json`, or relative to its `baseUrl`. Like `tsc`,
+/// the pattern with the longest prefix wins.
+pub fn resolve_alias(specifier: &str, options: &Options) -> Option<PathBuf> {
+    if is_relative(specifier) {
+        return None;
+    }
+    let mut matches: Vec<(&String, &str, &Vec<PathBuf>)> = options
+        .paths
+        .iter()
+        .filter_map(|(pattern, targets)| {
+            match_pattern(pattern, specifier).map(|matched| (pattern, matched, targets))
+        })
+        .collect();
+    matches
+        .sort_by_key(|(pattern, _, _)| std::cmp::Reverse(pattern.find('*').unwrap_or(usize::MAX)));
+    for (_, matched, targets) in matches {
+        for target in targets {
+            let target = target.to_string_lossy().replacen('*', matched, 1);
+            if let Some(path) = resolve_path(Path::new(&target), Resolution::Types) {
+                return Some(normalize(&path));
+            }
+        }
+    }
+    let path = resolve_path(
+        &options.base_url.as_ref()?.join(specifier),
+        Resolution::Types,
+    );
+    path.map(|path| normalize(&path))
+}
+
+/// The relative specifier `importer` imports `path` with in place of the
+/// alias `specifier`, like `../lib/util` for `@app/lib/util`. The TypeScript
+/// extension is left out, or replaced by the JavaScript one `specifier` has.
+pub fn relative_specifier(importer: &Path, path: &Path, specifier: &str) -> String {
+    let directory: Vec<_> = importer
+        .parent()
+        .unwrap_or(Path::new(""))
+        .components()
+        .collect();
+    let target: Vec<_> = path.components().collect();
+    let common = directory
+        .iter()
+        .zip(&target)
+        .take_while(|(directory, target)| directory == target)
+        .count();
+    let mut segments = vec![String::from(".."); directory.len() - common];
+    segments.extend(
+        target[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+    );
+    let mut relative = segments.join("/");
+    if !relative.starts_with("../") {
+        relative = format!("./{}", relative);
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    if specifier.ends_with(file_name.as_ref()) {
+        return relative;
+    }
+    let stem = [".d.ts", ".ts", ".tsx", ".mts", ".cts"]
+        .iter()
+        .find_map(|extension| relative.strip_suffix(extension))
+        .unwrap_or(&relative);
+    let extension = [".js", ".jsx", ".mjs", ".cjs"]
+        .into_iter()
+        .find(|extension| specifier.ends_with(extension))
+        .unwrap_or_default();
+    format!("{}{}", stem, extension)
+}
@@ -0,0 +1,279 @@
+use std::collections::{HashMap, HashSet};
+
+use super::commonjs::{declared_names, initializer_end, pattern_names};
+use super::decorators::decorator_end;
+use super::lookahead::{
+    is_closing, is_identifier, is_opening, matching_close, next_index, next_token, type_end,
+};
+
+/// Keywords whose parentheses are followed by a block that isn't a function
+/// body.
+const CONTROL_KEYWORDS: [&str; 5] = ["if", "for", "while", "switch", "with"];
+
+/// Modifiers of constructor parameter properties.
+const PARAMETER_MODIFIERS: [&str; 5] = ["public", "private", "protected", "readonly", "override"];
+
+/// The names declared inside functions and blocks, each with the ranges of
+/// tokens it is in scope for. Module level bindings like imports and const
+/// enums aren't what those names refer to inside of them.
+pub struct Scopes {
+    ranges: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl Scopes {
+    /// Whether `name` at `position` refers to a declaration inside a function
+    /// or block rather than to the module level one.
+    pub fn is_shadowed(&self, name: &str, position: usize) -> bool {
+        self.ranges.get(name).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|(start, end)| (*start..*end).contains(&position))
+        })
+    }
+
+    fn declare(&mut self, names: Vec<String>, range: (usize, usize)) {
+        for name in names {
+            self.ranges.entry(name).or_default().push(range);
+        }
+    }
+}
+
+// The token before `index` that isn't a line break.
+fn previous_token(tokens: &[String], index: usize) -> &str {
+    tokens[..index]
+        .iter()
+        .rfind(|token| token.as_str() != "\n")
+        .map_or("", String::as_str)
+}
+
+// Index past the statement starting at `position`: its block, or up to and
+// including its `;`.
+fn statement_end(tokens: &[String], position: usize) -> usize {
+    let mut index = next_index(tokens, position);
+    if next_token(tokens, index) == "{" {
+        return matching_close(tokens, index) + 1;
+    }
+    while index < tokens.len() && tokens[index] != ";" {
+        if is_opening(&tokens[index]) && tokens[index] != "<" {
+            index = matching_close(tokens, index);
+        }
+        index += 1;
+    }
+    index + 1
+}
+
+// Index past the body of a function that starts at `position`, either a
+// block or the expression of an arrow function.
+fn body_end(tokens: &[String], position: usize) -> usize {
+    let index = next_index(tokens, position);
+    match next_token(tokens, index) {
+        "{" => matching_close(tokens, index) + 1,
+        _ => initializer_end(tokens, index),
+    }
+}
+
+// The names bound by the parameters in the parentheses opened at `opening`,
+// which may have decorators, modifiers, types and default values.
+fn parameter_names(tokens: &[String], opening: usize) -> Vec<String> {
+    let close = matching_close(tokens, opening);
+    let mut names = vec![];
+    let mut index = next_index(tokens, opening + 1);
+    while index < close {
+        while next_token(tokens, index) == "@" {
+            index = next_index(tokens, decorator_end(tokens, next_index(tokens, index)));
+        }
+        while PARAMETER_MODIFIERS.contains(&next_token(tokens, index)) && {
+            let next = next_token(tokens, next_index(tokens, index) + 1);
+            is_identifier(next) || next == "{" || next == "["
+        } {
+            index = next_index(tokens, next_index(tokens, index) + 1);
+        }
+        if next_token(tokens, index) == "..." {
+            index = next_index(tokens, index + 1);
+        }
+        match next_token(tokens, index) {
+            "{" | "[" => pattern_names(tokens, index, &mut names),
+            token if is_identifier(token) && token != "this" => names.push(token.to_string()),
+            _ => {}
+        }
+        // The type and default value, up to the next parameter.
+        while index < close && tokens[index] != "," {
+            if is_opening(&tokens[index]) {
+                index = matching_close(tokens, index);
+            }
+            index += 1;
+        }
+        index = next_index(tokens, index + 1);
+    }
+    names
+}
+
+// When the parentheses opened at `opening` are the parameters of a function,
+// a method or a `catch` clause, the index its body starts at and the index
+// past it.
+fn function_body(tokens: &[String], opening: usize) -> Option<(usize, usize)> {
+    if CONTROL_KEYWORDS.contains(&previous_token(tokens, opening)) {
+        return None;
+    }
+    let mut index = next_index(tokens, matching_close(tokens, opening) + 1);
+    if next_token(tokens, index) == ":" {
+        index = next_index(tokens, type_end(tokens, next_index(tokens, index) + 1));
+    }
+    match next_token(tokens, index) {
+        "=>" => {
+            let body = next_index(tokens, index + 1);
+            Some((body, body_end(tokens, body)))
+        }
+        "{" if !is_heritage_call(tokens, opening) => {
+            Some((index, matching_close(tokens, index) + 1))
+        }
+        _ => None,
+    }
+}
+
+// `class A extends mixin(B) {`, where the `{` is the class body.
+fn is_heritage_call(tokens: &[String], opening: usize) -> bool {
+    let mut index = opening;
+    loop {
+        let Some(callee) = tokens[..index].iter().rposition(|token| token != "\n") else {
+            return false;
+        };
+        match tokens[callee].as_str() {
+            "extends" => return true,
+            "." => index = callee,
+            token if is_identifier(token) => index = callee,
+            _ => return false,
+        }
+    }
+}
+
+// A `function` or `class` keyword at `position` starts a declaration, rather
+// than an expression, when it starts a statement.
+fn is_declaration(tokens: &[String], position: usize) -> bool {
+    let mut index = position;
+    loop {
+        let Some(previous) = tokens[..index].iter().rposition(|token| token != "\n") else {
+            return true;
+        };
+        match tokens[previous].as_str() {
+            "async" | "export" | "default" | "declare" | "abstract" => index = previous,
+            token => return matches!(token, ";" | "{" | "}"),
+        }
+    }
+}
+
+// The name of the function or class whose keyword is at `position`, and the
+// index past its body.
+fn named_declaration(tokens: &[String], position: usize) -> Option<(String, usize)> {
+    let mut index = next_index(tokens, position + 1);
+    if next_token(tokens, index) == "*" {
+        index = next_index(tokens, index + 1);
+    }
+    let name = next_token(tokens, index);
+    if !is_identifier(name) || matches!(name, "extends" | "implements") {
+        return None;
+    }
+    let mut body = index + 1;
+    while body < tokens.len() && !matches!(tokens[body].as_str(), "{" | ";") {
+        if matches!(tokens[body].as_str(), "<" | "(" | "[") {
+            body = matching_close(tokens, body);
+        }
+        body += 1;
+    }
+    if tokens.get(body)? != "{" {
+        return None;
+    }
+    Some((name.to_string(), matching_close(tokens, body) + 1))
+}
+
+/// Finds the declarations inside functions and blocks: parameters, `let`,
+/// `const` and `var`, function and class names, `catch` bindings and the
+/// variables of `for` loops. Declarations at the module level are left out,
+/// as those are the module level bindings themselves.
+pub fn find_scopes(tokens: &[String]) -> Scopes {
+    let mut scopes = Scopes {
+        ranges: HashMap::new(),
+    };
+    // The brackets around the current token, and which braces are the body
+    // of a function.
+    let mut brackets: Vec<usize> = vec![];
+    let mut function_bodies: HashSet<usize> = HashSet::new();
+    let enclosing_block = |brackets: &[usize]| {
+        brackets
+            .last()
+            .filter(|opening| tokens[**opening] == "{")
+            .map(|opening| (*opening, matching_close(tokens, *opening) + 1))
+    };
+
+    for index in 0..tokens.len() {
+        let token = tokens[index].as_str();
+        if matches!(previous_token(tokens, index), "." | "?.") {
+            continue;
+        }
+        match token {
+            "(" => {
+                if let Some((body, end)) = function_body(tokens, index) {
+                    function_bodies.insert(body);
+                    scopes.declare(parameter_names(tokens, index), (index, end));
+                }
+            }
+            "let" | "const" | "var"
+                if {
+                    let next = next_token(tokens, index + 1);
+                    (is_identifier(next) && !matches!(next, "enum" | "in" | "of" | "instanceof"))
+                        || next == "{"
+                        || next == "["
+                } =>
+            {
+                let names = declared_names(tokens, index + 1);
+                let for_head = brackets.last().filter(|opening| {
+                    tokens[**opening] == "("
+                        && match previous_token(tokens, **opening) {
+                            "for" => true,
+                            "await" => previous_token(tokens, **opening - 1) == "for",
+                            _ => false,
+                        }
+                });
+                let range = match (token, for_head) {
+                    ("var", _) => brackets
+                        .iter()
+                        .rev()
+                        .find(|opening| function_bodies.contains(opening))
+                        .map(|opening| (*opening, matching_close(tokens, *opening) + 1)),
+                    (_, Some(opening)) => Some((
+                        *opening,
+                        statement_end(tokens, matching_close(tokens, *opening) + 1),
+                    )),
+                    _ => enclosing_block(&brackets),
+                };
+                if let Some(range) = range {
+                    scopes.declare(names, range);
+                }
+            }
+            "function" | "class" => {
+                if let Some((name, end)) = named_declaration(tokens, index) {
+                    let range = match is_declaration(tokens, index) {
+                        true => enclosing_block(&brackets),
+                        false => Some((index, end)),
+                    };
+                    if let Some(range) = range {
+                        scopes.declare(vec![name], range);
+                    }
+                }
+            }
+            // `a => a`
+            _ if is_identifier(token) && next_token(tokens, index + 1) == "=>" => {
+                let end = body_end(tokens, next_index(tokens, index + 1) + 1);
+                scopes.declare(vec![token.to_string()], (index, end));
+            }
+            _ => {}
+        }
+
+        if is_opening(token) && token != "<" {
+            brackets.push(index);
+        } else if is_closing(token) && token != ">" {
+            brackets.pop();
+        }
+    }
+    scopes
+}
@@ -0,0 +1,152 @@
+use crate::json::{self, Json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Parsed config and `package.json` files by path, with the modification
+/// time they were read at, so the loader doesn't parse them again for every
+/// module.
+type Cache = HashMap<PathBuf, (Option<SystemTime>, Option<Json>)>;
+
+fn cache() -> &'static Mutex<Cache> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The JSON file at `path`, comments allowed, or `None` when it can't be read.
+pub fn read(path: &Path) -> Option<Json> {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    let mut cache = cache().lock().unwrap_or_else(|error| error.into_inner());
+    if let Some((cached_modified, config)) = cache.get(path) {
+        if *cached_modified == modified {
+            return config.clone();
+        }
+    }
+    let config = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| json::parse(&contents));
+    cache.insert(path.to_path_buf(), (modified, config.clone()));
+    config
+}
+
+/// The `tsconfig.json` in the directory of `module_path` or the closest one
+/// above it.
+pub fn find(module_path: &Path) -> Option<PathBuf> {
+    module_path
+        .ancestors()
+        .skip(1)
+        .map(|directory| directory.join("tsconfig.json"))
+        .find(|path| path.is_file())
+}
+
+// The config `extends` refers to: a path relative to `directory`, or a
+// package like `@tsconfig/node20/tsconfig.json` in a `node_modules` above it.
+fn resolve_extends(directory: &Path, specifier: &str) -> Option<PathBuf> {
+    let with_extension = |path: PathBuf| match path.is_file() {
+        true => Some(path),
+        false => {
+            Some(PathBuf::from(format!("{}.json", path.display()))).filter(|path| path.is_file())
+        }
+    };
+    if specifier.starts_with('.') || Path::new(specifier).is_absolute() {
+        return with_extension(directory.join(specifier));
+    }
+    directory.ancestors().find_map(|ancestor| {
+        let package = ancestor.join("node_modules").join(specifier);
+        match package.is_dir() {
+            true => {
+                let tsconfig = read(&package.join("package.json"))
+                    .and_then(|manifest| manifest.get("tsconfig")?.as_str().map(String::from))
+                    .unwrap_or_else(|| String::from("tsconfig.json"));
+                Some(package.join(tsconfig)).filter(|path| path.is_file())
+            }
+            false => with_extension(package),
+        }
+    })
+}
+
+// The compiler options set by the config at `path` and the ones it
+// extends, which it overrides. `baseUrl` is made absolute, and so is the
+// directory `paths` are relative to when there is no `baseUrl`, as
+// `pathsBasePath`.
+fn compiler_options(path: &Path, seen: &mut Vec<PathBuf>) -> Vec<(String, Json)> {
+    if seen.iter().any(|seen| seen == path) {
+        return vec![];
+    }
+    seen.push(path.to_path_buf());
+    let Some(config) = read(path) else {
+        return vec![];
+    };
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut options = vec![];
+    let extends = match config.get("extends") {
+        Some(Json::String(specifier)) => vec![specifier.as_str()],
+        Some(Json::Array(specifiers)) => specifiers.iter().filter_map(Json::as_str).collect(),
+        _ => vec![],
+    };
+    for specifier in extends {
+        if let Some(base) = resolve_extends(directory, specifier) {
+            options.extend(compiler_options(&base, seen));
+        }
+    }
+
+    let own = config.get("compilerOptions").and_then(Json::as_object);
+    for (name, value) in own.unwrap_or_default() {
+        let value = match (name.as_str(), value) {
+            ("baseUrl", Json::String(base_url)) => {
+                Json::String(directory.join(base_url).display().to_string())
+            }
+            ("paths", _) => {
+                let base = directory.display().to_string();
+                options.push((String::from("pathsBasePath"), Json::String(base)));
+                value.clone()
+            }
+            _ => value.clone(),
+        };
+        options.push((name.clone(), value));
+    }
+    options
+}
+
+/// The compiler options of the closest `tsconfig.json` to `module_path`, as
+/// an object `Options::update` reads, or `None` when there isn't one.
+pub fn options(module_path: &Path) -> Option<Json> {
+    let path = find(module_path)?;
+    let mut options = compiler_options(&path, &mut vec![]);
+
+    // `node16` and `nodenext` are CommonJS or ES modules depending on the
+    // extension and the `type` of the closest `package.json`.
+    let module = options
+        .iter()
+        .rfind(|(name, _)| name == "module")
+        .and_then(|(_, value)| value.as_str())
+        .map(str::to_lowercase);
+    if let Some("node16" | "node18" | "nodenext") = module.as_deref() {
+        let module = match module_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("mts") => "esnext",
+            Some("cts") => "commonjs",
+            _ if package_type(module_path).as_deref() == Some("module") => "esnext",
+            _ => "commonjs",
+        };
+        options.push((String::from("module"), Json::String(module.to_string())));
+    }
+    Some(Json::Object(options))
+}
+
+fn package_type(module_path: &Path) -> Option<String> {
+    module_path
+        .ancestors()
+        .skip(1)
+        .map(|directory| directory.join("package.json"))
+        .find(|path| path.is_file())
+        .and_then(|path| read(&path))
+        .and_then(|manifest| manifest.get("type")?.as_str().map(String::from))
+}
@@ -1 +1 @@
-const B=true
+const B=true;
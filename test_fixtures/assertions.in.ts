@@ -0,0 +1,22 @@
+declare const input: unknown;
+declare function get(): { value?: string; list?: number[] };
+
+const length = (input as string).length;
+const first = get().list![0];
+const value = get()!.value!.trim();
+const doubled = (<number>input) * 2;
+const legacy = <string>input;
+const config = { mode: "fast" } as const;
+const checked = { port: 80 } satisfies { port: number };
+const chained = (input as unknown as { a: string }).a.toUpperCase();
+const called = Math.max(input as number, <number>input, 3);
+const negated = !input;
+const unequal = input! !== null;
+const generic = <T,>(item: T) => item;
+let as = 1;
+as = as + 1;
+const template = `${input as string}!`;
+
+function read(key: string) {
+  return (input as Record<string, string>)[key]!;
+}
@@ -0,0 +1 @@
+const length=(input).length;const first=get().list[0];const value=get().value.trim();const doubled=(input)*2;const legacy=input;const config={mode:"fast"};const checked={port:80};const chained=(input).a.toUpperCase();const called=Math.max(input,input,3);const negated=!input;const unequal=input!==null;const generic=(item)=>item;let as=1;as=as+1;const template=`${input}!`;function read(key){return(input)[key];}
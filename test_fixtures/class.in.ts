@@ -0,0 +1,53 @@
+interface Named {
+  name: string;
+}
+
+abstract class Base<T extends object = {}> {
+  protected abstract kind: string;
+  abstract describe(): string;
+  declare cached: T;
+  [key: string]: unknown;
+
+  public static create<T>(value: T): T {
+    return value;
+  }
+}
+
+class Service extends Base<Named> implements Named, Iterable<string> {
+  private readonly items: string[] = [];
+  public name: string = 'service';
+  protected count?: number;
+  static #instances = 0;
+  override kind = 'service';
+  total!: number
+  handler = (event: Event): void => console.log(event)
+
+  get size(): number {
+    return this.items.length;
+  }
+
+  set size(value: number) {
+    this.count = value;
+  }
+
+  describe(): string;
+  describe(verbose?: boolean): string;
+  describe(verbose: boolean = false): string {
+    return verbose ? this.name : this.kind;
+  }
+
+  maybe?(): void;
+
+  async *[Symbol.iterator](): AsyncGenerator<string> {
+    yield* this.items;
+  }
+
+  static {
+    Service.#instances++;
+  }
+}
+
+const Anonymous = class implements Named {
+  name = 'anonymous'
+}
+new Service()
@@ -0,0 +1,4 @@
+class Base{static create(value){return value;}}class Service extends Base{items=[];name='service';count;static#instances=0;kind='service';total
+handler=(event)=>console.log(event)
+get size(){return this.items.length;}set size(value){this.count=value;}describe(verbose=false){return verbose?this.name:this.kind;}async*[Symbol.iterator](){yield*this.items;}static{Service.#instances++;}}const Anonymous=class{name='anonymous'}
+new Service()
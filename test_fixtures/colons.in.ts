@@ -0,0 +1,26 @@
+const config = { name: 'rs-node', nested: { depth: 2 }, list: [{ a: 1 }] };
+const picked = config.name ? config.nested : { depth: 0 };
+const deep = picked.depth > 1 ? (picked.depth > 2 ? 'deep' : 'shallow') : 'flat';
+const describe = (value: number): string => (value ? `v: ${value}` : 'none');
+const format = picked.depth ? (value: number): string => `${value}` : null;
+const noop = deep ? (): void => {} : (deep);
+
+function kind(value: number): string {
+  switch (value) {
+    case 1:
+      return 'one';
+    case value > 2 ? 3 : 4:
+      return 'many';
+    default:
+      return { label: 'other' }.label;
+  }
+}
+
+outer: for (const item of config.list) {
+  for (const key in item) {
+    if (key) continue outer;
+  }
+}
+
+const inline = { a: 1 }
+kind(1)
@@ -0,0 +1,44 @@
+// @module: commonjs
+import './polyfills';
+import React from 'react';
+import * as path from 'path';
+import def, { readFile as read, type Stats } from 'fs';
+import { format, type Options } from './format';
+import type { Config } from './config';
+import { sealed } from './decorators';
+
+export const name: string = 'app', { a, b: [c] } = { a: 1, b: [2] };
+export let count = 0;
+export function increment(): void {
+  count++;
+}
+export class Store {
+  render() {
+    return React.createElement('div', { format, title: name });
+  }
+}
+export enum Color {
+  Red,
+}
+export default function () {
+  return read(path.join('a', 'b'), def);
+}
+
+@sealed
+export class Model {}
+
+function describe(format: string, ...rest: string[]) {
+  const { format: local } = { format };
+  for (const read of rest) {
+    count += read.length;
+  }
+  return local;
+}
+
+const label = format`x`;
+const options: Options = { format: 1 };
+export { label, options as settings, format };
+export type { Config };
+export { join, default as sep } from 'path';
+export * from './shared';
+export * as util from './util';
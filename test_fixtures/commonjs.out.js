@@ -0,0 +1 @@
+"use strict";var __createBinding=(this&&this.__createBinding)||(Object.create?(function(o,m,k,k2){if(k2===undefined)k2=k;var desc=Object.getOwnPropertyDescriptor(m,k);if(!desc||("get"in desc?!m.__esModule:desc.writable||desc.configurable)){desc={enumerable:true,get:function(){return m[k];}};}Object.defineProperty(o,k2,desc);}):(function(o,m,k,k2){if(k2===undefined)k2=k;o[k2]=m[k];}));var __setModuleDefault=(this&&this.__setModuleDefault)||(Object.create?(function(o,v){Object.defineProperty(o,"default",{enumerable:true,value:v});}):function(o,v){o["default"]=v;});var __decorate=(this&&this.__decorate)||function(decorators,target,key,desc){var c=arguments.length,r=c<3?target:desc===null?desc=Object.getOwnPropertyDescriptor(target,key):desc,d;if(typeof Reflect==="object"&&typeof Reflect.decorate==="function")r=Reflect.decorate(decorators,target,key,desc);else for(var i=decorators.length-1;i>=0;i--)if(d=decorators[i])r=(c<3?d(r):c>3?d(target,key,r):d(target,key))||r;return c>3&&r&&Object.defineProperty(target,key,r),r;};var __importStar=(this&&this.__importStar)||function(mod){if(mod&&mod.__esModule)return mod;var result={};if(mod!=null)for(var k in mod)if(k!=="default"&&Object.prototype.hasOwnProperty.call(mod,k))__createBinding(result,mod,k);__setModuleDefault(result,mod);return result;};var __exportStar=(this&&this.__exportStar)||function(m,exports){for(var p in m)if(p!=="default"&&!Object.prototype.hasOwnProperty.call(exports,p))__createBinding(exports,m,p);};var __importDefault=(this&&this.__importDefault)||function(mod){return(mod&&mod.__esModule)?mod:{"default":mod};};Object.defineProperty(exports,"__esModule",{value:true});Object.defineProperty(exports,"name",{enumerable:true,get:function(){return name;}});Object.defineProperty(exports,"a",{enumerable:true,get:function(){return a;}});Object.defineProperty(exports,"c",{enumerable:true,get:function(){return c;}});Object.defineProperty(exports,"count",{enumerable:true,get:function(){return count;}});Object.defineProperty(exports,"increment",{enumerable:true,get:function(){return increment;}});Object.defineProperty(exports,"Store",{enumerable:true,get:function(){return Store;}});Object.defineProperty(exports,"Color",{enumerable:true,get:function(){return Color;}});Object.defineProperty(exports,"default",{enumerable:true,get:function(){return default_1;}});Object.defineProperty(exports,"Model",{enumerable:true,get:function(){return Model;}});Object.defineProperty(exports,"label",{enumerable:true,get:function(){return label;}});Object.defineProperty(exports,"settings",{enumerable:true,get:function(){return options;}});Object.defineProperty(exports,"format",{enumerable:true,get:function(){return format_1.format;}});require('./polyfills');const react_1=__importDefault(require('react'));const path=__importStar(require('path'));const fs_1=__importStar(require('fs'));const format_1=require('./format');const decorators_1=require('./decorators');const name='app',{a,b:[c]}={a:1,b:[2]};let count=0;function increment(){count++;}class Store{render(){return react_1.default.createElement('div',{format:format_1.format,title:name});}}var Color;(function(Color){Color[Color["Red"]=0]="Red";})(Color||(Color={}));function default_1(){return(0,fs_1.readFile)(path.join('a','b'),fs_1.default);}let Model=class Model{};Model=__decorate([decorators_1.sealed],Model);function describe(format,...rest){const{format:local}={format};for(const read of rest){count+=read.length;}return local;}const label=(0,format_1.format)`x`;const options={format:1};const path_1=require('path');Object.defineProperty(exports,"join",{enumerable:true,get:function(){return path_1.join;}});Object.defineProperty(exports,"sep",{enumerable:true,get:function(){return __importDefault(path_1).default;}});__exportStar(require('./shared'),exports);exports.util=__importStar(require('./util'));
@@ -0,0 +1 @@
+export { Level as Severity } from './const-enum-values';
@@ -0,0 +1,16 @@
+export const enum Level {
+  Debug,
+  Info,
+  Warn = Info + 10,
+  Error,
+}
+
+export declare const enum Mode {
+  Read = 'r',
+  Write = "w",
+  ReadWrite = Read + Write,
+}
+
+const enum Internal {
+  Secret = 42,
+}
@@ -0,0 +1,31 @@
+import { Level, Mode as FileMode, log } from './const-enum-values';
+import { Mode } from './const-enum-values.js';
+import * as enums from './const-enum-values';
+import { Severity } from './const-enum-reexport';
+
+const enum Bits {
+  None = 0,
+  A = 1 << 0,
+  B = 1 << 1,
+  AB = A | B,
+  Mask = ~AB >>> 28,
+  Negative = -1,
+  Float = 1.5,
+  Hex = 0x10,
+  'quoted key' = "a \"b\"",
+}
+
+export const enum Exported {
+  Yes = 1,
+}
+
+log(Level.Debug, Level.Error, FileMode.ReadWrite, Mode.Read);
+const flags = Bits.A | Bits['B'] | Bits.Negative;
+const values = [Bits.AB, Bits.Mask, Bits.Float, Bits.Hex, Bits['quoted key'], Exported.Yes];
+const power = Bits.Negative ** 2;
+console.log(Bits.Hex.toString(), point.Bits.A)
+
+function shadowed(Bits: { A: number }) {
+  return Bits.A;
+}
+console.log(enums.Level.Warn, Severity.Info, shadowed({ A: 2 }));
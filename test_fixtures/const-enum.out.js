@@ -0,0 +1,2 @@
+import{log}from'./const-enum-values';log(0,12,"rw","r");const flags=1|2|(-1);const values=[3,15,1.5,16,"a \"b\"",1];const power=(-1)**2;console.log(16 .toString(),point.Bits.A)
+function shadowed(Bits){return Bits.A;}console.log(11,1,shadowed({A:2}));
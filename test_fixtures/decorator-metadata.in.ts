@@ -0,0 +1,54 @@
+// @emitDecoratorMetadata: true
+import { Injectable, Prop } from './core';
+import { Repository } from './repository';
+import type { Config } from './config';
+
+interface Shape {
+  sides: number;
+}
+type Id = string | number;
+type Name = string;
+enum Color {
+  Red,
+}
+enum Status {
+  Active = 'active',
+  Archived = `archived`,
+}
+class Local {}
+
+@Injectable()
+export class Service {
+  @Prop() text: string;
+  @Prop() optional?: number | null;
+  @Prop() flag: boolean;
+  @Prop() literal: 'a' | 'b';
+  @Prop() list: string[];
+  @Prop() tuple: [number, number];
+  @Prop() callback: (value: string) => void;
+  @Prop() shape: Shape;
+  @Prop() id: Id;
+  @Prop() name: Name;
+  @Prop() color: Color;
+  @Prop() status: Status;
+  @Prop() local: Local;
+  @Prop() mixed: string | number;
+  @Prop() date: Date;
+  @Prop() promise: Promise<Local>;
+  @Prop() untyped = 1;
+
+  constructor(private repository: Repository, config: Config, local: Local, ...rest: string[]) {}
+
+  @Prop()
+  async load(this: Service, id: number, @Prop() ...names: Array<string>): Promise<Shape> {
+    return { sides: id };
+  }
+
+  @Prop()
+  async fetch() {}
+
+  @Prop()
+  get total(): number {
+    return 0;
+  }
+}
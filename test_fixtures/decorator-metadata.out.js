@@ -0,0 +1 @@
+var __decorate=(this&&this.__decorate)||function(decorators,target,key,desc){var c=arguments.length,r=c<3?target:desc===null?desc=Object.getOwnPropertyDescriptor(target,key):desc,d;if(typeof Reflect==="object"&&typeof Reflect.decorate==="function")r=Reflect.decorate(decorators,target,key,desc);else for(var i=decorators.length-1;i>=0;i--)if(d=decorators[i])r=(c<3?d(r):c>3?d(target,key,r):d(target,key))||r;return c>3&&r&&Object.defineProperty(target,key,r),r;};var __metadata=(this&&this.__metadata)||function(k,v){if(typeof Reflect==="object"&&typeof Reflect.metadata==="function")return Reflect.metadata(k,v);};var __param=(this&&this.__param)||function(paramIndex,decorator){return function(target,key){decorator(target,key,paramIndex);}};import{Injectable,Prop}from'./core';import{Repository}from'./repository';var Color;(function(Color){Color[Color["Red"]=0]="Red";})(Color||(Color={}));var Status;(function(Status){Status["Active"]='active';Status["Archived"]=`archived`;})(Status||(Status={}));class Local{}let Service=class Service{text;optional;flag;literal;list;tuple;callback;shape;id;name;color;status;local;mixed;date;promise;untyped=1;constructor(repository,config,local,...rest){this.repository=repository;}async load(id,...names){return{sides:id};}async fetch(){}get total(){return 0;}};__decorate([Prop(),__metadata("design:type",String)],Service.prototype,"text",void 0);__decorate([Prop(),__metadata("design:type",Number)],Service.prototype,"optional",void 0);__decorate([Prop(),__metadata("design:type",Boolean)],Service.prototype,"flag",void 0);__decorate([Prop(),__metadata("design:type",String)],Service.prototype,"literal",void 0);__decorate([Prop(),__metadata("design:type",Array)],Service.prototype,"list",void 0);__decorate([Prop(),__metadata("design:type",Array)],Service.prototype,"tuple",void 0);__decorate([Prop(),__metadata("design:type",Function)],Service.prototype,"callback",void 0);__decorate([Prop(),__metadata("design:type",Object)],Service.prototype,"shape",void 0);__decorate([Prop(),__metadata("design:type",Object)],Service.prototype,"id",void 0);__decorate([Prop(),__metadata("design:type",String)],Service.prototype,"name",void 0);__decorate([Prop(),__metadata("design:type",Number)],Service.prototype,"color",void 0);__decorate([Prop(),__metadata("design:type",String)],Service.prototype,"status",void 0);__decorate([Prop(),__metadata("design:type",Local)],Service.prototype,"local",void 0);__decorate([Prop(),__metadata("design:type",Object)],Service.prototype,"mixed",void 0);__decorate([Prop(),__metadata("design:type",Date)],Service.prototype,"date",void 0);__decorate([Prop(),__metadata("design:type",Promise)],Service.prototype,"promise",void 0);__decorate([Prop(),__metadata("design:type",Object)],Service.prototype,"untyped",void 0);__decorate([Prop(),__param(1,Prop()),__metadata("design:type",Function),__metadata("design:paramtypes",[Number,String]),__metadata("design:returntype",Promise)],Service.prototype,"load",null);__decorate([Prop(),__metadata("design:type",Function),__metadata("design:paramtypes",[]),__metadata("design:returntype",Promise)],Service.prototype,"fetch",null);__decorate([Prop(),__metadata("design:type",Number),__metadata("design:paramtypes",[])],Service.prototype,"total",null);Service=__decorate([Injectable(),__metadata("design:paramtypes",[typeof Repository==="undefined"?Object:Repository,typeof Config==="undefined"?Object:Config,Local,String])],Service);export{Service};
@@ -0,0 +1,46 @@
+import { Component, Input, Inject, HostListener, Injectable, Optional } from './core';
+import { Logger } from './logger';
+import type { Options } from './options';
+
+@Component({ selector: 'app' })
+export class AppComponent {
+  @Input() name: string;
+  @Input()
+  @Optional() count = 0;
+  @Inject(Logger) static shared: Logger;
+
+  constructor(@Inject(Logger) private logger: Logger, options: Options) {}
+
+  @HostListener('click', ['$event'])
+  onClick(event: Event, @Inject('x') @Optional() x?: string) {
+    return event;
+  }
+
+  get value(): string {
+    return this.name;
+  }
+
+  @Input() set value(value: string) {}
+
+  @Input() ['computed' + 1]() {}
+
+  method(@Inject(Logger) logger: Logger) {}
+}
+
+@Injectable()
+class Factory {
+  static create(): Factory {
+    return new Factory();
+  }
+}
+
+class Plain {
+  @Input() field = 1;
+
+  undecorated() {}
+}
+
+@Injectable()
+@Component.Named
+export default class {
+}
@@ -0,0 +1 @@
+var __decorate=(this&&this.__decorate)||function(decorators,target,key,desc){var c=arguments.length,r=c<3?target:desc===null?desc=Object.getOwnPropertyDescriptor(target,key):desc,d;if(typeof Reflect==="object"&&typeof Reflect.decorate==="function")r=Reflect.decorate(decorators,target,key,desc);else for(var i=decorators.length-1;i>=0;i--)if(d=decorators[i])r=(c<3?d(r):c>3?d(target,key,r):d(target,key))||r;return c>3&&r&&Object.defineProperty(target,key,r),r;};var __param=(this&&this.__param)||function(paramIndex,decorator){return function(target,key){decorator(target,key,paramIndex);}};import{Component,Input,Inject,HostListener,Injectable,Optional}from'./core';import{Logger}from'./logger';let AppComponent=class AppComponent{name;count=0;static shared;constructor(logger,options){this.logger=logger;}onClick(event,x){return event;}get value(){return this.name;}set value(value){}['computed'+1](){}method(logger){}};__decorate([Input()],AppComponent.prototype,"name",void 0);__decorate([Input(),Optional()],AppComponent.prototype,"count",void 0);__decorate([HostListener('click',['$event']),__param(1,Inject('x')),__param(1,Optional())],AppComponent.prototype,"onClick",null);__decorate([Input()],AppComponent.prototype,"value",null);__decorate([Input()],AppComponent.prototype,'computed'+1,null);__decorate([__param(0,Inject(Logger))],AppComponent.prototype,"method",null);__decorate([Inject(Logger)],AppComponent,"shared",void 0);AppComponent=__decorate([Component({selector:'app'}),__param(0,Inject(Logger))],AppComponent);export{AppComponent};var Factory_1;let Factory=Factory_1=class Factory{static create(){return new Factory_1();}};Factory=Factory_1=__decorate([Injectable()],Factory);class Plain{field=1;undecorated(){}}__decorate([Input()],Plain.prototype,"field",void 0);let default_1=class{};default_1=__decorate([Injectable(),Component.Named],default_1);export default default_1;
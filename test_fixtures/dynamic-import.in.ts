@@ -0,0 +1,14 @@
+// @module: commonjs
+type Lazy = typeof import('./lazy');
+
+export async function load(name: string): Promise<Lazy> {
+  const lazy = await import('./lazy');
+  const plugin = await import(`./plugins/${name}`);
+  return import('./lazy').then((module) => module.default ?? plugin);
+}
+
+const url: string = import.meta.url;
+const directory = import.meta.dirname;
+const file = import.meta.filename;
+const lazyUrl = import.meta.resolve('./lazy');
+const meta = import.meta;
@@ -0,0 +1 @@
+"use strict";var __createBinding=(this&&this.__createBinding)||(Object.create?(function(o,m,k,k2){if(k2===undefined)k2=k;var desc=Object.getOwnPropertyDescriptor(m,k);if(!desc||("get"in desc?!m.__esModule:desc.writable||desc.configurable)){desc={enumerable:true,get:function(){return m[k];}};}Object.defineProperty(o,k2,desc);}):(function(o,m,k,k2){if(k2===undefined)k2=k;o[k2]=m[k];}));var __setModuleDefault=(this&&this.__setModuleDefault)||(Object.create?(function(o,v){Object.defineProperty(o,"default",{enumerable:true,value:v});}):function(o,v){o["default"]=v;});var __importStar=(this&&this.__importStar)||function(mod){if(mod&&mod.__esModule)return mod;var result={};if(mod!=null)for(var k in mod)if(k!=="default"&&Object.prototype.hasOwnProperty.call(mod,k))__createBinding(result,mod,k);__setModuleDefault(result,mod);return result;};Object.defineProperty(exports,"__esModule",{value:true});Object.defineProperty(exports,"load",{enumerable:true,get:function(){return load;}});async function load(name){const lazy=await Promise.resolve().then(()=>__importStar(require('./lazy')));const plugin=await Promise.resolve(`./plugins/${name}`).then(s=>__importStar(require(s)));return Promise.resolve().then(()=>__importStar(require('./lazy'))).then((module)=>module.default??plugin);}const url=require("url").pathToFileURL(__filename).href;const directory=__dirname;const file=__filename;const lazyUrl=(s=>require("url").pathToFileURL(require.resolve(s)).href)('./lazy');const meta={url:require("url").pathToFileURL(__filename).href,dirname:__dirname,filename:__filename,resolve:s=>require("url").pathToFileURL(require.resolve(s)).href};
@@ -0,0 +1,35 @@
+enum Direction {
+  Up,
+  Down,
+  Left = 10,
+  Right,
+}
+
+export enum Status {
+  Active = 'active',
+  Inactive = "inactive",
+  Label = `label`,
+  Both = Active + '-' + Inactive,
+}
+
+enum Flags {
+  None = 0,
+  Read = 1 << 0,
+  Write = 1 << 1,
+  ReadWrite = Read | Write,
+  Next,
+  'with space' = -1,
+  Last,
+  Size = 'abc'.length
+}
+
+enum Direction {
+  Center = 100,
+}
+
+declare enum Ambient {
+  A,
+}
+
+const enumLike = { enum: 1 }
+console.log(Direction.Up, Direction[0], Status.Active, Flags.ReadWrite, enumLike.enum)
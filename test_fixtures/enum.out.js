@@ -0,0 +1,2 @@
+var Direction;(function(Direction){Direction[Direction["Up"]=0]="Up";Direction[Direction["Down"]=1]="Down";Direction[Direction["Left"]=10]="Left";Direction[Direction["Right"]=11]="Right";})(Direction||(Direction={}));export var Status;(function(Status){Status["Active"]='active';Status["Inactive"]="inactive";Status["Label"]=`label`;Status["Both"]=Status.Active+'-'+Status.Inactive;})(Status||(Status={}));var Flags;(function(Flags){Flags[Flags["None"]=0]="None";Flags[Flags["Read"]=1<<0]="Read";Flags[Flags["Write"]=1<<1]="Write";Flags[Flags["ReadWrite"]=Flags.Read|Flags.Write]="ReadWrite";Flags[Flags["Next"]=Flags["ReadWrite"]+1]="Next";Flags[Flags['with space']=-1]='with space';Flags[Flags["Last"]=0]="Last";Flags[Flags["Size"]='abc'.length]="Size";})(Flags||(Flags={}));(function(Direction){Direction[Direction["Center"]=100]="Center";})(Direction||(Direction={}));const enumLike={enum:1}
+console.log(Direction.Up,Direction[0],Status.Active,Flags.ReadWrite,enumLike.enum)
@@ -0,0 +1,19 @@
+declare function identity<T>(value: T): T;
+declare function useState<S>(initial: S): [S, (next: S) => void];
+declare function sql<R>(strings: TemplateStringsArray, ...values: unknown[]): R[];
+interface Foo {
+  id: number;
+}
+
+const text = identity<string>("a");
+const counts = new Map<string, number>();
+const nested = new Map<string, Array<Map<string, number>>>();
+const [foo, setFoo] = useState<Foo | null>(null);
+const rows = sql<{ id: number }>`select id from rows`;
+const tuple = identity<[string, (a: number) => void]>(["a", () => {}]);
+const set = new Set<number>;
+const instantiated = identity<number>;
+const a = 1, b = 2, c = 3, d = 4;
+const compared = a < b && c > d;
+const shifted = a >> b >>> c << d;
+const chained = identity<Foo>({ id: 1 }).id;
@@ -0,0 +1 @@
+const text=identity("a");const counts=new Map();const nested=new Map();const[foo,setFoo]=useState(null);const rows=sql`select id from rows`;const tuple=identity(["a",()=>{}]);const set=new Set;const instantiated=identity;const a=1,b=2,c=3,d=4;const compared=a<b&&c>d;const shifted=a>>b>>>c<<d;const chained=identity({id:1}).id;
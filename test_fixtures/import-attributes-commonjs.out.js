@@ -0,0 +1 @@
+"use strict";var __createBinding=(this&&this.__createBinding)||(Object.create?(function(o,m,k,k2){if(k2===undefined)k2=k;var desc=Object.getOwnPropertyDescriptor(m,k);if(!desc||("get"in desc?!m.__esModule:desc.writable||desc.configurable)){desc={enumerable:true,get:function(){return m[k];}};}Object.defineProperty(o,k2,desc);}):(function(o,m,k,k2){if(k2===undefined)k2=k;o[k2]=m[k];}));var __setModuleDefault=(this&&this.__setModuleDefault)||(Object.create?(function(o,v){Object.defineProperty(o,"default",{enumerable:true,value:v});}):function(o,v){o["default"]=v;});var __importStar=(this&&this.__importStar)||function(mod){if(mod&&mod.__esModule)return mod;var result={};if(mod!=null)for(var k in mod)if(k!=="default"&&Object.prototype.hasOwnProperty.call(mod,k))__createBinding(result,mod,k);__setModuleDefault(result,mod);return result;};var __exportStar=(this&&this.__exportStar)||function(m,exports){for(var p in m)if(p!=="default"&&!Object.prototype.hasOwnProperty.call(exports,p))__createBinding(exports,m,p);};Object.defineProperty(exports,"__esModule",{value:true});const data_json_1=require('./data.json');const config_json_1=require('./config.json');require('./theme.css');__exportStar(require('./more.json'),exports);exports.extra=__importStar(require('./extra.json'));const settings_json_1=require('./settings.json');Object.defineProperty(exports,"settings",{enumerable:true,get:function(){return settings_json_1;}});const value=data_json_1.value+config_json_1.value;const lazy=Promise.resolve('./lazy.json',{with:{type:'json'}}).then(s=>__importStar(require(s)));const all=__importStar(require('./all.json'));console.log(all.default);
@@ -0,0 +1 @@
+import data from'./data.json'with{type:'json'};import config from'./config.json'assert{type:'json'};import'./theme.css'with{type:'css'};export*from'./more.json'with{type:'json'};export*as extra from'./extra.json'with{type:'json'};export{default as settings}from'./settings.json'with{type:'json'};const value=data.value+config.value;const lazy=import('./lazy.json',{with:{type:'json'}});
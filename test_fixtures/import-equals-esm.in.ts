@@ -0,0 +1,7 @@
+import fs = require('fs');
+import type { Stats } from 'fs';
+export import join = require('path');
+
+const stats: Stats = fs.statSync('.');
+
+export = stats;
@@ -0,0 +1 @@
+import{createRequire as _createRequire}from"module";const __require=_createRequire(import.meta.url);const fs=__require('fs');export const join=__require('path');const stats=fs.statSync('.');export default stats;
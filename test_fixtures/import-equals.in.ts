@@ -0,0 +1,24 @@
+// @module: commonjs
+import fs = require('fs');
+import path = require('path');
+import type ts = require('typescript');
+import shapes = require('./shapes');
+
+namespace Geometry {
+  export namespace Shapes {
+    export class Circle {}
+  }
+  export import Circle = Shapes.Circle;
+  import Unused = Shapes.Circle;
+}
+
+import Circle = Geometry.Circle;
+import Square = shapes.Square;
+import Polygon = shapes.Polygon;
+
+const stats: fs.Stats = fs.statSync(path.join('a', 'b'));
+const circle: Polygon = new Circle();
+const square = new Square();
+square.export = circle;
+
+export = { stats, circle, square };
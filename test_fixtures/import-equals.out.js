@@ -0,0 +1 @@
+"use strict";const fs=require('fs');const path=require('path');const shapes=require('./shapes');var Geometry;(function(Geometry){let Shapes;(function(Shapes){class Circle{}Shapes.Circle=Circle;})(Shapes=Geometry.Shapes||(Geometry.Shapes={}));Geometry.Circle=Shapes.Circle;})(Geometry||(Geometry={}));var Circle=Geometry.Circle;var Square=shapes.Square;const stats=fs.statSync(path.join('a','b'));const circle=new Circle();const square=new Square();square.export=circle;module.exports={stats,circle,square};
@@ -0,0 +1,10 @@
+// @jsx: react-jsx
+// @module: commonjs
+import { Button } from './button';
+
+export const App = ({ ok, items }: { ok: boolean; items: string[] }) => (
+  <>
+    {ok ? <p style={{ margin: 0 }}>{`${items.length} items`}</p> : <span>none</span>}
+    {items.map((name) => <Button key={name} label={name} />)}
+  </>
+);
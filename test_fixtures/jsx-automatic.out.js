@@ -0,0 +1 @@
+"use strict";Object.defineProperty(exports,"__esModule",{value:true});Object.defineProperty(exports,"App",{enumerable:true,get:function(){return App;}});const jsx_runtime_1=require("react/jsx-runtime");const button_1=require('./button');const App=({ok,items})=>((0,jsx_runtime_1.jsxs)(jsx_runtime_1.Fragment,{children:[ok?(0,jsx_runtime_1.jsx)("p",{style:{margin:0},children:`${items.length} items`}):(0,jsx_runtime_1.jsx)("span",{children:"none"}),items.map((name)=>(0,jsx_runtime_1.jsx)(button_1.Button,{label:name},name))]}));
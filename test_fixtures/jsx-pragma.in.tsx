@@ -0,0 +1,11 @@
+/** @jsx h */
+/** @jsxFrag Fragment */
+import { h, Fragment } from 'preact';
+
+export const Card = () => (
+  <Fragment>
+    <label class="card" for="name">Name</label>
+  </Fragment>
+);
+
+export const Empty = () => <></>;
@@ -0,0 +1 @@
+import{h,Fragment}from'preact';export const Card=()=>(h(Fragment,null,h("label",{class:"card",for:"name"},"Name")));export const Empty=()=>h(Fragment,null);
@@ -0,0 +1,31 @@
+import React from 'react';
+import { Button } from './button';
+
+interface ListProps {
+  title: string;
+  items: { id: number; name: string }[];
+}
+
+const identity = <T,>(value: T): T => value;
+
+export function List({ title, items }: ListProps) {
+  const count: number = items.length;
+  return (
+    <div className="list" data-count={count} hidden>
+      <h1>{title} &amp; more</h1>
+      {/* Only the items */}
+      <ul>
+        {items.map((item) => (
+          <li key={item.id} {...item}>
+            {item.name as string}
+          </li>
+        ))}
+      </ul>
+      <>
+        Don't   stop
+        believing
+      </>
+      <Button.Primary onClick={() => count > 1 && alert("hi")} label='A "b"' />
+    </div>
+  );
+}
@@ -0,0 +1 @@
+import React from'react';import{Button}from'./button';const identity=(value)=>value;export function List({title,items}){const count=items.length;return(React.createElement("div",{className:"list","data-count":count,hidden:true},React.createElement("h1",null,title," & more"),React.createElement("ul",null,items.map((item)=>(React.createElement("li",{key:item.id,...item},item.name)))),React.createElement(React.Fragment,null,"Don't   stop believing"),React.createElement(Button.Primary,{onClick:()=>count>1&&alert("hi"),label:"A \"b\""})));}
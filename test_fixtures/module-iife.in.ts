@@ -0,0 +1,15 @@
+// @module: iife
+// @globalName: Counter
+// @globals: jquery:$
+import $ from 'jquery';
+import { debounce } from 'lodash-es';
+import { throttle } from 'lodash-es';
+import './polyfill';
+
+let count: number = 0;
+
+export function increment(): number {
+  return ++count;
+}
+export const render = debounce(throttle(() => $('#count').text(String(count)), 5), 10);
+export default count;
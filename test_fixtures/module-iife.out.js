@@ -0,0 +1 @@
+var Counter=(function(exports,require){"use strict";var __importDefault=(this&&this.__importDefault)||function(mod){return(mod&&mod.__esModule)?mod:{"default":mod};};Object.defineProperty(exports,"__esModule",{value:true});Object.defineProperty(exports,"increment",{enumerable:true,get:function(){return increment;}});Object.defineProperty(exports,"render",{enumerable:true,get:function(){return render;}});const jquery_1=__importDefault(require('jquery'));const lodash_es_1=require('lodash-es');const lodash_es_2=require('lodash-es');require('./polyfill');let count=0;function increment(){return++count;}const render=(0,lodash_es_1.debounce)((0,lodash_es_2.throttle)(()=>(0,jquery_1.default)('#count').text(String(count)),5),10);exports.default=count;return exports;})({},function(id){switch(id){case'jquery':return $;case'lodash-es':return lodashEs;}});
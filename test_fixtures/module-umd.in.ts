@@ -0,0 +1,8 @@
+// @module: umd
+import { format } from './format';
+import type { Options } from './options';
+
+export function render(options: Options): string {
+  return format(options);
+}
+export * from './shared';
@@ -0,0 +1,60 @@
+namespace Validation {
+  export interface StringValidator {
+    isAcceptable(s: string): boolean;
+  }
+  const lettersRegexp = /^[A-Za-z]+$/;
+  export const numberRegexp = /^[0-9]+$/, maxLength: number = 10;
+  export let count = 0;
+  export function isLetters(s: string): boolean {
+    count++;
+    return lettersRegexp.test(s) && s.length < maxLength;
+  }
+  export class LettersOnlyValidator implements StringValidator {
+    isAcceptable(s: string) {
+      return isLetters(s);
+    }
+  }
+  export enum Kind {
+    Letters,
+    Numbers,
+  }
+  export declare const injected: string;
+  export type Result = { ok: boolean; count: number };
+  export const summary = () => ({ count, numberRegexp: numberRegexp.source });
+  export const { source, flags: regexpFlags } = lettersRegexp, [first] = [source];
+  export const { global, unicode } = lettersRegexp;
+}
+
+namespace Validation {
+  export const extra = count + 1;
+  export const check = (s: string) => isLetters(s) && new LettersOnlyValidator().isAcceptable(s);
+}
+
+namespace Company.Product.Api {
+  export const version = '1.0';
+  namespace Internal {
+    export const secret = version;
+  }
+}
+
+module Legacy {
+  export function hello() {}
+}
+
+namespace Types {
+  export interface User {
+    name: string;
+  }
+  export type Id = string;
+  export namespace Nested {
+    export type Flag = boolean;
+  }
+}
+
+declare namespace Ambient {
+  const value: number;
+}
+
+declare const global: unknown;
+
+console.log(Validation.isLetters('abc'), Company.Product.Api.version, module.exports)
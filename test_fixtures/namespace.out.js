@@ -0,0 +1 @@
+var Validation;(function(Validation){const lettersRegexp=/^[A-Za-z]+$/;Validation.numberRegexp=/^[0-9]+$/,Validation.maxLength=10;Validation.count=0;function isLetters(s){Validation.count++;return lettersRegexp.test(s)&&s.length<Validation.maxLength;}Validation.isLetters=isLetters;class LettersOnlyValidator{isAcceptable(s){return isLetters(s);}}Validation.LettersOnlyValidator=LettersOnlyValidator;let Kind;(function(Kind){Kind[Kind["Letters"]=0]="Letters";Kind[Kind["Numbers"]=1]="Numbers";})(Kind=Validation.Kind||(Validation.Kind={}));Validation.summary=()=>({count:Validation.count,numberRegexp:Validation.numberRegexp.source});({source:Validation.source,flags:Validation.regexpFlags}=lettersRegexp),([Validation.first]=[Validation.source]);({global:Validation.global,unicode:Validation.unicode}=lettersRegexp);})(Validation||(Validation={}));(function(Validation){Validation.extra=Validation.count+1;Validation.check=(s)=>Validation.isLetters(s)&&new Validation.LettersOnlyValidator().isAcceptable(s);})(Validation||(Validation={}));var Company;(function(Company){let Product;(function(Product){let Api;(function(Api){Api.version='1.0';let Internal;(function(Internal){Internal.secret=Api.version;})(Internal||(Internal={}));})(Api=Product.Api||(Product.Api={}));})(Product=Company.Product||(Company.Product={}));})(Company||(Company={}));var Legacy;(function(Legacy){function hello(){}Legacy.hello=hello;})(Legacy||(Legacy={}));console.log(Validation.isLetters('abc'),Company.Product.Api.version,module.exports)
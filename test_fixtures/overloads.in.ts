@@ -0,0 +1,51 @@
+interface Foo {
+  name: string;
+}
+
+function parse(input: string): number;
+function parse(input: number): number;
+function parse(input: any) {
+  return Number(input);
+}
+export function format(value: number): string;
+export function format(value: any) {
+  return String(value);
+}
+async function load<T extends (a: string) => void>(url: string): Promise<T>;
+async function load(url: any) {
+  return url;
+}
+
+function onClick(this: HTMLElement, event: MouseEvent) {
+  return this.id + event.type;
+}
+function bound(this: Foo) {
+  return this.name;
+}
+
+class Parser {
+  parse(input: string): number;
+  parse(input: any) {
+    return Number(input);
+  }
+  handle(this: Parser, input: string) {
+    return this.parse(input);
+  }
+}
+
+const key = "computed";
+const handlers = {
+  total: Math.max(1, 2),
+  greet(this: Foo, greeting: string): string {
+    return greeting + this.name;
+  },
+  async *items<T>(this: Foo, item: T): AsyncGenerator<T> {
+    yield item;
+  },
+  [key](value: string): string {
+    return value;
+  },
+  get size(): number {
+    return 1;
+  },
+};
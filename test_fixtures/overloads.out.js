@@ -0,0 +1 @@
+function parse(input){return Number(input);}export function format(value){return String(value);}async function load(url){return url;}function onClick(event){return this.id+event.type;}function bound(){return this.name;}class Parser{parse(input){return Number(input);}handle(input){return this.parse(input);}}const key="computed";const handlers={total:Math.max(1,2),greet(greeting){return greeting+this.name;},async*items(item){yield item;},[key](value){return value;},get size(){return 1;},};
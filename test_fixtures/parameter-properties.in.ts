@@ -0,0 +1,21 @@
+class Db {}
+
+class Repository {
+  constructor(private readonly db: Db, public name = 'x') {}
+}
+
+class Service extends Repository {
+  count: number;
+  constructor(db: Db, protected readonly logger: Console, override name: string = 'service') {
+    const label = `${name}!`;
+    super(db, label);
+    this.count = 0;
+  }
+}
+
+class Lazy extends Repository {
+  constructor(readonly db: Db) {
+    super(db)
+    console.log(this.db)
+  }
+}
@@ -0,0 +1 @@
+class Db{}class Repository{constructor(db,name='x'){this.db=db;this.name=name;}}class Service extends Repository{count;constructor(db,logger,name='service'){const label=`${name}!`;super(db,label);this.logger=logger;this.name=name;this.count=0;}}class Lazy extends Repository{constructor(db){super(db);this.db=db;console.log(this.db)}}
@@ -0,0 +1,34 @@
+interface Point {
+  x: number;
+  y: number;
+}
+
+function optional(x?: number, y: number = 5, ...rest: string[]) {
+  return [x, y, rest];
+}
+function destructured({ x, y }: Point, [first, second]: [number, number] = [1, 2]) {
+  return x + y + first + second;
+}
+function nested({ x = 1, y: { z } }: { x?: number; y: { z: number } }, callback?: (event: Event) => void) {
+  return callback ?? x + z;
+}
+function defaults(a: number = Math.random() > 0.5 ? 1 : 2, b = { c: 1 }, d: string[] = []) {
+  return a + b.c + d.length;
+}
+
+const arrow = (x?: number, { y }: Partial<Point> = {}, ...args: any[]): void => {};
+const single = async ({ name }: { name: string }) => name;
+const generic = async <T,>(value: T, fallback?: T) => value ?? fallback;
+
+class Shape {
+  constructor(private readonly width?: number, public height: number = 2, ...sides: number[]) {}
+  scale(factor?: number, { x }: Partial<Point> = {}, ...extra: number[]) {
+    return factor;
+  }
+}
+
+const methods = {
+  move(dx?: number, ...rest: number[]) {
+    return dx;
+  },
+};
@@ -0,0 +1 @@
+function optional(x,y=5,...rest){return[x,y,rest];}function destructured({x,y},[first,second]=[1,2]){return x+y+first+second;}function nested({x=1,y:{z}},callback){return callback??x+z;}function defaults(a=Math.random()>0.5?1:2,b={c:1},d=[]){return a+b.c+d.length;}const arrow=(x,{y}={},...args)=>{};const single=async({name})=>name;const generic=async(value,fallback)=>value??fallback;class Shape{constructor(width,height=2,...sides){this.width=width;this.height=height;}scale(factor,{x}={},...extra){return factor;}}const methods={move(dx,...rest){return dx;},};
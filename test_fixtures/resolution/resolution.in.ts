@@ -0,0 +1,4 @@
+import { Color } from '@acme/colors';
+import { Dark } from '@acme/colors/shades/dark';
+
+export const palette = [Color.Red, Color.Blue, Dark.Navy];
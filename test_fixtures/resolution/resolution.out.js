@@ -0,0 +1 @@
+export const palette=["red","blue","navy"];
@@ -0,0 +1,5 @@
+import { double } from './math.js';
+import { identity } from './utils';
+import { format } from 'dual';
+
+console.log(format(identity(double(21))));
@@ -0,0 +1 @@
+export const identity = <T>(value: T): T => value;
@@ -0,0 +1,44 @@
+// @experimentalDecorators: false
+import { logged, bound, track, register, tracked } from './decorators';
+
+@register
+export class Counter extends Base {
+  @tracked count: number = 0;
+  @tracked label = 'counter'
+  @track static instances = 0;
+  @logged accessor step = 1;
+
+  constructor(public name: string) {
+    super(name);
+  }
+
+  @bound increment(): void {
+    this.count += this.step;
+  }
+
+  @logged get double(): number {
+    return this.count * 2;
+  }
+
+  @logged static create(): Counter {
+    return new Counter('default');
+  }
+}
+
+class Plain {
+  accessor value = 1;
+  static accessor shared: string;
+
+  @bound method() {}
+}
+
+const Registered = @register class {
+  class = 'registered';
+  @bound describe() {
+    return this.class;
+  }
+};
+
+export default class {
+  @tracked #secret = 1;
+}
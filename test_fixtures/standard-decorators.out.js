@@ -0,0 +1,10 @@
+var __esDecorate=(this&&this.__esDecorate)||function(ctor,descriptorIn,decorators,contextIn,initializers,extraInitializers){function accept(f){if(f!==void 0&&typeof f!=="function")throw new TypeError("Function expected");return f;}var kind=contextIn.kind,key=kind==="getter"?"get":kind==="setter"?"set":"value";var target=!descriptorIn&&ctor?contextIn["static"]?ctor:ctor.prototype:null;var descriptor=descriptorIn||(target?Object.getOwnPropertyDescriptor(target,contextIn.name):{});var _,done=false;for(var i=decorators.length-1;i>=0;i--){var context={};for(var p in contextIn)context[p]=p==="access"?{}:contextIn[p];for(var p in contextIn.access)context.access[p]=contextIn.access[p];context.addInitializer=function(f){if(done)throw new TypeError("Cannot add initializers after decoration has completed");extraInitializers.push(accept(f||null));};var result=(0,decorators[i])(kind==="accessor"?{get:descriptor.get,set:descriptor.set}:descriptor[key],context);if(kind==="accessor"){if(result===void 0)continue;if(result===null||typeof result!=="object")throw new TypeError("Object expected");if(_=accept(result.get))descriptor.get=_;if(_=accept(result.set))descriptor.set=_;if(_=accept(result.init))initializers.unshift(_);}else if(_=accept(result)){if(kind==="field")initializers.unshift(_);else descriptor[key]=_;}}if(target)Object.defineProperty(target,contextIn.name,descriptor);done=true;};var __runInitializers=(this&&this.__runInitializers)||function(thisArg,initializers,value){var useValue=arguments.length>2;for(var i=0;i<initializers.length;i++){value=useValue?initializers[i].call(thisArg,value):initializers[i].call(thisArg);}return useValue?value:void 0;};import{logged,bound,track,register,tracked}from'./decorators';let Counter=(()=>{let _classDecorators=[register];let _classDescriptor;let _classExtraInitializers=[];let _classThis;let _staticExtraInitializers=[];let _instanceExtraInitializers=[];let _count_decorators;let _count_initializers=[];let _count_extraInitializers=[];let _label_decorators;let _label_initializers=[];let _label_extraInitializers=[];let _static_instances_decorators;let _static_instances_initializers=[];let _static_instances_extraInitializers=[];let _step_decorators;let _step_initializers=[];let _step_extraInitializers=[];let _increment_decorators;let _get_double_decorators;let _static_create_decorators;var Counter=class extends Base{static{_classThis=this;}static{const _metadata=typeof Symbol==="function"&&Symbol.metadata?Object.create(Object.getPrototypeOf(this)[Symbol.metadata]??null):void 0;_static_create_decorators=[logged];__esDecorate(this,null,_static_create_decorators,{kind:"method",name:"create",static:true,private:false,access:{has:obj=>"create" in obj,get:obj=>obj.create},metadata:_metadata},null,_staticExtraInitializers);_increment_decorators=[bound];__esDecorate(this,null,_increment_decorators,{kind:"method",name:"increment",static:false,private:false,access:{has:obj=>"increment" in obj,get:obj=>obj.increment},metadata:_metadata},null,_instanceExtraInitializers);_get_double_decorators=[logged];__esDecorate(this,null,_get_double_decorators,{kind:"getter",name:"double",static:false,private:false,access:{has:obj=>"double" in obj,get:obj=>obj.double},metadata:_metadata},null,_instanceExtraInitializers);_static_instances_decorators=[track];__esDecorate(null,null,_static_instances_decorators,{kind:"field",name:"instances",static:true,private:false,access:{has:obj=>"instances" in obj,get:obj=>obj.instances,set:(obj,value)=>{obj.instances=value;}},metadata:_metadata},_static_instances_initializers,_static_instances_extraInitializers);_count_decorators=[tracked];__esDecorate(null,null,_count_decorators,{kind:"field",name:"count",static:false,private:false,access:{has:obj=>"count" in obj,get:obj=>obj.count,set:(obj,value)=>{obj.count=value;}},metadata:_metadata},_count_initializers,_count_extraInitializers);_label_decorators=[tracked];__esDecorate(null,null,_label_decorators,{kind:"field",name:"label",static:false,private:false,access:{has:obj=>"label" in obj,get:obj=>obj.label,set:(obj,value)=>{obj.label=value;}},metadata:_metadata},_label_initializers,_label_extraInitializers);_step_decorators=[logged];__esDecorate(this,null,_step_decorators,{kind:"accessor",name:"step",static:false,private:false,access:{has:obj=>"step" in obj,get:obj=>obj.step,set:(obj,value)=>{obj.step=value;}},metadata:_metadata},_step_initializers,_step_extraInitializers);__esDecorate(null,_classDescriptor={value:_classThis},_classDecorators,{kind:"class",name:_classThis.name,metadata:_metadata},null,_classExtraInitializers);Counter=_classThis=_classDescriptor.value;if(_metadata)Object.defineProperty(_classThis,Symbol.metadata,{enumerable:true,configurable:true,writable:true,value:_metadata});__runInitializers(_classThis,_staticExtraInitializers);}
+count=(__runInitializers(this,_instanceExtraInitializers),__runInitializers(this,_count_initializers,0));label=(__runInitializers(this,_count_extraInitializers),__runInitializers(this,_label_initializers,'counter'))
+static instances=__runInitializers(_classThis,_static_instances_initializers,0);static{__runInitializers(_classThis,_static_instances_extraInitializers);}
+#step_accessor_storage=(__runInitializers(this,_label_extraInitializers),__runInitializers(this,_step_initializers,1));get step(){return this.#step_accessor_storage;}set step(value){this.#step_accessor_storage=value;}
+constructor(name){super(name);this.name=name;__runInitializers(this,_step_extraInitializers);}increment(){this.count+=this.step;}get double(){return this.count*2;}static create(){return new Counter('default');}static{__runInitializers(_classThis,_classExtraInitializers);}};return Counter=_classThis;})();export{Counter};let Plain=(()=>{let _instanceExtraInitializers=[];let _method_decorators;return class Plain{static{const _metadata=typeof Symbol==="function"&&Symbol.metadata?Object.create(null):void 0;_method_decorators=[bound];__esDecorate(this,null,_method_decorators,{kind:"method",name:"method",static:false,private:false,access:{has:obj=>"method" in obj,get:obj=>obj.method},metadata:_metadata},null,_instanceExtraInitializers);if(_metadata)Object.defineProperty(this,Symbol.metadata,{enumerable:true,configurable:true,writable:true,value:_metadata});}
+#value_accessor_storage=(__runInitializers(this,_instanceExtraInitializers),1);get value(){return this.#value_accessor_storage;}set value(value){this.#value_accessor_storage=value;}
+static#shared_accessor_storage;static get shared(){return Plain.#shared_accessor_storage;}static set shared(value){Plain.#shared_accessor_storage=value;}
+method(){}};})();const Registered=(()=>{let _classDecorators=[register];let _classDescriptor;let _classExtraInitializers=[];let _classThis;let _instanceExtraInitializers=[];let _describe_decorators;var Registered=class{static{_classThis=this;}static{const _metadata=typeof Symbol==="function"&&Symbol.metadata?Object.create(null):void 0;_describe_decorators=[bound];__esDecorate(this,null,_describe_decorators,{kind:"method",name:"describe",static:false,private:false,access:{has:obj=>"describe" in obj,get:obj=>obj.describe},metadata:_metadata},null,_instanceExtraInitializers);__esDecorate(null,_classDescriptor={value:_classThis},_classDecorators,{kind:"class",name:_classThis.name,metadata:_metadata},null,_classExtraInitializers);Registered=_classThis=_classDescriptor.value;if(_metadata)Object.defineProperty(_classThis,Symbol.metadata,{enumerable:true,configurable:true,writable:true,value:_metadata});}
+class=(__runInitializers(this,_instanceExtraInitializers),'registered');describe(){return this.class;}static{__runInitializers(_classThis,_classExtraInitializers);}};return Registered=_classThis;})();let default_1=(()=>{let _private_secret_decorators;let _private_secret_initializers=[];let _private_secret_extraInitializers=[];return class{static{const _metadata=typeof Symbol==="function"&&Symbol.metadata?Object.create(null):void 0;_private_secret_decorators=[tracked];__esDecorate(null,null,_private_secret_decorators,{kind:"field",name:"#secret",static:false,private:true,access:{has:obj=>#secret in obj,get:obj=>obj.#secret,set:(obj,value)=>{obj.#secret=value;}},metadata:_metadata},_private_secret_initializers,_private_secret_extraInitializers);if(_metadata)Object.defineProperty(this,Symbol.metadata,{enumerable:true,configurable:true,writable:true,value:_metadata});}
+#secret=__runInitializers(this,_private_secret_initializers,1);constructor(){__runInitializers(this,_private_secret_extraInitializers);}};})();export default default_1;
@@ -0,0 +1,29 @@
+// @target: es2015
+export class Counter {
+  static #created = 0;
+  static step = 2 ** 3;
+  #count = 0;
+  label = 'counter';
+
+  constructor() {
+    Counter.#created++;
+  }
+
+  #log() {
+    console.log(this.label, this.#count);
+  }
+
+  increment() {
+    this.#count += Counter.step;
+    this.#log();
+    return this.#count ** 2;
+  }
+
+  reset(other: Counter) {
+    [this.#count, other.#count] = [0, this.#count];
+  }
+
+  static isCounter(value: object) {
+    return #count in value;
+  }
+}
@@ -0,0 +1 @@
+var __classPrivateFieldGet=(this&&this.__classPrivateFieldGet)||function(receiver,state,kind,f){if(kind==="a"&&!f)throw new TypeError("Private accessor was defined without a getter");if(typeof state==="function"?receiver!==state||!f:!state.has(receiver))throw new TypeError("Cannot read private member from an object whose class did not declare it");return kind==="m"?f:kind==="a"?f.call(receiver):f?f.value:state.get(receiver);};var __classPrivateFieldSet=(this&&this.__classPrivateFieldSet)||function(receiver,state,value,kind,f){if(kind==="m")throw new TypeError("Private method is not writable");if(kind==="a"&&!f)throw new TypeError("Private accessor was defined without a setter");if(typeof state==="function"?receiver!==state||!f:!state.has(receiver))throw new TypeError("Cannot write private member to an object whose class did not declare it");return(kind==="a"?f.call(receiver,value):f?f.value=value:state.set(receiver,value)),value;};var __classPrivateFieldIn=(this&&this.__classPrivateFieldIn)||function(state,receiver){if(receiver===null||(typeof receiver!=="object"&&typeof receiver!=="function"))throw new TypeError("Cannot use 'in' operator on non-object");return typeof state==="function"?receiver===state:state.has(receiver);};var _Counter_instances,_Counter_count,_Counter_created;export class Counter{constructor(){var _a;_Counter_instances.add(this);_Counter_count.set(this,0);this.label='counter';__classPrivateFieldSet(Counter,Counter,(_a=__classPrivateFieldGet(Counter,Counter,"f",_Counter_created),++_a),"f",_Counter_created);}increment(){__classPrivateFieldSet(this,_Counter_count,__classPrivateFieldGet(this,_Counter_count,"f")+Counter.step,"f");__classPrivateFieldGet(this,_Counter_instances,"m",_Counter_log).call(this);return Math.pow(__classPrivateFieldGet(this,_Counter_count,"f"),2);}reset(other){var _b;[(_b=this,{set value(value_1){__classPrivateFieldSet(_b,_Counter_count,value_1,"f");}}).value,({set value(value_2){__classPrivateFieldSet(other,_Counter_count,value_2,"f");}}).value]=[0,__classPrivateFieldGet(this,_Counter_count,"f")];}static isCounter(value){return __classPrivateFieldIn(_Counter_count,value);}}_Counter_count=new WeakMap();_Counter_instances=new WeakSet();_Counter_created={value:0};Counter.step=Math.pow(2,3);function _Counter_log(){console.log(this.label,__classPrivateFieldGet(this,_Counter_count,"f"));}
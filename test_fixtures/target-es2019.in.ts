@@ -0,0 +1,14 @@
+// @target: es2019
+interface Config {
+  retries?: number;
+  server?: { host?: string; start?(): void };
+}
+
+export function connect(config?: Config, fallback = 1_000) {
+  const retries = config?.retries ?? 3;
+  config?.server?.start?.();
+  let timeout = config?.server?.host ? 10_000 : fallback;
+  timeout ||= 500;
+  config.retries ??= retries;
+  return `${config?.server?.host ?? 'localhost'}:${timeout}`;
+}
@@ -0,0 +1 @@
+export function connect(config,fallback=1000){var _a,_b,_c,_d,_e,_f,_g;const retries=((_a=(config===null||config===void 0?void 0:config.retries))!==null&&_a!==void 0?_a:3);((_c=((_b=(config===null||config===void 0?void 0:config.server))===null||_b===void 0?void 0:_b.start))===null||_c===void 0?void 0:_c.call(_b));let timeout=((_d=(config===null||config===void 0?void 0:config.server))===null||_d===void 0?void 0:_d.host)?10000:fallback;timeout||(timeout=500);((_e=config.retries)!==null&&_e!==void 0?_e:(config.retries=retries));return`${((_g=((_f=(config===null||config===void 0?void 0:config.server))===null||_f===void 0?void 0:_f.host))!==null&&_g!==void 0?_g:'localhost')}:${timeout}`;}
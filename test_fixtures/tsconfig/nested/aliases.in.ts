@@ -0,0 +1,10 @@
+import { Direction } from '@app/directions.js';
+export { Direction as Heading } from '@app/directions';
+import type { Shape } from '@app/shapes';
+
+export const load = () => import('@app/directions');
+export const missing = () => import('@app/missing');
+
+export function turn(shape: Shape) {
+  return [shape, Direction.Down];
+}
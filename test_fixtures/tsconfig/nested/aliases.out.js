@@ -0,0 +1 @@
+import'../directions.js';export{Direction as Heading}from'../directions';export const load=()=>import('../directions');export const missing=()=>import('@app/missing');export function turn(shape){return[shape,"DOWN"];}
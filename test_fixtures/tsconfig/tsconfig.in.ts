@@ -0,0 +1,14 @@
+import { Direction } from '@app/directions';
+import { type Shape } from './shapes';
+import { unused } from './unused';
+
+export class Turtle {
+  heading = Direction.Up;
+  shape?: Shape;
+
+  constructor(private readonly speed: number) {}
+
+  turn() {
+    this.heading = Direction.Down;
+  }
+}
@@ -0,0 +1 @@
+import'./directions';import'./shapes';import{unused}from'./unused';export class Turtle{constructor(speed){this.speed=speed;this.heading="UP";}turn(){this.heading="DOWN";}}
@@ -0,0 +1,39 @@
+import type { Config } from './config';
+import type Default from './default';
+import type * as Everything from './everything';
+import { type Options, createClient } from './client';
+import { type OnlyType } from './only-type';
+import Logger, { LogLevel, format } from './logger';
+import * as helpers from './helpers';
+import { Shape, draw } from './shapes';
+import { reexported } from './reexported';
+import './side-effect';
+import type from './named-type';
+
+export type { Config };
+export type { Remote } from './remote';
+export type * from './all-types';
+export { type Options, createClient };
+export interface Settings {
+  level: LogLevel;
+}
+export type Id = string;
+export default interface Props {
+  id: Id;
+}
+export declare const ambient: number;
+export namespace Types {
+  export type Flag = boolean;
+}
+export const settings: Settings = { level: 'info' };
+export { Settings, settings as config, reexported };
+export { helper } from './helper';
+
+interface Local {
+  shape: Shape;
+}
+export { Local };
+
+const client: Options = createClient(type);
+draw(format(client));
+draw({ Shape: 'circle', level: (settings as any).LogLevel });
@@ -0,0 +1 @@
+import{createClient}from'./client';import{format}from'./logger';import{draw}from'./shapes';import{reexported}from'./reexported';import'./side-effect';import type from'./named-type';export{createClient};export const settings={level:'info'};export{settings as config,reexported};export{helper}from'./helper';const client=createClient(type);draw(format(client));draw({Shape:'circle',level:(settings).LogLevel});
@@ -0,0 +1,15 @@
+const point: { x: number; y: number } = { x: 1, y: 2 };
+let maybe: string[] | null = null;
+let tuple: [number, string] = [1, 'a'];
+const cb: (e: Event) => void = (e) => console.log(e);
+const nested: Map<string, Array<number>> = new Map();
+let a: number = 1,
+  b: string = 'b';
+const { x, y: renamed }: { x: number; y: number } = point;
+const [first, second]: [number, string] = tuple;
+let definite!: string;
+let noSemicolon: number
+let union:
+  | 'a'
+  | 'b' = 'a'
+const literal = 'done'
@@ -0,0 +1,3 @@
+const point={x:1,y:2};let maybe=null;let tuple=[1,'a'];const cb=(e)=>console.log(e);const nested=new Map();let a=1,b='b';const{x,y:renamed}=point;const[first,second]=tuple;let definite;let noSemicolon
+let union='a'
+const literal='done'